@@ -11,6 +11,8 @@ use tokio::fs::File;
 use tokio::io::{AsyncBufReadExt, AsyncSeekExt, BufReader};
 
 pub mod bpf;
+pub mod shutdown;
+pub mod status;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const NAME: &str = env!("CARGO_PKG_NAME");
@@ -84,6 +86,16 @@ pub fn hardware_threads() -> Result<u64, ()> {
         .map(|i| i + 1)
 }
 
+/// helper function to get the hostname of this machine
+pub fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    if unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) } != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+    std::str::from_utf8(&buf[..len]).ok().map(|h| h.to_string())
+}
+
 /// helper function to create a nested map from files with the form of
 /// pkey1 lkey1 lkey2 ... lkeyN
 /// pkey1 value1 value2 ... valueN
@@ -141,6 +153,10 @@ impl KernelInfo {
         })
     }
 
+    pub fn release(&self) -> &str {
+        self.release.trim()
+    }
+
     pub fn release_major(&self) -> Result<u32, std::io::Error> {
         let parts: Vec<&str> = self.release.split('.').collect();
         if let Some(s) = parts.get(0) {
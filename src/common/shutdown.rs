@@ -0,0 +1,134 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+
+/// Signals a group of tasks, such as the samplers or the push exporters, to
+/// stop by a deadline and waits for them to finish. Each task holds a `Guard`
+/// from `register` until it has finished, and both threads and async tasks may
+/// wait for the signal.
+pub struct Shutdown {
+    state: Mutex<State>,
+    condvar: Condvar,
+    sender: watch::Sender<bool>,
+    receiver: watch::Receiver<bool>,
+}
+
+struct State {
+    triggered: bool,
+    deadline: Option<Instant>,
+    running: usize,
+}
+
+/// Held by a task until it has finished
+pub struct Guard {
+    shutdown: Arc<Shutdown>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        let (sender, receiver) = watch::channel(false);
+        Self {
+            state: Mutex::new(State {
+                triggered: false,
+                deadline: None,
+                running: 0,
+            }),
+            condvar: Condvar::new(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signal the tasks to stop, which should finish by the deadline
+    pub fn trigger(&self, deadline: Instant) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.triggered = true;
+            state.deadline = Some(deadline);
+        }
+        self.condvar.notify_all();
+        let _ = self.sender.send(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.state.lock().unwrap().triggered
+    }
+
+    /// The time left until the deadline, or `None` if the signal has not been
+    /// triggered
+    pub fn remaining(&self) -> Option<Duration> {
+        self.state
+            .lock()
+            .unwrap()
+            .deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Limit a timeout to the time left until the deadline, if the signal has
+    /// been triggered
+    pub fn limit(&self, timeout: Duration) -> Duration {
+        match self.remaining() {
+            Some(remaining) => timeout.min(remaining),
+            None => timeout,
+        }
+    }
+
+    /// Record that a task is running until the returned guard is dropped
+    pub fn register(self: &Arc<Self>) -> Guard {
+        self.state.lock().unwrap().running += 1;
+        Guard {
+            shutdown: self.clone(),
+        }
+    }
+
+    /// Sleep for up to the timeout, returning early if the signal is
+    /// triggered. Returns true if the signal has been triggered.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let state = self.state.lock().unwrap();
+        let (state, _) = self
+            .condvar
+            .wait_timeout_while(state, timeout, |state| !state.triggered)
+            .unwrap();
+        state.triggered
+    }
+
+    /// Wait until the signal is triggered
+    pub async fn wait(&self) {
+        let mut receiver = self.receiver.clone();
+        while !*receiver.borrow() {
+            if receiver.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Wait until every registered task has finished or the deadline has
+    /// passed. Returns false if tasks were still running at the deadline.
+    pub fn finished(&self, deadline: Instant) -> bool {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let state = self.state.lock().unwrap();
+        let (state, _) = self
+            .condvar
+            .wait_timeout_while(state, timeout, |state| state.running > 0)
+            .unwrap();
+        state.running == 0
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        self.shutdown.state.lock().unwrap().running -= 1;
+        self.shutdown.condvar.notify_all();
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+/// A sampler is considered stalled if it has not produced a sample within
+/// this many of its intervals
+const STALE_INTERVALS: u32 = 3;
+
+/// Tracks the progress of the enabled samplers so that the exposition layer
+/// can report on readiness and which BPF probes were attached.
+#[derive(Default)]
+pub struct Status {
+    samplers: DashMap<&'static str, SamplerStatus>,
+}
+
+#[derive(Default)]
+struct SamplerStatus {
+    interval: Option<Duration>,
+    last_sample: Option<Instant>,
+    probes: Vec<String>,
+}
+
+impl Status {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that a sampler is enabled
+    pub fn register(&self, sampler: &'static str) {
+        self.samplers.entry(sampler).or_default();
+    }
+
+    /// Record that a sampler has been initialized and will sample at the
+    /// provided interval
+    pub fn initialized(&self, sampler: &'static str, interval: Duration) {
+        self.samplers.entry(sampler).or_default().interval = Some(interval);
+    }
+
+    /// Record that a sampler has completed a sample
+    pub fn sampled(&self, sampler: &'static str) {
+        self.samplers.entry(sampler).or_default().last_sample = Some(Instant::now());
+    }
+
+    /// Record that a BPF probe was attached for a sampler
+    #[cfg_attr(not(feature = "bpf"), allow(dead_code))]
+    pub fn attached(&self, sampler: &'static str, probe: &str) {
+        self.samplers
+            .entry(sampler)
+            .or_default()
+            .probes
+            .push(probe.to_string());
+    }
+
+    /// Returns the names of the enabled samplers, in sorted order
+    pub fn samplers(&self) -> Vec<&'static str> {
+        let mut samplers: Vec<&'static str> = self.samplers.iter().map(|s| *s.key()).collect();
+        samplers.sort_unstable();
+        samplers
+    }
+
+    /// Returns the probes which were attached for a sampler
+    pub fn probes(&self, sampler: &str) -> Vec<String> {
+        self.samplers
+            .get(sampler)
+            .map(|s| s.probes.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the names of enabled samplers which are not ready, in sorted
+    /// order. A sampler is ready once it has been initialized and has
+    /// produced a sample recently.
+    pub fn pending(&self) -> Vec<&'static str> {
+        let now = Instant::now();
+        let mut pending: Vec<&'static str> = self
+            .samplers
+            .iter()
+            .filter(|s| match (s.interval, s.last_sample) {
+                (Some(interval), Some(last_sample)) => {
+                    now.duration_since(last_sample) > interval * STALE_INTERVALS
+                }
+                _ => true,
+            })
+            .map(|s| *s.key())
+            .collect();
+        pending.sort_unstable();
+        pending
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::path::{Path, PathBuf};
+
+use crate::config::*;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct File {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_interval")]
+    interval: usize,
+    path: Option<PathBuf>,
+    #[serde(default)]
+    format: FileFormat,
+    #[serde(default = "default_max_size")]
+    max_size: u64,
+    #[serde(default)]
+    max_age: u64,
+    #[serde(default = "default_retain")]
+    retain: usize,
+    #[serde(default)]
+    compress: bool,
+}
+
+/// The format of each record written to the file
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileFormat {
+    /// One JSON object per line with the timestamp, hostname, and metrics
+    #[default]
+    Json,
+    /// One `timestamp,name,labels,value` row per metric
+    Csv,
+}
+
+impl Default for File {
+    fn default() -> File {
+        File {
+            enabled: false,
+            interval: default_interval(),
+            path: None,
+            format: Default::default(),
+            max_size: default_max_size(),
+            max_age: 0,
+            retain: default_retain(),
+            compress: false,
+        }
+    }
+}
+
+fn default_interval() -> usize {
+    10_000
+}
+
+fn default_max_size() -> u64 {
+    100 * 1024 * 1024
+}
+
+fn default_retain() -> usize {
+    10
+}
+
+impl File {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// interval in ms between snapshots
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// file which snapshots are appended to
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn format(&self) -> FileFormat {
+        self.format
+    }
+
+    /// size in bytes at which the file is rotated, or 0 to never rotate by
+    /// size
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// time in seconds after which the file is rotated, or 0 to never rotate
+    /// by age
+    pub fn max_age(&self) -> u64 {
+        self.max_age
+    }
+
+    /// number of rotated files to keep
+    pub fn retain(&self) -> usize {
+        self.retain
+    }
+
+    /// whether to gzip rotated files
+    pub fn compress(&self) -> bool {
+        self.compress
+    }
+}
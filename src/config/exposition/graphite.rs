@@ -0,0 +1,90 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::config::*;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Graphite {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_interval")]
+    interval: usize,
+    address: Option<String>,
+    #[serde(default)]
+    prefix: String,
+    #[serde(default = "default_timeout")]
+    timeout: usize,
+    #[serde(default = "default_buffer")]
+    buffer: usize,
+    #[serde(default = "default_max_backoff")]
+    max_backoff: usize,
+}
+
+impl Default for Graphite {
+    fn default() -> Graphite {
+        Graphite {
+            enabled: false,
+            interval: default_interval(),
+            address: None,
+            prefix: String::new(),
+            timeout: default_timeout(),
+            buffer: default_buffer(),
+            max_backoff: default_max_backoff(),
+        }
+    }
+}
+
+fn default_interval() -> usize {
+    10_000
+}
+
+fn default_timeout() -> usize {
+    5_000
+}
+
+fn default_buffer() -> usize {
+    100_000
+}
+
+fn default_max_backoff() -> usize {
+    60_000
+}
+
+impl Graphite {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// interval in ms between pushes
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// carbon plaintext endpoint, eg: `carbon:2003`
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    /// prefix for all metric paths, where `{hostname}` is replaced with the
+    /// hostname
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// timeout in ms for connecting and writing
+    pub fn timeout(&self) -> usize {
+        self.timeout
+    }
+
+    /// maximum number of lines to buffer while the endpoint is unavailable
+    pub fn buffer(&self) -> usize {
+        self.buffer
+    }
+
+    /// maximum delay in ms between reconnection attempts
+    pub fn max_backoff(&self) -> usize {
+        self.max_backoff
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::BTreeMap;
+
+use crate::config::*;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Influx {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_interval")]
+    interval: usize,
+    address: Option<String>,
+    token: Option<String>,
+    #[serde(default = "default_host_tag")]
+    host_tag: bool,
+    #[serde(default)]
+    tags: BTreeMap<String, String>,
+    #[serde(default = "default_timeout")]
+    timeout: usize,
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+    #[serde(default = "default_retries")]
+    retries: usize,
+    #[serde(default = "default_buffer")]
+    buffer: usize,
+    #[serde(default = "default_max_packet_size")]
+    max_packet_size: usize,
+}
+
+impl Default for Influx {
+    fn default() -> Influx {
+        Influx {
+            enabled: false,
+            interval: default_interval(),
+            address: None,
+            token: None,
+            host_tag: default_host_tag(),
+            tags: BTreeMap::new(),
+            timeout: default_timeout(),
+            batch_size: default_batch_size(),
+            retries: default_retries(),
+            buffer: default_buffer(),
+            max_packet_size: default_max_packet_size(),
+        }
+    }
+}
+
+fn default_interval() -> usize {
+    10_000
+}
+
+fn default_host_tag() -> bool {
+    true
+}
+
+fn default_timeout() -> usize {
+    5_000
+}
+
+fn default_batch_size() -> usize {
+    5_000
+}
+
+fn default_retries() -> usize {
+    3
+}
+
+fn default_buffer() -> usize {
+    100_000
+}
+
+fn default_max_packet_size() -> usize {
+    1432
+}
+
+impl Influx {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// interval in ms between pushes
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// endpoint to write to, either `udp://host:port` or the url of the http
+    /// write endpoint, eg: `http://localhost:8086/write?db=rezolus`
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    /// token sent in the `Authorization` header of http writes
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// whether to tag all measurements with the hostname
+    pub fn host_tag(&self) -> bool {
+        self.host_tag
+    }
+
+    /// additional tags for all measurements
+    pub fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
+
+    /// timeout in ms for http writes
+    pub fn timeout(&self) -> usize {
+        self.timeout
+    }
+
+    /// maximum number of lines in each write
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// number of times a failed write is retried before it is deferred to
+    /// the next push
+    pub fn retries(&self) -> usize {
+        self.retries
+    }
+
+    /// maximum number of lines to buffer while the endpoint is unavailable
+    pub fn buffer(&self) -> usize {
+        self.buffer
+    }
+
+    /// maximum size in bytes of each datagram for udp writes
+    pub fn max_packet_size(&self) -> usize {
+        self.max_packet_size
+    }
+}
@@ -15,6 +15,57 @@ pub struct Kafka {
     interval: AtomicUsize,
     hosts: Vec<String>,
     topic: Option<String>,
+    #[serde(default)]
+    format: KafkaFormat,
+    #[serde(default)]
+    key_by_host: bool,
+    #[serde(default)]
+    compression: KafkaCompression,
+    #[serde(default)]
+    acks: KafkaAcks,
+    #[serde(default = "default_ack_timeout")]
+    ack_timeout: usize,
+    #[serde(default = "default_buffer")]
+    buffer: usize,
+    #[serde(default = "default_max_backoff")]
+    max_backoff: usize,
+}
+
+/// The encoding of each message
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KafkaFormat {
+    /// A flat JSON object of metric names to values
+    #[default]
+    Flat,
+    /// A JSON object with the hostname and timestamp, and the flat metrics
+    /// object under `metrics`
+    Json,
+    /// InfluxDB line protocol, tagged with the hostname
+    LineProtocol,
+}
+
+/// The codec used to compress message sets
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KafkaCompression {
+    #[default]
+    None,
+    Gzip,
+    Snappy,
+}
+
+/// The acknowledgement required from the brokers for a send to succeed
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KafkaAcks {
+    /// Do not wait for any acknowledgement
+    None,
+    /// Wait for the partition leader
+    #[default]
+    One,
+    /// Wait for all in-sync replicas
+    All,
 }
 
 impl Default for Kafka {
@@ -24,6 +75,13 @@ impl Default for Kafka {
             interval: default_interval(),
             hosts: Default::default(),
             topic: Default::default(),
+            format: Default::default(),
+            key_by_host: false,
+            compression: Default::default(),
+            acks: Default::default(),
+            ack_timeout: default_ack_timeout(),
+            buffer: default_buffer(),
+            max_backoff: default_max_backoff(),
         }
     }
 }
@@ -36,6 +94,18 @@ fn default_interval() -> AtomicUsize {
     AtomicUsize::new(500)
 }
 
+fn default_ack_timeout() -> usize {
+    30_000
+}
+
+fn default_buffer() -> usize {
+    1_000
+}
+
+fn default_max_backoff() -> usize {
+    60_000
+}
+
 #[cfg(feature = "push_kafka")]
 impl Kafka {
     pub fn enabled(&self) -> bool {
@@ -53,4 +123,37 @@ impl Kafka {
     pub fn topic(&self) -> Option<String> {
         self.topic.clone()
     }
+
+    pub fn format(&self) -> KafkaFormat {
+        self.format
+    }
+
+    /// whether to key messages by hostname, so that the messages from each
+    /// host are sent to the same partition
+    pub fn key_by_host(&self) -> bool {
+        self.key_by_host
+    }
+
+    pub fn compression(&self) -> KafkaCompression {
+        self.compression
+    }
+
+    pub fn acks(&self) -> KafkaAcks {
+        self.acks
+    }
+
+    /// time in ms the brokers may wait for the required acknowledgements
+    pub fn ack_timeout(&self) -> usize {
+        self.ack_timeout
+    }
+
+    /// maximum number of messages to buffer while the brokers are unavailable
+    pub fn buffer(&self) -> usize {
+        self.buffer
+    }
+
+    /// maximum time in ms between attempts to reconnect
+    pub fn max_backoff(&self) -> usize {
+        self.max_backoff
+    }
 }
@@ -4,19 +4,71 @@
 
 use serde_derive::*;
 
+mod file;
+mod graphite;
+mod influx;
 mod kafka;
+mod otlp;
+mod remote_write;
+mod statsd;
 
+pub use self::file::FileFormat;
+use self::file::*;
+use self::graphite::*;
+use self::influx::*;
 use self::kafka::*;
+#[cfg(feature = "push_kafka")]
+pub use self::kafka::{KafkaAcks, KafkaCompression, KafkaFormat};
+pub use self::otlp::OtlpProtocol;
+use self::otlp::*;
+use self::remote_write::*;
+use self::statsd::*;
 
 #[derive(Debug, Default, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Exposition {
+    #[serde(default)]
+    file: File,
+    #[serde(default)]
+    graphite: Graphite,
+    #[serde(default)]
+    influx: Influx,
     #[serde(default)]
     #[allow(dead_code)]
     kafka: Kafka,
+    #[serde(default)]
+    otlp: Otlp,
+    #[serde(default)]
+    remote_write: RemoteWrite,
+    #[serde(default)]
+    statsd: StatsD,
 }
 
 impl Exposition {
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    pub fn graphite(&self) -> &Graphite {
+        &self.graphite
+    }
+
+    pub fn influx(&self) -> &Influx {
+        &self.influx
+    }
+
+    pub fn otlp(&self) -> &Otlp {
+        &self.otlp
+    }
+
+    pub fn remote_write(&self) -> &RemoteWrite {
+        &self.remote_write
+    }
+
+    pub fn statsd(&self) -> &StatsD {
+        &self.statsd
+    }
+
     #[cfg(feature = "push_kafka")]
     pub fn kafka(&self) -> &Kafka {
         &self.kafka
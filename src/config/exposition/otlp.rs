@@ -0,0 +1,123 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::BTreeMap;
+
+use crate::config::*;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Otlp {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_interval")]
+    interval: usize,
+    #[serde(default)]
+    protocol: OtlpProtocol,
+    endpoint: Option<String>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default = "default_service_name")]
+    service_name: String,
+    #[serde(default)]
+    resource_attributes: BTreeMap<String, String>,
+    #[serde(default = "default_timeout")]
+    timeout: usize,
+    #[serde(default = "default_retries")]
+    retries: usize,
+}
+
+/// The transport used to export metrics
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OtlpProtocol {
+    /// Protobuf encoded requests over HTTP
+    #[default]
+    Http,
+    /// The gRPC metrics service, over HTTP/2 without TLS
+    Grpc,
+}
+
+impl Default for Otlp {
+    fn default() -> Otlp {
+        Otlp {
+            enabled: false,
+            interval: default_interval(),
+            protocol: Default::default(),
+            endpoint: None,
+            headers: BTreeMap::new(),
+            service_name: default_service_name(),
+            resource_attributes: BTreeMap::new(),
+            timeout: default_timeout(),
+            retries: default_retries(),
+        }
+    }
+}
+
+fn default_interval() -> usize {
+    10_000
+}
+
+fn default_service_name() -> String {
+    "rezolus".to_string()
+}
+
+fn default_timeout() -> usize {
+    5_000
+}
+
+fn default_retries() -> usize {
+    3
+}
+
+impl Otlp {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// interval in ms between pushes
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    pub fn protocol(&self) -> OtlpProtocol {
+        self.protocol
+    }
+
+    /// endpoint to export to, defaulting to the standard collector port on
+    /// localhost for the protocol
+    pub fn endpoint(&self) -> &str {
+        match (&self.endpoint, self.protocol) {
+            (Some(endpoint), _) => endpoint,
+            (None, OtlpProtocol::Http) => "http://127.0.0.1:4318/v1/metrics",
+            (None, OtlpProtocol::Grpc) => "http://127.0.0.1:4317",
+        }
+    }
+
+    /// additional headers for each request, eg: for authentication
+    pub fn headers(&self) -> &BTreeMap<String, String> {
+        &self.headers
+    }
+
+    /// value of the `service.name` resource attribute
+    pub fn service_name(&self) -> &str {
+        &self.service_name
+    }
+
+    /// additional resource attributes, which take precedence over the
+    /// defaults
+    pub fn resource_attributes(&self) -> &BTreeMap<String, String> {
+        &self.resource_attributes
+    }
+
+    /// timeout in ms for each request
+    pub fn timeout(&self) -> usize {
+        self.timeout
+    }
+
+    /// number of times a failed export is retried
+    pub fn retries(&self) -> usize {
+        self.retries
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::BTreeMap;
+
+use crate::config::*;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteWrite {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_interval")]
+    interval: usize,
+    url: Option<String>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    external_labels: BTreeMap<String, String>,
+    #[serde(default = "default_timeout")]
+    timeout: usize,
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+    #[serde(default = "default_retries")]
+    retries: usize,
+    #[serde(default = "default_buffer")]
+    buffer: usize,
+}
+
+impl Default for RemoteWrite {
+    fn default() -> RemoteWrite {
+        RemoteWrite {
+            enabled: false,
+            interval: default_interval(),
+            url: None,
+            headers: BTreeMap::new(),
+            external_labels: BTreeMap::new(),
+            timeout: default_timeout(),
+            batch_size: default_batch_size(),
+            retries: default_retries(),
+            buffer: default_buffer(),
+        }
+    }
+}
+
+fn default_interval() -> usize {
+    10_000
+}
+
+fn default_timeout() -> usize {
+    5_000
+}
+
+fn default_batch_size() -> usize {
+    5_000
+}
+
+fn default_retries() -> usize {
+    3
+}
+
+fn default_buffer() -> usize {
+    100_000
+}
+
+impl RemoteWrite {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// interval in ms between pushes
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// url of the remote write endpoint, eg:
+    /// `http://prometheus:9090/api/v1/write`
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// additional headers for each request, eg: for authentication
+    pub fn headers(&self) -> &BTreeMap<String, String> {
+        &self.headers
+    }
+
+    /// labels added to every series, unless the series has a label with the
+    /// same name
+    pub fn external_labels(&self) -> &BTreeMap<String, String> {
+        &self.external_labels
+    }
+
+    /// timeout in ms for each request
+    pub fn timeout(&self) -> usize {
+        self.timeout
+    }
+
+    /// maximum number of series in each request
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// number of times a failed request is retried before it is deferred to
+    /// the next push
+    pub fn retries(&self) -> usize {
+        self.retries
+    }
+
+    /// maximum number of samples to buffer while the endpoint is unavailable
+    pub fn buffer(&self) -> usize {
+        self.buffer
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::config::*;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatsD {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_interval")]
+    interval: usize,
+    #[serde(default = "default_address")]
+    address: String,
+    #[serde(default)]
+    prefix: String,
+    #[serde(default)]
+    dogstatsd: bool,
+    #[serde(default = "default_max_packet_size")]
+    max_packet_size: usize,
+}
+
+impl Default for StatsD {
+    fn default() -> StatsD {
+        StatsD {
+            enabled: false,
+            interval: default_interval(),
+            address: default_address(),
+            prefix: String::new(),
+            dogstatsd: false,
+            max_packet_size: default_max_packet_size(),
+        }
+    }
+}
+
+fn default_interval() -> usize {
+    10_000
+}
+
+fn default_address() -> String {
+    "127.0.0.1:8125".to_string()
+}
+
+fn default_max_packet_size() -> usize {
+    1432
+}
+
+impl StatsD {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// interval in ms between pushes
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// address of the statsd agent
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// prefix for all metric names, where `{hostname}` is replaced with the
+    /// hostname
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// whether to send labels as DogStatsD tags
+    pub fn dogstatsd(&self) -> bool {
+        self.dogstatsd
+    }
+
+    /// maximum size in bytes of each datagram
+    pub fn max_packet_size(&self) -> usize {
+        self.max_packet_size
+    }
+}
@@ -9,7 +9,16 @@ use crate::config::*;
 #[derive(Debug, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct General {
-    listen: Option<String>,
+    listen: Option<Addresses>,
+    listen_tls: Option<Addresses>,
+    tls_certificate: Option<String>,
+    tls_private_key: Option<String>,
+    unix_socket_mode: Option<u32>,
+    unix_socket_owner: Option<String>,
+    unix_socket_group: Option<String>,
+    auth_token: Option<String>,
+    auth_username: Option<String>,
+    auth_password: Option<String>,
     #[serde(with = "LevelDef")]
     #[serde(default = "default_logging_level")]
     logging: Level,
@@ -23,11 +32,76 @@ pub struct General {
     fault_tolerant: AtomicBool,
     #[serde(default = "default_reading_suffix")]
     reading_suffix: String,
+    #[serde(default)]
+    histograms: bool,
+    histogram_bounds: Option<Vec<u64>>,
+    #[serde(default)]
+    history: Vec<String>,
+    #[serde(default = "default_history_depth")]
+    history_depth: usize,
+    #[serde(default = "default_stream_buffer")]
+    stream_buffer: usize,
+    #[serde(default = "default_stream_clients")]
+    stream_clients: usize,
+    #[serde(default = "default_shutdown_timeout")]
+    shutdown_timeout: usize,
 }
 
 impl General {
-    pub fn listen(&self) -> Option<String> {
-        self.listen.clone()
+    /// addresses to serve plaintext HTTP on
+    pub fn listen(&self) -> Vec<String> {
+        self.listen
+            .as_ref()
+            .map(Addresses::list)
+            .unwrap_or_default()
+    }
+
+    /// addresses to serve HTTPS on
+    pub fn listen_tls(&self) -> Vec<String> {
+        self.listen_tls
+            .as_ref()
+            .map(Addresses::list)
+            .unwrap_or_default()
+    }
+
+    /// path to the PEM encoded certificate chain for the TLS listeners
+    pub fn tls_certificate(&self) -> Option<&str> {
+        self.tls_certificate.as_deref()
+    }
+
+    /// path to the PEM encoded private key for the TLS listeners
+    pub fn tls_private_key(&self) -> Option<&str> {
+        self.tls_private_key.as_deref()
+    }
+
+    /// permissions for unix domain socket listeners
+    pub fn unix_socket_mode(&self) -> Option<u32> {
+        self.unix_socket_mode
+    }
+
+    /// user name or id to own unix domain socket listeners
+    pub fn unix_socket_owner(&self) -> Option<&str> {
+        self.unix_socket_owner.as_deref()
+    }
+
+    /// group name or id to own unix domain socket listeners
+    pub fn unix_socket_group(&self) -> Option<&str> {
+        self.unix_socket_group.as_deref()
+    }
+
+    /// bearer token which grants access to the HTTP endpoints
+    pub fn auth_token(&self) -> Option<&str> {
+        self.auth_token.as_deref()
+    }
+
+    /// username for basic authentication to the HTTP endpoints
+    pub fn auth_username(&self) -> Option<&str> {
+        self.auth_username.as_deref()
+    }
+
+    /// password for basic authentication to the HTTP endpoints
+    pub fn auth_password(&self) -> Option<&str> {
+        self.auth_password.as_deref()
     }
 
     pub fn logging(&self) -> Level {
@@ -63,18 +137,89 @@ impl General {
             Some(&self.reading_suffix)
         }
     }
+
+    /// whether to export bucket counts for distribution statistics
+    pub fn histograms(&self) -> bool {
+        self.histograms
+    }
+
+    /// inclusive upper bounds for the buckets of the exported histograms
+    pub fn histogram_bounds(&self) -> Option<&[u64]> {
+        self.histogram_bounds.as_deref()
+    }
+
+    /// regular expressions for the statistic names which retain a history of
+    /// their readings
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// number of readings to retain for each statistic with a history
+    pub fn history_depth(&self) -> usize {
+        self.history_depth
+    }
+
+    /// number of updates to buffer for each streaming client before they are
+    /// dropped
+    pub fn stream_buffer(&self) -> usize {
+        self.stream_buffer
+    }
+
+    /// maximum number of concurrent streaming clients
+    pub fn stream_clients(&self) -> usize {
+        self.stream_clients
+    }
+
+    /// time in ms to wait for an orderly shutdown before exiting with an
+    /// error
+    pub fn shutdown_timeout(&self) -> usize {
+        self.shutdown_timeout
+    }
 }
 
 impl Default for General {
     fn default() -> General {
         General {
             listen: None,
+            listen_tls: None,
+            tls_certificate: None,
+            tls_private_key: None,
+            unix_socket_mode: None,
+            unix_socket_owner: None,
+            unix_socket_group: None,
+            auth_token: None,
+            auth_username: None,
+            auth_password: None,
             logging: default_logging_level(),
             interval: default_interval(),
             threads: default_threads(),
             window: default_window(),
             fault_tolerant: default_fault_tolerant(),
             reading_suffix: default_reading_suffix(),
+            histograms: false,
+            histogram_bounds: None,
+            history: Vec::new(),
+            history_depth: default_history_depth(),
+            stream_buffer: default_stream_buffer(),
+            stream_clients: default_stream_clients(),
+            shutdown_timeout: default_shutdown_timeout(),
+        }
+    }
+}
+
+/// One or more addresses, which may be given as a single string or a list
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Addresses {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Addresses {
+    fn list(&self) -> Vec<String> {
+        match self {
+            Self::One(address) => vec![address.clone()],
+            Self::Many(addresses) => addresses.clone(),
         }
     }
 }
@@ -99,6 +244,22 @@ fn default_reading_suffix() -> String {
     "count".to_string()
 }
 
+fn default_history_depth() -> usize {
+    600
+}
+
+fn default_stream_buffer() -> usize {
+    1_000
+}
+
+fn default_stream_clients() -> usize {
+    16
+}
+
+fn default_shutdown_timeout() -> usize {
+    10_000
+}
+
 #[derive(Clone, Deserialize, Debug)]
 #[serde(rename_all = "lowercase")]
 #[serde(remote = "Level")]
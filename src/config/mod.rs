@@ -8,6 +8,7 @@ mod samplers;
 
 use std::io::Read;
 use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::PathBuf;
 
 use clap::{App, Arg};
 use rustcommon_logger::Level;
@@ -16,12 +17,18 @@ use serde_derive::*;
 use crate::*;
 
 use config::exposition::*;
+pub use config::exposition::{FileFormat, OtlpProtocol};
+#[cfg(feature = "push_kafka")]
+pub use config::exposition::{KafkaAcks, KafkaCompression, KafkaFormat};
 pub use config::general::General;
 use config::samplers::*;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const NAME: &str = env!("CARGO_PKG_NAME");
 
+/// Prefix for listen addresses which are paths to unix domain sockets
+const UNIX_PREFIX: &str = "unix:";
+
 #[derive(Debug, Default, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
@@ -77,11 +84,30 @@ impl Config {
         config
     }
 
-    /// get listen address
-    pub fn listen(&self) -> Option<SocketAddr> {
+    /// get listen addresses for plaintext HTTP
+    pub fn listen(&self) -> Vec<ListenAddress> {
         self.general
             .listen()
-            .map(|v| v.to_socket_addrs().unwrap().next().unwrap())
+            .iter()
+            .map(|address| match address.strip_prefix(UNIX_PREFIX) {
+                Some(path) => ListenAddress::Unix(PathBuf::from(path)),
+                None => ListenAddress::Tcp(resolve(address)),
+            })
+            .collect()
+    }
+
+    /// get listen addresses for HTTPS
+    pub fn listen_tls(&self) -> Vec<SocketAddr> {
+        self.general
+            .listen_tls()
+            .iter()
+            .map(|address| {
+                if address.starts_with(UNIX_PREFIX) {
+                    fatal!("unix sockets are not supported for listen_tls: {}", address);
+                }
+                resolve(address)
+            })
+            .collect()
     }
 
     /// get logging level
@@ -122,6 +148,32 @@ impl Config {
     }
 }
 
+/// An address for the stats listener
+#[derive(Clone, Debug)]
+pub enum ListenAddress {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl std::fmt::Display for ListenAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "{}", address),
+            Self::Unix(path) => write!(f, "{}{}", UNIX_PREFIX, path.display()),
+        }
+    }
+}
+
+/// resolve an address to the first socket address it refers to
+fn resolve(address: &str) -> SocketAddr {
+    match address.to_socket_addrs().map(|mut a| a.next()) {
+        Ok(Some(address)) => address,
+        _ => {
+            fatal!("failed to resolve listen address: {}", address);
+        }
+    }
+}
+
 pub trait SamplerConfig {
     type Statistic;
     fn bpf(&self) -> bool {
@@ -132,8 +184,33 @@ pub trait SamplerConfig {
     }
     fn interval(&self) -> Option<usize>;
     fn percentiles(&self) -> &[f64];
+    fn summaries(&self) -> &[SummaryOutput];
     fn perf_events(&self) -> bool {
         false
     }
     fn statistics(&self) -> Vec<<Self as config::SamplerConfig>::Statistic>;
 }
+
+/// Additional outputs which summarize a statistic across the same window as
+/// the configured percentiles.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryOutput {
+    Min,
+    Max,
+    Mean,
+    Stddev,
+    Samples,
+}
+
+impl From<SummaryOutput> for Output {
+    fn from(summary: SummaryOutput) -> Self {
+        match summary {
+            SummaryOutput::Min => Output::Min,
+            SummaryOutput::Max => Output::Max,
+            SummaryOutput::Mean => Output::Mean,
+            SummaryOutput::Stddev => Output::Stddev,
+            SummaryOutput::Samples => Output::Samples,
+        }
+    }
+}
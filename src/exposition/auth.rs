@@ -0,0 +1,109 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::config::General;
+
+/// Credentials which grant access to the HTTP endpoints. A request is allowed
+/// if it presents any of the configured credentials in its `Authorization`
+/// header.
+pub struct Auth {
+    token: Option<String>,
+    basic: Option<String>,
+}
+
+impl Auth {
+    /// Returns `None` if no credentials are configured
+    pub fn new(config: &General) -> Option<Self> {
+        let token = config.auth_token().map(|token| token.to_string());
+        let basic = match (config.auth_username(), config.auth_password()) {
+            (Some(username), Some(password)) => {
+                Some(base64::encode(format!("{}:{}", username, password)))
+            }
+            (None, None) => None,
+            _ => {
+                fatal!("both auth_username and auth_password must be set for basic authentication");
+            }
+        };
+        if token.is_none() && basic.is_none() {
+            return None;
+        }
+        Some(Self { token, basic })
+    }
+
+    /// Check the value of an `Authorization` header
+    pub fn authorized(&self, authorization: Option<&str>) -> bool {
+        let (scheme, credentials) = match authorization.and_then(|a| a.trim().split_once(' ')) {
+            Some(parts) => parts,
+            None => return false,
+        };
+        let expected = if scheme.eq_ignore_ascii_case("bearer") {
+            &self.token
+        } else if scheme.eq_ignore_ascii_case("basic") {
+            &self.basic
+        } else {
+            return false;
+        };
+        expected
+            .as_ref()
+            .map(|expected| constant_time_eq(expected.as_bytes(), credentials.trim().as_bytes()))
+            .unwrap_or(false)
+    }
+
+    /// The value for the `WWW-Authenticate` header of a rejected request
+    pub fn challenge(&self) -> &'static str {
+        if self.basic.is_some() {
+            "Basic realm=\"rezolus\""
+        } else {
+            "Bearer realm=\"rezolus\""
+        }
+    }
+}
+
+/// Compare credentials without exiting early on the first mismatch, so that
+/// response timing does not reveal how much of a guess was correct
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn auth(config: &str) -> Option<Auth> {
+        let config: Config =
+            toml::from_str(&format!("[general]\n{}", config)).expect("invalid config");
+        Auth::new(config.general())
+    }
+
+    #[test]
+    fn disabled() {
+        assert!(auth("").is_none());
+    }
+
+    #[test]
+    fn bearer() {
+        let auth = auth("auth_token = \"s3cret\"").unwrap();
+        assert!(auth.authorized(Some("Bearer s3cret")));
+        assert!(auth.authorized(Some("bearer s3cret")));
+        assert!(!auth.authorized(Some("Bearer s3cre")));
+        assert!(!auth.authorized(Some("Basic s3cret")));
+        assert!(!auth.authorized(Some("s3cret")));
+        assert!(!auth.authorized(None));
+        assert_eq!(auth.challenge(), "Bearer realm=\"rezolus\"");
+    }
+
+    #[test]
+    fn basic() {
+        let auth = auth("auth_username = \"prometheus\"\nauth_password = \"hunter2\"").unwrap();
+        // base64 of `prometheus:hunter2`
+        assert!(auth.authorized(Some("Basic cHJvbWV0aGV1czpodW50ZXIy")));
+        assert!(!auth.authorized(Some("Basic cHJvbWV0aGV1czpodW50ZXIz")));
+        assert!(!auth.authorized(Some("Bearer cHJvbWV0aGV1czpodW50ZXIy")));
+        assert_eq!(auth.challenge(), "Basic realm=\"rezolus\"");
+    }
+}
@@ -0,0 +1,356 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::convert::TryInto;
+use std::fs::{File, OpenOptions};
+use std::io::{Error, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::common::shutdown::Shutdown;
+use crate::*;
+
+use crate::common::hostname;
+use crate::config::{Config, FileFormat};
+use crate::exposition::{labels_suffix, Exporter, Filter, MetricsSnapshot};
+
+/// The first line of each csv file
+const CSV_HEADER: &str = "timestamp,name,labels,value\n";
+
+/// Appends a timestamped snapshot to a local file each interval. The file is
+/// rotated once it reaches a size or age limit by renaming it with the time
+/// of rotation in milliseconds as a suffix, eg: `rezolus.log.1655856000000`,
+/// and the oldest rotated files are removed beyond the retention limit. The
+/// age is measured from when the file was created, which may be before the
+/// sink opened it.
+pub struct FileSink {
+    snapshot: MetricsSnapshot,
+    path: PathBuf,
+    format: FileFormat,
+    host: String,
+    interval: Duration,
+    max_size: u64,
+    max_age: Option<Duration>,
+    retain: usize,
+    compress: bool,
+    file: Option<(File, SystemTime)>,
+}
+
+impl FileSink {
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Self {
+        let file = config.exposition().file();
+        let path = match file.path() {
+            Some(path) => path.to_path_buf(),
+            None => {
+                fatal!("file exposition requires a path");
+            }
+        };
+        Self {
+            snapshot: MetricsSnapshot::new(metrics, config.general().reading_suffix()),
+            path,
+            format: file.format(),
+            host: hostname().unwrap_or_else(|| "unknown".to_string()),
+            interval: Duration::from_millis(file.interval().try_into().unwrap()),
+            max_size: file.max_size(),
+            max_age: match file.max_age() {
+                0 => None,
+                seconds => Some(Duration::from_secs(seconds)),
+            },
+            retain: file.retain(),
+            compress: file.compress(),
+            file: None,
+        }
+    }
+
+    fn write(&mut self, record: &[u8]) -> Result<(), Error> {
+        if let Some((file, created)) = &self.file {
+            let size_exceeded = self.max_size > 0 && file.metadata()?.len() >= self.max_size;
+            let age = SystemTime::now()
+                .duration_since(*created)
+                .unwrap_or_default();
+            let age_exceeded = self.max_age.map(|max_age| age >= max_age);
+            if size_exceeded || age_exceeded.unwrap_or(false) {
+                self.file = None;
+                // the record is still written if rotation fails, and it is
+                // attempted again on the next write
+                if let Err(e) = self.rotate() {
+                    error!("failed to rotate {}: {}", self.path.display(), e);
+                }
+            }
+        }
+        if self.file.is_none() {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            let metadata = file.metadata()?;
+            if self.format == FileFormat::Csv && metadata.len() == 0 {
+                file.write_all(CSV_HEADER.as_bytes())?;
+            }
+            // not every filesystem records the creation time
+            let created = metadata.created().unwrap_or_else(|_| SystemTime::now());
+            self.file = Some((file, created));
+        }
+        if let Some((file, _)) = self.file.as_mut() {
+            file.write_all(record)?;
+        }
+        Ok(())
+    }
+
+    /// Move the current file aside, compressing it if configured, and remove
+    /// the oldest rotated files beyond the retention limit. Old files are
+    /// removed even if compression fails, in which case the rotated file is
+    /// kept uncompressed.
+    fn rotate(&self) -> Result<(), Error> {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let rotated = self.rotation_path(time);
+        std::fs::rename(&self.path, &rotated)?;
+        debug!("rotated {} to {}", self.path.display(), rotated.display());
+
+        let compressed = if self.compress {
+            compress(&rotated)
+        } else {
+            Ok(())
+        };
+
+        let mut rotated = self.rotated()?;
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(self.retain);
+        for (_, path) in rotated.iter().take(excess) {
+            debug!("removing {}", path.display());
+            std::fs::remove_file(path)?;
+        }
+        compressed
+    }
+
+    /// The path to rotate the file to at a time in milliseconds. If a file
+    /// was already rotated at that time the next free time is used, so that
+    /// it is not overwritten and the files still sort by time.
+    fn rotation_path(&self, mut time: u128) -> PathBuf {
+        loop {
+            let mut path = self.path.clone().into_os_string();
+            path.push(format!(".{}", time));
+            let mut compressed = path.clone();
+            compressed.push(".gz");
+            if !Path::new(&path).exists() && !Path::new(&compressed).exists() {
+                return PathBuf::from(path);
+            }
+            time += 1;
+        }
+    }
+
+    /// The rotated files alongside the current file with the time each was
+    /// rotated
+    fn rotated(&self) -> Result<Vec<(u128, PathBuf)>, Error> {
+        let directory = match self.path.parent() {
+            Some(parent) if parent != Path::new("") => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let mut prefix = self
+            .path
+            .file_name()
+            .map(|name| name.to_os_string())
+            .unwrap_or_default();
+        prefix.push(".");
+        let prefix = prefix.to_string_lossy().to_string();
+
+        let mut rotated = Vec::new();
+        for entry in std::fs::read_dir(directory)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let suffix = match name.strip_prefix(&prefix) {
+                Some(suffix) => suffix.strip_suffix(".gz").unwrap_or(suffix),
+                None => continue,
+            };
+            if let Ok(time) = suffix.parse() {
+                rotated.push((time, entry.path()));
+            }
+        }
+        Ok(rotated)
+    }
+}
+
+impl Exporter for FileSink {
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Snapshot the metrics and append them to the file, rotating it first if
+    /// it has reached a limit.
+    fn push(&mut self, _shutdown: &Shutdown) {
+        self.snapshot.refresh();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let record = match self.format {
+            FileFormat::Json => format!("{}\n", self.snapshot.json_record(&self.host, timestamp)),
+            FileFormat::Csv => csv(&self.snapshot, timestamp),
+        };
+        if let Err(e) = self.write(record.as_bytes()) {
+            error!("failed to write to {}: {}", self.path.display(), e);
+            // the file is reopened on the next write
+            self.file = None;
+        }
+    }
+}
+
+/// Compress a rotated file to a `.gz` file alongside it, removing the original
+/// if successful and the partial compressed file if not
+fn compress(path: &Path) -> Result<(), Error> {
+    let mut compressed = path.to_path_buf().into_os_string();
+    compressed.push(".gz");
+    let result = File::create(&compressed).and_then(|file| {
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        std::io::copy(&mut File::open(path)?, &mut encoder)?;
+        encoder.finish()
+    });
+    match result {
+        Ok(_) => std::fs::remove_file(path),
+        Err(e) => {
+            let _ = std::fs::remove_file(&compressed);
+            Err(e)
+        }
+    }
+}
+
+/// Renders the snapshot as one csv row per sample, with the labels in the same
+/// form as in the JSON format, eg: `{cpu=0}`
+fn csv(snapshot: &MetricsSnapshot, timestamp: Duration) -> String {
+    let mut content = String::new();
+    for sample in snapshot.samples(&Filter::default()) {
+        content += &format!(
+            "{},{},{},{}\n",
+            timestamp.as_millis(),
+            csv_field(&sample.name),
+            csv_field(&labels_suffix(&sample.labels)),
+            sample.value
+        );
+    }
+    content
+}
+
+/// Quotes a field which contains a delimiter, quote, or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::samplers::cpu::CpuStatistic;
+
+    fn metrics() -> Arc<Metrics> {
+        let metrics = Arc::new(Metrics::new());
+        let usage = Labeled::new(CpuStatistic::UsageUser, Labels::new().with("cpu", 0));
+        metrics.add_output(&usage, Output::Reading);
+        metrics
+            .record_counter(&usage, crate::Instant::now(), 1000)
+            .unwrap();
+        metrics.add_output(&CpuStatistic::Frequency, Output::Reading);
+        metrics
+            .record_gauge(
+                &CpuStatistic::Frequency,
+                crate::Instant::now(),
+                2_400_000_000,
+            )
+            .unwrap();
+        metrics
+    }
+
+    /// An empty directory for a test, removed by the test once it passes
+    fn directory(test: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("rezolus-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn config(path: &Path, options: &str) -> Config {
+        toml::from_str(&format!(
+            "[exposition.file]\n\
+             enabled = true\n\
+             path = \"{}\"\n\
+             {}",
+            path.display(),
+            options
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn rotation() {
+        let directory = directory("file-rotation");
+        let path = directory.join("rezolus.csv");
+
+        // every write after the first rotates the file
+        let config = config(
+            &path,
+            "format = \"csv\"\nmax_size = 1\nretain = 2\ncompress = true\n",
+        );
+        let mut sink = FileSink::new(&config, metrics());
+        for _ in 0..4 {
+            sink.push(&Shutdown::new());
+            std::thread::sleep(Duration::from_millis(2));
+        }
+
+        let mut names: Vec<String> = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names.len(), 3);
+        assert_eq!(names[0], "rezolus.csv");
+        assert!(names[1].starts_with("rezolus.csv.") && names[1].ends_with(".gz"));
+        assert!(names[2].starts_with("rezolus.csv.") && names[2].ends_with(".gz"));
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "timestamp,name,labels,value");
+        assert!(lines[1].ends_with(",cpu/frequency/count,,2400000000"));
+        assert!(lines[2].ends_with(",cpu/usage/user/count,{cpu=0},1000"));
+
+        let compressed = std::fs::read(directory.join(&names[1])).unwrap();
+        let mut decoder = flate2::write::GzDecoder::new(Vec::new());
+        decoder.write_all(&compressed).unwrap();
+        let rotated = String::from_utf8(decoder.finish().unwrap()).unwrap();
+        assert_eq!(rotated.lines().count(), 3);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn same_millisecond() {
+        // files already rotated at a time, compressed or not, are not
+        // overwritten by a second rotation in the same millisecond
+        let directory = directory("file-same-millisecond");
+        let path = directory.join("rezolus.log");
+        let sink = FileSink::new(&config(&path, ""), metrics());
+
+        File::create(directory.join("rezolus.log.1655856000000")).unwrap();
+        File::create(directory.join("rezolus.log.1655856000001.gz")).unwrap();
+        assert_eq!(
+            sink.rotation_path(1655856000000),
+            directory.join("rezolus.log.1655856000002")
+        );
+        assert_eq!(
+            sink.rotation_path(1655856000003),
+            directory.join("rezolus.log.1655856000003")
+        );
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
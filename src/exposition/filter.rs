@@ -0,0 +1,170 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::*;
+
+use regex::Regex;
+
+/// The kinds of outputs which may be selected with the `outputs` parameter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputKind {
+    Reading,
+    Percentile,
+    Histogram,
+    Min,
+    Max,
+    Mean,
+    Stddev,
+    Samples,
+}
+
+impl OutputKind {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "reading" => Ok(Self::Reading),
+            "percentile" | "percentiles" => Ok(Self::Percentile),
+            "histogram" => Ok(Self::Histogram),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            "mean" => Ok(Self::Mean),
+            "stddev" => Ok(Self::Stddev),
+            "samples" => Ok(Self::Samples),
+            _ => Err(format!("unknown output: {}", name)),
+        }
+    }
+}
+
+impl From<Output> for OutputKind {
+    fn from(output: Output) -> Self {
+        match output {
+            Output::Reading => Self::Reading,
+            Output::Percentile(_) => Self::Percentile,
+            Output::Histogram => Self::Histogram,
+            Output::Min => Self::Min,
+            Output::Max => Self::Max,
+            Output::Mean => Self::Mean,
+            Output::Stddev => Self::Stddev,
+            Output::Samples => Self::Samples,
+        }
+    }
+}
+
+/// Selects a subset of the metrics in a snapshot. Filters are parsed from the
+/// query string of a request, eg: `?prefix=tcp/&outputs=reading`, and all of
+/// the provided filters must match for a metric to be included.
+#[derive(Default)]
+pub struct Filter {
+    prefix: Option<String>,
+    pattern: Option<Regex>,
+    outputs: Option<Vec<OutputKind>>,
+    percentiles: Option<Vec<u64>>,
+}
+
+impl Filter {
+    /// Parse a filter from a query string. Parameters which are not filters
+    /// are ignored. Returns an error describing the first invalid filter.
+    pub fn from_query(query: &str) -> Result<Self, String> {
+        let mut filter = Self::default();
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            let value = decode(value)?;
+            match key {
+                "prefix" => {
+                    filter.prefix = Some(value);
+                }
+                "match" => {
+                    let pattern =
+                        Regex::new(&value).map_err(|e| format!("invalid match pattern: {}", e))?;
+                    filter.pattern = Some(pattern);
+                }
+                "outputs" => {
+                    let outputs = list(&value)
+                        .map(OutputKind::parse)
+                        .collect::<Result<Vec<OutputKind>, String>>()?;
+                    filter.outputs = Some(outputs);
+                }
+                "percentiles" => {
+                    let percentiles = list(&value)
+                        .map(|p| match p.parse::<f64>() {
+                            Ok(p) if (0.0..=100.0).contains(&p) => Ok(approx_percentile(p)),
+                            _ => Err(format!("invalid percentile: {}", p)),
+                        })
+                        .collect::<Result<Vec<u64>, String>>()?;
+                    filter.percentiles = Some(percentiles);
+                }
+                _ => {}
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Returns true if the metric should be included
+    pub fn matches(&self, metric: &Metric) -> bool {
+        if !self.selects(metric.statistic()) {
+            return false;
+        }
+        let output = metric.output();
+        if let Some(ref outputs) = self.outputs {
+            if !outputs.contains(&OutputKind::from(output)) {
+                return false;
+            }
+        }
+        if let (Some(percentiles), Output::Percentile(percentile)) = (&self.percentiles, output) {
+            if !percentiles.contains(&approx_percentile(percentile)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns true if the statistic is selected by the `prefix` and `match`
+    /// filters, which are the only filters which apply to a statistic rather
+    /// than its outputs
+    pub fn selects(&self, statistic: &dyn Statistic) -> bool {
+        let name = statistic.name();
+        if let Some(ref prefix) = self.prefix {
+            if !name.starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+        if let Some(ref pattern) = self.pattern {
+            if !pattern.is_match(name) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Percentiles are compared with the same precision as they are tracked with
+fn approx_percentile(percentile: f64) -> u64 {
+    (percentile * 1000000.0).ceil() as u64
+}
+
+/// Splits a comma separated list, ignoring empty entries
+fn list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(|v| v.trim()).filter(|v| !v.is_empty())
+}
+
+/// Decodes a percent-encoded query string value, where `+` encodes a space
+pub(super) fn decode(value: &str) -> Result<String, String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut iter = value.bytes();
+    while let Some(byte) = iter.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex: Vec<u8> = iter.by_ref().take(2).collect();
+                let decoded = std::str::from_utf8(&hex)
+                    .ok()
+                    .filter(|hex| hex.len() == 2)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| format!("invalid percent-encoding in: {}", value))?;
+                bytes.push(decoded);
+            }
+            byte => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| format!("invalid utf-8 in: {}", value))
+}
@@ -0,0 +1,282 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::common::shutdown::Shutdown;
+use crate::*;
+
+use crate::config::Config;
+use crate::exposition::{prefix, Exporter, Filter, MetricsSnapshot, Sample};
+
+/// Initial delay before reconnecting after a failed connection attempt
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Pushes metrics to a Graphite/Carbon endpoint using the plaintext protocol,
+/// one `path value timestamp` line per sample. Lines are buffered while the
+/// endpoint is unreachable, up to a limit after which the oldest are dropped.
+pub struct Graphite {
+    snapshot: MetricsSnapshot,
+    address: String,
+    prefix: String,
+    interval: Duration,
+    timeout: Duration,
+    stream: Option<TcpStream>,
+    buffer: VecDeque<String>,
+    buffer_limit: usize,
+    backoff: Duration,
+    max_backoff: Duration,
+    next_connect: Instant,
+}
+
+impl Graphite {
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Self {
+        let graphite = config.exposition().graphite();
+        let address = match graphite.address() {
+            Some(address) => address.to_string(),
+            None => {
+                fatal!("graphite exposition requires an address");
+            }
+        };
+        Self {
+            snapshot: MetricsSnapshot::new(metrics, config.general().reading_suffix()),
+            address,
+            prefix: prefix(graphite.prefix(), path_component),
+            interval: Duration::from_millis(graphite.interval().try_into().unwrap()),
+            timeout: Duration::from_millis(graphite.timeout().try_into().unwrap()),
+            stream: None,
+            buffer: VecDeque::new(),
+            buffer_limit: graphite.buffer(),
+            backoff: MIN_BACKOFF,
+            max_backoff: Duration::from_millis(graphite.max_backoff().try_into().unwrap()),
+            next_connect: Instant::now(),
+        }
+    }
+
+    /// Write the buffered lines, connecting first if needed. Lines remain
+    /// buffered if the write fails, and the connection is retried with an
+    /// exponential backoff.
+    fn flush(&mut self, shutdown: &Shutdown) {
+        let timeout = shutdown.limit(self.timeout);
+        if self.buffer.is_empty() || timeout.is_zero() {
+            return;
+        }
+        if self.stream.is_none() {
+            if Instant::now() < self.next_connect {
+                return;
+            }
+            match self.connect(timeout) {
+                Ok(stream) => {
+                    debug!("connected to graphite at {}", self.address);
+                    self.stream = Some(stream);
+                    self.backoff = MIN_BACKOFF;
+                }
+                Err(e) => {
+                    error!("failed to connect to graphite at {}: {}", self.address, e);
+                    self.next_connect = Instant::now() + self.backoff;
+                    self.backoff = (self.backoff * 2).min(self.max_backoff);
+                    return;
+                }
+            }
+        }
+        let (front, back) = self.buffer.as_slices();
+        let content: String = front
+            .iter()
+            .chain(back.iter())
+            .map(|l| l.as_str())
+            .collect();
+        if let Some(stream) = self.stream.as_mut() {
+            match stream
+                .set_write_timeout(Some(timeout))
+                .and_then(|_| stream.write_all(content.as_bytes()))
+            {
+                Ok(()) => self.buffer.clear(),
+                Err(e) => {
+                    // the lines may have been partially written, resending
+                    // them is harmless as graphite keeps the latest value
+                    error!("failed to write to graphite at {}: {}", self.address, e);
+                    self.stream = None;
+                }
+            }
+        }
+    }
+
+    fn connect(&self, timeout: Duration) -> Result<TcpStream, std::io::Error> {
+        let mut error = std::io::Error::from(std::io::ErrorKind::NotFound);
+        for address in self.address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, timeout) {
+                Ok(stream) => {
+                    let _ = stream.set_nodelay(true);
+                    return Ok(stream);
+                }
+                Err(e) => error = e,
+            }
+        }
+        Err(error)
+    }
+}
+
+impl Exporter for Graphite {
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Snapshot the metrics and write them after any lines which are still
+    /// buffered.
+    fn push(&mut self, shutdown: &Shutdown) {
+        self.snapshot.refresh();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|t| t.as_secs())
+            .unwrap_or(0);
+        for sample in self.snapshot.samples(&Filter::default()) {
+            if let Some(line) = line(&self.prefix, &sample, timestamp) {
+                self.buffer.push_back(line);
+            }
+        }
+        if self.buffer.len() > self.buffer_limit {
+            let dropped = self.buffer.len() - self.buffer_limit;
+            self.buffer.drain(..dropped);
+            warn!("graphite buffer full, dropped {} lines", dropped);
+        }
+        self.flush(shutdown);
+    }
+}
+
+/// Renders a sample as a line in the plaintext protocol. The path is the
+/// sample name with `/` translated to `.`, and labels are rendered as tags.
+/// Returns `None` for values which graphite can not represent.
+pub(crate) fn line(prefix: &str, sample: &Sample, timestamp: u64) -> Option<String> {
+    if let MetricValue::Float(value) = sample.value {
+        if !value.is_finite() {
+            return None;
+        }
+    }
+    let path: Vec<String> = sample.name.split('/').map(path_component).collect();
+    let mut line = format!("{}{}", prefix, path.join("."));
+    for (key, value) in sample.labels.iter() {
+        line += &format!(";{}={}", tag(key), tag(value));
+    }
+    Some(format!("{} {} {}\n", line, sample.value, timestamp))
+}
+
+/// Replaces characters which separate or delimit paths
+fn path_component(component: &str) -> String {
+    component
+        .chars()
+        .map(|c| match c {
+            '.' | ' ' | ';' | '=' | '~' | '\t' | '\n' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Replaces characters which are not allowed in tag names and values
+fn tag(value: &str) -> String {
+    let value: String = value
+        .chars()
+        .map(|c| match c {
+            ' ' | ';' | '=' | '~' | '!' | '^' | '\t' | '\n' => '_',
+            c => c,
+        })
+        .collect();
+    if value.is_empty() {
+        "_".to_string()
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::samplers::cpu::CpuStatistic;
+
+    fn metrics() -> Arc<Metrics> {
+        let metrics = Arc::new(Metrics::new());
+        let usage = Labeled::new(CpuStatistic::UsageUser, Labels::new().with("cpu", 0));
+        metrics.add_output(&usage, Output::Reading);
+        metrics.add_output(&CpuStatistic::Frequency, Output::Reading);
+        let now = crate::Instant::now();
+        metrics.record_counter(&usage, now, 123_456_789).unwrap();
+        metrics
+            .record_gauge(&CpuStatistic::Frequency, now, 2_400_000_000)
+            .unwrap();
+        metrics
+    }
+
+    #[test]
+    fn lines() {
+        let mut snapshot = MetricsSnapshot::new(metrics(), None);
+        snapshot.refresh();
+        let mut lines: Vec<String> = snapshot
+            .samples(&Filter::default())
+            .iter()
+            .filter_map(|sample| line("rezolus.web01.", sample, 1655856000))
+            .collect();
+        lines.sort();
+        assert_eq!(
+            lines,
+            [
+                "rezolus.web01.cpu.frequency 2400000000 1655856000\n",
+                "rezolus.web01.cpu.usage.user;cpu=0 123456789 1655856000\n",
+            ]
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        let sample = Sample {
+            name: "disk/read/bytes".to_string(),
+            labels: Labels::new()
+                .with("model", "SSD 970;EVO")
+                .with("serial", ""),
+            counter: true,
+            value: MetricValue::Unsigned(4096),
+        };
+        assert_eq!(
+            line("", &sample, 1655856000).unwrap(),
+            "disk.read.bytes;model=SSD_970_EVO;serial=_ 4096 1655856000\n"
+        );
+        let sample = Sample {
+            name: "ntp/offset".to_string(),
+            labels: Labels::new(),
+            counter: false,
+            value: MetricValue::Float(f64::NAN),
+        };
+        assert_eq!(line("", &sample, 1655856000), None);
+    }
+
+    #[test]
+    fn buffer_overflow() {
+        // nothing listens on the port, so the lines stay buffered
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config: Config = toml::from_str(&format!(
+            "[exposition.graphite]\n\
+             enabled = true\n\
+             address = \"127.0.0.1:{}\"\n\
+             buffer = 3\n",
+            port
+        ))
+        .unwrap();
+        let mut graphite = Graphite::new(&config, metrics());
+        graphite.push(&Shutdown::new());
+        assert_eq!(graphite.buffer.len(), 2);
+        let newest = graphite.buffer[1].clone();
+
+        // the oldest line is dropped to make room for the next push
+        graphite.push(&Shutdown::new());
+        assert_eq!(graphite.buffer.len(), 3);
+        assert_eq!(graphite.buffer[0], newest);
+    }
+}
@@ -2,78 +2,964 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
-use std::net::SocketAddr;
-use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::convert::Infallible;
+use std::io::Write;
+use std::net::TcpListener;
+use std::os::unix::net::UnixListener;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::*;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use hyper::body::Bytes;
+use hyper::header::{
+    HeaderValue, ACCEPT, ACCEPT_ENCODING, ALLOW, AUTHORIZATION, CACHE_CONTROL, CONTENT_ENCODING,
+    CONTENT_TYPE, VARY, WWW_AUTHENTICATE,
+};
+use hyper::server::conn::Http as Connection;
+use hyper::service::service_fn;
+use hyper::{Body, Method, Request, Response, StatusCode};
 use rustcommon_logger::*;
-use tiny_http::{Method, Response, Server};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::runtime::Runtime;
 
-use super::MetricsSnapshot;
+use super::auth::Auth;
+use super::filter::decode;
+use super::tls::Tls;
+use super::unix;
+use super::{json_value, Filter, MetricsSnapshot};
+use crate::common::shutdown::Shutdown;
+use crate::common::status::Status;
+use crate::common::{hostname, KernelInfo};
+use crate::config::{Config, General, ListenAddress};
+
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+const JSON_CONTENT_TYPE: &str = "application/json";
+const TEXT_CONTENT_TYPE: &str = "text/plain; charset=utf-8";
+const EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+
+/// Snapshots are shared between concurrent requests and only refreshed once
+/// they are older than this
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Time to wait before accepting again after a failed accept, which avoids
+/// spinning when the process is out of file descriptors
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Time without updates after which a comment is sent to a streaming client,
+/// which keeps idle connections from being closed by proxies
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
 
 pub struct Http {
-    snapshot: MetricsSnapshot,
-    server: Server,
-    updated: Instant,
+    listeners: Vec<Listener>,
+    tls: Option<Arc<Tls>>,
+    state: Arc<State>,
+}
+
+/// A bound socket, which serves HTTPS if `tls` is set
+struct Listener {
+    address: ListenAddress,
+    socket: Socket,
+    tls: bool,
+}
+
+enum Socket {
+    Tcp(TcpListener),
+    Unix(UnixListener),
+}
+
+/// State shared between concurrent requests
+struct State {
+    snapshot: RwLock<Arc<MetricsSnapshot>>,
+    refreshing: tokio::sync::Mutex<()>,
+    metrics: Arc<Metrics>,
+    status: Arc<Status>,
+    auth: Option<Auth>,
+    hostname: Option<String>,
+    kernel: Option<String>,
+    stream_buffer: usize,
+    stream_clients: usize,
+    /// Signals the listeners and any open streams to close
+    shutdown: Arc<Shutdown>,
 }
 
 impl Http {
-    pub fn new(address: SocketAddr, metrics: Arc<Metrics>, count_label: Option<&str>) -> Self {
-        let server = tiny_http::Server::http(address);
-        if server.is_err() {
-            fatal!("Failed to open {} for HTTP Stats listener", address);
+    pub fn new(
+        config: &Config,
+        metrics: Arc<Metrics>,
+        status: Arc<Status>,
+        shutdown: Arc<Shutdown>,
+    ) -> Self {
+        let general = config.general();
+        let plaintext = config
+            .listen()
+            .into_iter()
+            .map(|address| Listener::bind(address, false, general));
+        let secure = config
+            .listen_tls()
+            .into_iter()
+            .map(|address| Listener::bind(ListenAddress::Tcp(address), true, general));
+        let listeners: Vec<Listener> = plaintext.chain(secure).collect();
+        if listeners.is_empty() {
+            fatal!("no listen address");
         }
+
+        let tls = if listeners.iter().any(|listener| listener.tls) {
+            match (general.tls_certificate(), general.tls_private_key()) {
+                (Some(certificate), Some(private_key)) => {
+                    match Tls::new(certificate, private_key) {
+                        Ok(tls) => Some(Arc::new(tls)),
+                        Err(e) => {
+                            fatal!("Failed to load TLS certificate: {}", e);
+                        }
+                    }
+                }
+                _ => {
+                    fatal!("tls_certificate and tls_private_key are required for listen_tls");
+                }
+            }
+        } else {
+            None
+        };
+
         Self {
-            snapshot: MetricsSnapshot::new(metrics, count_label),
-            server: server.unwrap(),
-            updated: Instant::now(),
+            listeners,
+            tls,
+            state: Arc::new(State::new(general, metrics, status, shutdown)),
         }
     }
 
-    pub fn run(&mut self) {
-        if let Ok(Some(request)) = self.server.try_recv() {
-            if self.updated.elapsed() >= Duration::from_millis(500) {
-                self.snapshot.refresh();
-                self.updated = Instant::now();
+    /// Serve requests on the runtime until shutdown. Connections are handled
+    /// concurrently and are kept alive between requests.
+    pub fn spawn(self, runtime: &Runtime) {
+        let _guard = runtime.enter();
+        let shutdown = self.state.shutdown.clone();
+        if let Some(ref tls) = self.tls {
+            tls.clone().spawn(runtime, shutdown.clone());
+        }
+        for listener in self.listeners {
+            let address = listener.address;
+            let state = self.state.clone();
+            let guard = shutdown.register();
+            let shutdown = shutdown.clone();
+            match listener.socket {
+                Socket::Tcp(socket) => {
+                    let socket = match tokio::net::TcpListener::from_std(socket) {
+                        Ok(socket) => socket,
+                        Err(e) => {
+                            fatal!("Failed to start HTTP Stats listener on {}: {}", address, e);
+                        }
+                    };
+                    let tls = if listener.tls { self.tls.clone() } else { None };
+                    runtime.spawn(async move {
+                        loop {
+                            let accepted = tokio::select! {
+                                accepted = socket.accept() => accepted,
+                                _ = shutdown.wait() => break,
+                            };
+                            let (stream, peer) = match accepted {
+                                Ok(connection) => connection,
+                                Err(e) => {
+                                    error!("HTTP Stats listener on {} failed: {}", address, e);
+                                    tokio::time::sleep(ACCEPT_BACKOFF).await;
+                                    continue;
+                                }
+                            };
+                            let state = state.clone();
+                            let tls = tls.clone();
+                            tokio::spawn(async move {
+                                match tls {
+                                    Some(tls) => match tls.acceptor().accept(stream).await {
+                                        Ok(stream) => serve(stream, state).await,
+                                        Err(e) => {
+                                            debug!("TLS handshake with {} failed: {}", peer, e)
+                                        }
+                                    },
+                                    None => serve(stream, state).await,
+                                }
+                            });
+                        }
+                        drop(socket);
+                        debug!("closed HTTP Stats listener on {}", address);
+                        drop(guard);
+                    });
+                }
+                Socket::Unix(socket) => {
+                    let socket = match tokio::net::UnixListener::from_std(socket) {
+                        Ok(socket) => socket,
+                        Err(e) => {
+                            fatal!("Failed to start HTTP Stats listener on {}: {}", address, e);
+                        }
+                    };
+                    runtime.spawn(async move {
+                        loop {
+                            let accepted = tokio::select! {
+                                accepted = socket.accept() => accepted,
+                                _ = shutdown.wait() => break,
+                            };
+                            let stream = match accepted {
+                                Ok((stream, _)) => stream,
+                                Err(e) => {
+                                    error!("HTTP Stats listener on {} failed: {}", address, e);
+                                    tokio::time::sleep(ACCEPT_BACKOFF).await;
+                                    continue;
+                                }
+                            };
+                            tokio::spawn(serve(stream, state.clone()));
+                        }
+                        drop(socket);
+                        if let ListenAddress::Unix(ref path) = address {
+                            let _ = std::fs::remove_file(path);
+                        }
+                        debug!("closed HTTP Stats listener on {}", address);
+                        drop(guard);
+                    });
+                }
             }
-            let url = request.url();
-            let parts: Vec<&str> = url.split('?').collect();
-            let url = parts[0];
-            match request.method() {
-                Method::Get => match url {
-                    "/" => {
-                        debug!("Serving GET on index");
-                        let _ = request.respond(Response::from_string(format!(
-                            "Welcome to {}\nVersion: {}\n",
-                            crate::config::NAME,
-                            crate::config::VERSION,
-                        )));
-                    }
-                    "/metrics" => {
-                        debug!("Serving Prometheus compatible stats");
-                        let _ = request.respond(Response::from_string(self.snapshot.prometheus()));
-                    }
-                    "/metrics.json" | "/vars.json" | "/admin/metrics.json" => {
-                        debug!("Serving machine readable stats");
-                        let _ = request.respond(Response::from_string(self.snapshot.json(false)));
-                    }
-                    "/vars" => {
-                        debug!("Serving human readable stats");
-                        let _ = request.respond(Response::from_string(self.snapshot.human()));
+        }
+    }
+}
+
+impl State {
+    fn new(
+        config: &General,
+        metrics: Arc<Metrics>,
+        status: Arc<Status>,
+        shutdown: Arc<Shutdown>,
+    ) -> Self {
+        Self {
+            snapshot: RwLock::new(Arc::new(MetricsSnapshot::new(
+                metrics.clone(),
+                config.reading_suffix(),
+            ))),
+            refreshing: Default::default(),
+            metrics,
+            status,
+            auth: Auth::new(config),
+            hostname: hostname(),
+            kernel: KernelInfo::new()
+                .ok()
+                .map(|kernel| kernel.release().to_string()),
+            stream_buffer: config.stream_buffer(),
+            stream_clients: config.stream_clients(),
+            shutdown,
+        }
+    }
+}
+
+impl Listener {
+    fn bind(address: ListenAddress, tls: bool, config: &General) -> Self {
+        let socket = match address {
+            ListenAddress::Tcp(address) => TcpListener::bind(address)
+                .and_then(|socket| socket.set_nonblocking(true).map(|_| socket))
+                .map(Socket::Tcp),
+            ListenAddress::Unix(ref path) => unix::bind(path, config).map(Socket::Unix),
+        };
+        match socket {
+            Ok(socket) => Self {
+                address,
+                socket,
+                tls,
+            },
+            Err(e) => {
+                fatal!("Failed to open {} for HTTP Stats listener: {}", address, e);
+            }
+        }
+    }
+}
+
+/// Serve HTTP requests on a single connection until it is closed
+async fn serve<S>(stream: S, state: Arc<State>)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let service = service_fn(move |request| {
+        let state = state.clone();
+        async move { Ok::<_, Infallible>(handle(&state, request).await) }
+    });
+    if let Err(e) = Connection::new()
+        .http1_only(true)
+        .serve_connection(stream, service)
+        .await
+    {
+        debug!("error serving HTTP connection: {}", e);
+    }
+}
+
+/// Route a request to the matching exposition format or status endpoint
+async fn handle(state: &State, request: Request<Body>) -> Response<Body> {
+    let path = request.uri().path();
+    let known = matches!(
+        path,
+        "/" | "/metrics"
+            | "/metrics.json"
+            | "/vars.json"
+            | "/admin/metrics.json"
+            | "/vars"
+            | "/health"
+            | "/ready"
+            | "/info"
+            | "/history"
+            | "/stream"
+    );
+    if !known {
+        debug!("request for non-existent url: {}", path);
+        return respond(StatusCode::NOT_FOUND, TEXT_CONTENT_TYPE, "not found\n");
+    }
+    // health checks reveal nothing and are exempt from authentication
+    if let Some(ref auth) = state.auth {
+        if path != "/health" && !auth.authorized(header(&request, AUTHORIZATION)) {
+            debug!("unauthorized request for: {}", path);
+            let mut response = respond(
+                StatusCode::UNAUTHORIZED,
+                TEXT_CONTENT_TYPE,
+                "unauthorized\n",
+            );
+            response
+                .headers_mut()
+                .insert(WWW_AUTHENTICATE, HeaderValue::from_static(auth.challenge()));
+            return response;
+        }
+    }
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        debug!("unsupported request method: {}", request.method());
+        let mut response = respond(
+            StatusCode::METHOD_NOT_ALLOWED,
+            TEXT_CONTENT_TYPE,
+            "method not allowed\n",
+        );
+        response
+            .headers_mut()
+            .insert(ALLOW, HeaderValue::from_static("GET, HEAD"));
+        return response;
+    }
+
+    match path {
+        "/health" => {
+            return respond(StatusCode::OK, TEXT_CONTENT_TYPE, "ok\n");
+        }
+        "/ready" => {
+            let pending = state.status.pending();
+            if pending.is_empty() {
+                return respond(StatusCode::OK, TEXT_CONTENT_TYPE, "ready\n");
+            }
+            debug!("samplers not ready: {:?}", pending);
+            return respond(
+                StatusCode::SERVICE_UNAVAILABLE,
+                TEXT_CONTENT_TYPE,
+                format!("not ready: {}\n", pending.join(", ")),
+            );
+        }
+        "/info" => {
+            return respond(StatusCode::OK, JSON_CONTENT_TYPE, info(state));
+        }
+        "/history" => {
+            let query = request.uri().query().unwrap_or("");
+            return match history(&state.metrics, query) {
+                Ok(Some(content)) => {
+                    debug!("Serving history");
+                    ok(&request, JSON_CONTENT_TYPE, content)
+                }
+                Ok(None) => respond(
+                    StatusCode::NOT_FOUND,
+                    TEXT_CONTENT_TYPE,
+                    "no history for metric\n",
+                ),
+                Err(e) => {
+                    debug!("invalid query: {}", e);
+                    respond(StatusCode::BAD_REQUEST, TEXT_CONTENT_TYPE, e + "\n")
+                }
+            };
+        }
+        "/stream" => {
+            return stream(state, request.uri().query().unwrap_or(""));
+        }
+        _ => {}
+    }
+
+    let query = request.uri().query().unwrap_or("");
+    // metadata is opt-in to keep the default output a flat map of values
+    let metadata = query.split('&').any(|param| param == "metadata");
+    let filter = match Filter::from_query(query) {
+        Ok(filter) => filter,
+        Err(e) => {
+            debug!("invalid query: {}", e);
+            return respond(StatusCode::BAD_REQUEST, TEXT_CONTENT_TYPE, e + "\n");
+        }
+    };
+
+    let snapshot = snapshot(state).await;
+    let (content_type, content) = match path {
+        "/" => {
+            debug!("Serving GET on index");
+            (
+                TEXT_CONTENT_TYPE,
+                format!(
+                    "Welcome to {}\nVersion: {}\n",
+                    crate::config::NAME,
+                    crate::config::VERSION,
+                ),
+            )
+        }
+        "/metrics" => {
+            let openmetrics = header(&request, ACCEPT)
+                .map(accepts_openmetrics)
+                .unwrap_or(false);
+            if openmetrics {
+                debug!("Serving OpenMetrics stats");
+                (OPENMETRICS_CONTENT_TYPE, snapshot.openmetrics(&filter))
+            } else {
+                debug!("Serving Prometheus compatible stats");
+                (PROMETHEUS_CONTENT_TYPE, snapshot.prometheus(&filter))
+            }
+        }
+        "/vars" => {
+            debug!("Serving human readable stats");
+            (TEXT_CONTENT_TYPE, snapshot.human(&filter))
+        }
+        _ => {
+            debug!("Serving machine readable stats");
+            (JSON_CONTENT_TYPE, snapshot.json(false, metadata, &filter))
+        }
+    };
+
+    ok(&request, content_type, content)
+}
+
+/// A successful response, which is compressed if the client accepts gzip
+fn ok(request: &Request<Body>, content_type: &'static str, content: String) -> Response<Body> {
+    let gzip = header(request, ACCEPT_ENCODING)
+        .map(accepts_gzip)
+        .unwrap_or(false);
+    if gzip {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        if let Ok(compressed) = encoder
+            .write_all(content.as_bytes())
+            .and_then(|_| encoder.finish())
+        {
+            let mut response = respond(StatusCode::OK, content_type, compressed);
+            let headers = response.headers_mut();
+            headers.insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+            headers.insert(VARY, HeaderValue::from_static("Accept-Encoding"));
+            return response;
+        }
+    }
+    let mut response = respond(StatusCode::OK, content_type, content);
+    response
+        .headers_mut()
+        .insert(VARY, HeaderValue::from_static("Accept-Encoding"));
+    response
+}
+
+/// Describes the build and host along with the enabled samplers and the BPF
+/// probes which were attached for each of them
+fn info(state: &State) -> String {
+    let mut features = json::JsonValue::new_array();
+    if cfg!(feature = "bpf") {
+        let _ = features.push("bpf");
+    }
+    if cfg!(feature = "push_kafka") {
+        let _ = features.push("push_kafka");
+    }
+    let mut samplers = json::JsonValue::new_array();
+    let mut probes = json::JsonValue::new_object();
+    for sampler in state.status.samplers() {
+        let _ = samplers.push(sampler);
+        let attached = state.status.probes(sampler);
+        if !attached.is_empty() {
+            probes[sampler] = attached.into();
+        }
+    }
+    let info = json::object! {
+        "name": crate::config::NAME,
+        "version": crate::config::VERSION,
+        "features": features,
+        "kernel": state.kernel.clone(),
+        "hostname": state.hostname.clone(),
+        "samplers": samplers,
+        "probes": probes,
+    };
+    info.dump() + "\n"
+}
+
+/// Renders the retained readings of a statistic for a query such as
+/// `metric=cpu/usage/user&since=1655856000000`, with one series for each set
+/// of labels. Readings are rendered as `[timestamp, value]` pairs with the
+/// timestamp in milliseconds since the unix epoch, and only those after
+/// `since` are included if it is provided. Returns `None` if the statistic
+/// does not retain a history.
+pub(crate) fn history(metrics: &Metrics, query: &str) -> Result<Option<String>, String> {
+    let mut metric = None;
+    let mut since = None;
+    for param in query.split('&').filter(|param| !param.is_empty()) {
+        let (key, value) = param.split_once('=').unwrap_or((param, ""));
+        match key {
+            "metric" => {
+                metric = Some(decode(value)?);
+            }
+            "since" => {
+                let value = decode(value)?;
+                since = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| format!("invalid since: {}", value))?,
+                );
+            }
+            _ => {}
+        }
+    }
+    let metric = metric.ok_or_else(|| "metric is required".to_string())?;
+
+    let history = metrics.history(&metric);
+    if history.is_empty() {
+        return Ok(None);
+    }
+
+    let mut series = Vec::new();
+    for (labels, readings) in history {
+        let readings: Vec<String> = readings
+            .iter()
+            .map(|(_, timestamp, value)| (unix_millis(*timestamp), value))
+            .filter(|(timestamp, _)| *timestamp > since.unwrap_or(0))
+            .map(|(timestamp, value)| format!("[{},{}]", timestamp, json_value(value)))
+            .collect();
+        series.push(format!(
+            "{{\"labels\":{},\"readings\":[{}]}}",
+            json_labels(&labels),
+            readings.join(",")
+        ));
+    }
+    Ok(Some(format!(
+        "{{\"metric\":{},\"series\":[{}]}}\n",
+        json::stringify(metric),
+        series.join(",")
+    )))
+}
+
+/// Streams the readings of the statistics selected by the `prefix` and `match`
+/// filters in the query as server-sent events, as they are recorded. Each
+/// client has a bounded buffer of updates, so a client which falls behind has
+/// updates dropped rather than delaying the samplers, and is told how many
+/// with a `dropped` event. Streams are closed when the listeners shut down.
+fn stream(state: &State, query: &str) -> Response<Body> {
+    let filter = match Filter::from_query(query) {
+        Ok(filter) => filter,
+        Err(e) => {
+            debug!("invalid query: {}", e);
+            return respond(StatusCode::BAD_REQUEST, TEXT_CONTENT_TYPE, e + "\n");
+        }
+    };
+    let mut subscription = match state.metrics.try_subscribe(
+        state.stream_clients,
+        state.stream_buffer,
+        move |statistic| filter.selects(statistic),
+    ) {
+        Some(subscription) => subscription,
+        None => {
+            debug!("rejecting stream, too many clients");
+            return respond(
+                StatusCode::SERVICE_UNAVAILABLE,
+                TEXT_CONTENT_TYPE,
+                "too many streams\n",
+            );
+        }
+    };
+
+    debug!("Serving stream");
+    let (mut sender, body) = Body::channel();
+    let shutdown = state.shutdown.clone();
+    tokio::spawn(async move {
+        let mut reported = 0;
+        loop {
+            let received = tokio::select! {
+                received = tokio::time::timeout(KEEPALIVE_INTERVAL, subscription.recv()) => received,
+                _ = shutdown.wait() => break,
+            };
+            let events = match received {
+                Ok(Some(update)) => {
+                    let mut events = String::new();
+                    let dropped = subscription.dropped();
+                    if dropped > reported {
+                        events += &format!("event: dropped\ndata: {}\n\n", dropped - reported);
+                        reported = dropped;
                     }
-                    url => {
-                        debug!("GET on non-existent url: {}", url);
-                        debug!("Serving machine readable stats");
-                        let _ = request.respond(Response::from_string(self.snapshot.json(false)));
+                    events += &event(&update);
+                    // send whatever else is buffered in the same write
+                    while let Some(update) = subscription.try_recv() {
+                        events += &event(&update);
                     }
-                },
-                method => {
-                    debug!("unsupported request method: {}", method);
-                    let _ = request.respond(Response::empty(404));
+                    events
                 }
+                Ok(None) => break,
+                Err(_) => ": keepalive\n\n".to_string(),
+            };
+            // fails once the client has disconnected, which ends the
+            // subscription
+            if sender.send_data(Bytes::from(events)).await.is_err() {
+                break;
             }
         }
-        std::thread::sleep(std::time::Duration::from_millis(1));
+    });
+
+    let mut response = Response::new(body);
+    let headers = response.headers_mut();
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(EVENT_STREAM_CONTENT_TYPE),
+    );
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    response
+}
+
+/// Renders an update as a server-sent event with a single line JSON object,
+/// eg: `{"name":"cpu/usage/user","labels":{},"timestamp":1655856000000,"value":12345}`
+fn event(update: &Update) -> String {
+    format!(
+        "data: {{\"name\":{},\"labels\":{},\"timestamp\":{},\"value\":{}}}\n\n",
+        json::stringify(update.name.as_str()),
+        json_labels(&update.labels),
+        unix_millis(update.timestamp),
+        json_value(&update.value)
+    )
+}
+
+/// Renders labels as a JSON object
+fn json_labels(labels: &Labels) -> String {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}:{}", json::stringify(k), json::stringify(v)))
+        .collect();
+    format!("{{{}}}", labels.join(","))
+}
+
+/// Converts the wall-clock time of a reading to milliseconds since the unix
+/// epoch
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// The shared snapshot, which is replaced first if it is stale. The new
+/// snapshot is taken on a blocking thread so that it does not stall the
+/// runtime, and requests which arrive meanwhile wait for the same refresh.
+async fn snapshot(state: &State) -> Arc<MetricsSnapshot> {
+    let current = state.snapshot.read().unwrap().clone();
+    if current.age() < REFRESH_INTERVAL {
+        return current;
+    }
+    let _refreshing = state.refreshing.lock().await;
+    // another request may have refreshed it while waiting for the lock
+    let current = state.snapshot.read().unwrap().clone();
+    if current.age() < REFRESH_INTERVAL {
+        return current;
+    }
+    let previous = current.clone();
+    match tokio::task::spawn_blocking(move || previous.refreshed()).await {
+        Ok(refreshed) => {
+            let refreshed = Arc::new(refreshed);
+            *state.snapshot.write().unwrap() = refreshed.clone();
+            refreshed
+        }
+        Err(e) => {
+            error!("failed to refresh snapshot: {}", e);
+            current
+        }
+    }
+}
+
+fn respond<T: Into<Body>>(
+    status: StatusCode,
+    content_type: &'static str,
+    body: T,
+) -> Response<Body> {
+    let mut response = Response::new(body.into());
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+fn header(request: &Request<Body>, name: hyper::header::HeaderName) -> Option<&str> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// The highest quality value given to any of the names in a header which lists
+/// media types or encodings, eg: `Accept: text/plain;q=0.5`. Returns `None` if
+/// none of the names are listed.
+fn quality(header: &str, names: &[&str]) -> Option<f64> {
+    let mut result: Option<f64> = None;
+    for range in header.split(',') {
+        let mut params = range.split(';').map(|v| v.trim());
+        let name = params.next().unwrap_or("");
+        if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            continue;
+        }
+        let quality = params
+            .filter_map(|param| param.strip_prefix("q="))
+            .filter_map(|q| q.parse::<f64>().ok())
+            .next()
+            .unwrap_or(1.0);
+        result = Some(result.map_or(quality, |q| q.max(quality)));
+    }
+    result
+}
+
+/// Determines if OpenMetrics is preferred over the Prometheus text format by
+/// comparing the quality values in an `Accept` header.
+fn accepts_openmetrics(accept: &str) -> bool {
+    let openmetrics = quality(accept, &["application/openmetrics-text"]).unwrap_or(0.0);
+    let text = quality(accept, &["text/plain", "text/*", "*/*"]).unwrap_or(0.0);
+    openmetrics > 0.0 && openmetrics >= text
+}
+
+/// Determines if gzip is acceptable from an `Accept-Encoding` header. An
+/// explicit quality for gzip takes precedence over the wildcard.
+fn accepts_gzip(accept_encoding: &str) -> bool {
+    quality(accept_encoding, &["gzip"])
+        .or_else(|| quality(accept_encoding, &["*"]))
+        .unwrap_or(0.0)
+        > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(config: &str) -> State {
+        let config: Config =
+            toml::from_str(&format!("[general]\n{}", config)).expect("invalid config");
+        State::new(
+            config.general(),
+            Arc::new(Metrics::new()),
+            Arc::new(Status::new()),
+            Arc::new(Shutdown::new()),
+        )
+    }
+
+    fn request(method: Method, path: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(path)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    async fn body(response: Response<Body>) -> Vec<u8> {
+        hyper::body::to_bytes(response.into_body())
+            .await
+            .expect("failed to read body")
+            .to_vec()
+    }
+
+    #[tokio::test]
+    async fn routing() {
+        let state = state("");
+
+        let response = handle(&state, request(Method::GET, "/missing")).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = handle(&state, request(Method::POST, "/metrics")).await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[ALLOW], "GET, HEAD");
+
+        let response = handle(&state, request(Method::GET, "/metrics")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], PROMETHEUS_CONTENT_TYPE);
+
+        let mut openmetrics = request(Method::GET, "/metrics");
+        openmetrics.headers_mut().insert(
+            ACCEPT,
+            HeaderValue::from_static("application/openmetrics-text; version=1.0.0"),
+        );
+        let response = handle(&state, openmetrics).await;
+        assert_eq!(response.headers()[CONTENT_TYPE], OPENMETRICS_CONTENT_TYPE);
+    }
+
+    #[tokio::test]
+    async fn gzip() {
+        let state = state("");
+
+        let mut compressed = request(Method::GET, "/");
+        compressed
+            .headers_mut()
+            .insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip, deflate"));
+        let response = handle(&state, compressed).await;
+        assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+        assert_eq!(response.headers()[VARY], "Accept-Encoding");
+        let mut decoder = flate2::write::GzDecoder::new(Vec::new());
+        decoder.write_all(&body(response).await).unwrap();
+        let content = decoder.finish().expect("invalid gzip");
+        assert!(content.starts_with(b"Welcome to"));
+
+        let response = handle(&state, request(Method::GET, "/")).await;
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+        assert!(body(response).await.starts_with(b"Welcome to"));
+    }
+
+    #[test]
+    fn accept() {
+        assert_eq!(
+            quality("text/plain;q=0.5, */*;q=0.1", &["text/plain"]),
+            Some(0.5)
+        );
+        assert_eq!(quality("TEXT/PLAIN", &["text/plain"]), Some(1.0));
+        assert_eq!(quality("application/json", &["text/plain"]), None);
+
+        assert!(accepts_openmetrics("application/openmetrics-text"));
+        assert!(accepts_openmetrics(
+            "application/openmetrics-text;version=1.0.0;q=0.75,text/plain;version=0.0.4;q=0.5,*/*;q=0.1"
+        ));
+        assert!(!accepts_openmetrics(
+            "application/openmetrics-text;q=0.5,text/plain"
+        ));
+        assert!(!accepts_openmetrics("application/openmetrics-text;q=0"));
+        assert!(!accepts_openmetrics("*/*"));
+
+        assert!(accepts_gzip("gzip"));
+        assert!(accepts_gzip("*"));
+        assert!(!accepts_gzip("gzip;q=0, *"));
+        assert!(!accepts_gzip("identity"));
+    }
+
+    #[tokio::test]
+    async fn ready() {
+        let state = state("");
+        state.status.register("cpu");
+        state.status.register("memory");
+        state.status.initialized("cpu", Duration::from_secs(1));
+        state.status.sampled("cpu");
+
+        let response = handle(&state, request(Method::GET, "/ready")).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body(response).await, b"not ready: memory\n");
+
+        state.status.initialized("memory", Duration::from_secs(1));
+        state.status.sampled("memory");
+        let response = handle(&state, request(Method::GET, "/ready")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn info() {
+        let state = state("");
+        state.status.register("tcp");
+        state.status.register("cpu");
+        state.status.attached("tcp", "tcp_v4_connect");
+
+        let response = handle(&state, request(Method::GET, "/info")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], JSON_CONTENT_TYPE);
+        let body = String::from_utf8(body(response).await).unwrap();
+        let info = json::parse(&body).expect("invalid json");
+        assert_eq!(info["name"], crate::config::NAME);
+        assert_eq!(info["version"], crate::config::VERSION);
+        assert_eq!(info["samplers"], json::array!["cpu", "tcp"]);
+        assert_eq!(info["probes"], json::object! { "tcp": ["tcp_v4_connect"] });
+    }
+
+    #[tokio::test]
+    async fn unauthorized() {
+        let state = state("auth_token = \"s3cret\"");
+
+        let response = handle(&state, request(Method::GET, "/metrics")).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()[WWW_AUTHENTICATE],
+            "Bearer realm=\"rezolus\""
+        );
+
+        let mut authorized = request(Method::GET, "/metrics");
+        authorized
+            .headers_mut()
+            .insert(AUTHORIZATION, HeaderValue::from_static("Bearer s3cret"));
+        let response = handle(&state, authorized).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // health checks do not require credentials
+        let response = handle(&state, request(Method::GET, "/health")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn stream_limit() {
+        use crate::samplers::cpu::CpuStatistic;
+
+        let state = state("stream_clients = 1");
+
+        let first = handle(&state, request(Method::GET, "/stream")).await;
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(first.headers()[CONTENT_TYPE], EVENT_STREAM_CONTENT_TYPE);
+
+        let response = handle(&state, request(Method::GET, "/stream")).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        // the slot is released once an update fails to reach the first
+        // client after it goes away
+        drop(first);
+        state.metrics.register(&CpuStatistic::UsageUser);
+        state
+            .metrics
+            .record_counter(&CpuStatistic::UsageUser, crate::Instant::now(), 10)
+            .unwrap();
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        let response = handle(&state, request(Method::GET, "/stream")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn stream_dropped() {
+        use crate::samplers::cpu::CpuStatistic;
+        use hyper::body::HttpBody;
+
+        let state = state("stream_buffer = 1");
+        state.metrics.register(&CpuStatistic::UsageUser);
+
+        let response = handle(&state, request(Method::GET, "/stream?prefix=cpu")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // the stream does not run until this task yields, so only the first
+        // of these readings fits in the buffer
+        let now = crate::Instant::now();
+        for (offset, value) in [10, 20, 30].into_iter().enumerate() {
+            state
+                .metrics
+                .record_counter(
+                    &CpuStatistic::UsageUser,
+                    now + crate::Duration::from_secs(offset as u64),
+                    value,
+                )
+                .unwrap();
+        }
+
+        let mut body = response.into_body();
+        let events = body.data().await.unwrap().unwrap();
+        let events = std::str::from_utf8(&events).unwrap();
+        assert!(events.starts_with("event: dropped\ndata: 2\n\n"));
+        assert!(events.contains("\"name\":\"cpu/usage/user\""));
+    }
+
+    #[tokio::test]
+    async fn stream_shutdown() {
+        use hyper::body::HttpBody;
+
+        let state = state("");
+        let response = handle(&state, request(Method::GET, "/stream")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // open streams end when the listeners shut down
+        state
+            .shutdown
+            .trigger(std::time::Instant::now() + Duration::from_secs(1));
+        let mut body = response.into_body();
+        assert!(body.data().await.is_none());
     }
 }
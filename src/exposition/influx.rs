@@ -0,0 +1,402 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::net::UdpSocket;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::common::shutdown::Shutdown;
+use crate::*;
+
+use crate::common::hostname;
+use crate::config::Config;
+use crate::exposition::{packets, Exporter, Filter, MetricsSnapshot, Pending, Sample, WriteError};
+
+/// Pushes metrics to InfluxDB using the line protocol, either over UDP or to
+/// the HTTP `/write` endpoint. Each sampler is a measurement, eg: `cpu`, with
+/// the remainder of the metric names as fields and labels as tags.
+pub struct Influx {
+    snapshot: MetricsSnapshot,
+    transport: Transport,
+    tags: Vec<(String, String)>,
+    interval: Duration,
+    pending: Pending<String>,
+}
+
+enum Transport {
+    Udp {
+        socket: UdpSocket,
+        address: String,
+        connected: bool,
+        max_packet_size: usize,
+    },
+    Http {
+        client: reqwest::blocking::Client,
+        url: String,
+        token: Option<String>,
+        timeout: Duration,
+    },
+}
+
+impl Influx {
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Self {
+        let influx = config.exposition().influx();
+        let timeout = Duration::from_millis(influx.timeout().try_into().unwrap());
+        let transport = match influx.address() {
+            Some(address) if address.starts_with("udp://") => {
+                let socket = match UdpSocket::bind("0.0.0.0:0") {
+                    Ok(socket) => socket,
+                    Err(e) => {
+                        fatal!("failed to open socket for influx: {}", e);
+                    }
+                };
+                // the socket is connected so that the address is only resolved
+                // once rather than for every packet
+                let address = address.trim_start_matches("udp://").to_string();
+                let connected = match socket.connect(&address) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        error!("failed to connect to influx at {}: {}", address, e);
+                        false
+                    }
+                };
+                Transport::Udp {
+                    socket,
+                    address,
+                    connected,
+                    max_packet_size: influx.max_packet_size(),
+                }
+            }
+            Some(address) if address.starts_with("http://") || address.starts_with("https://") => {
+                let client = match reqwest::blocking::ClientBuilder::new()
+                    .timeout(timeout)
+                    .build()
+                {
+                    Ok(client) => client,
+                    Err(e) => {
+                        fatal!("failed to create http client for influx: {}", e);
+                    }
+                };
+                Transport::Http {
+                    client,
+                    url: address.to_string(),
+                    token: influx.token().map(|t| t.to_string()),
+                    timeout,
+                }
+            }
+            Some(address) => {
+                fatal!(
+                    "influx address must start with udp://, http://, or https://: {}",
+                    address
+                );
+            }
+            None => {
+                fatal!("influx exposition requires an address");
+            }
+        };
+        let mut tags: Vec<(String, String)> = influx
+            .tags()
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        if influx.host_tag() {
+            if let Some(host) = hostname() {
+                tags.push(("host".to_string(), host));
+            }
+        }
+        Self {
+            snapshot: MetricsSnapshot::new(metrics, config.general().reading_suffix()),
+            transport,
+            tags,
+            interval: Duration::from_millis(influx.interval().try_into().unwrap()),
+            pending: Pending::new(
+                "influx",
+                "lines",
+                influx.batch_size(),
+                influx.retries(),
+                influx.buffer(),
+            ),
+        }
+    }
+}
+
+impl Transport {
+    fn write(&mut self, batch: &[String], shutdown: &Shutdown) -> Result<(), WriteError> {
+        match self {
+            Transport::Udp {
+                socket,
+                address,
+                connected,
+                max_packet_size,
+            } => {
+                if !*connected {
+                    socket
+                        .connect(address.as_str())
+                        .map_err(|e| WriteError::Retry(e.to_string()))?;
+                    *connected = true;
+                }
+                for packet in packets(batch, *max_packet_size) {
+                    if let Err(e) = socket.send(packet.as_bytes()) {
+                        // the address is resolved again on the next write, in
+                        // case it has changed
+                        *connected = false;
+                        return Err(WriteError::Retry(e.to_string()));
+                    }
+                }
+                Ok(())
+            }
+            Transport::Http {
+                client,
+                url,
+                token,
+                timeout,
+            } => {
+                let mut request = client
+                    .post(url.as_str())
+                    .timeout(shutdown.limit(*timeout))
+                    .body(batch.join("\n"));
+                if let Some(token) = token {
+                    request = request.header("Authorization", format!("Token {}", token));
+                }
+                let response = request
+                    .send()
+                    .map_err(|e| WriteError::Retry(e.to_string()))?;
+                let status = response.status();
+                if status.is_success() {
+                    Ok(())
+                } else if status.is_server_error() || status.as_u16() == 429 {
+                    Err(WriteError::Retry(format!("status: {}", status)))
+                } else {
+                    let body = response.text().unwrap_or_default();
+                    Err(WriteError::Rejected(format!("status: {} {}", status, body)))
+                }
+            }
+        }
+    }
+}
+
+impl Exporter for Influx {
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Snapshot the metrics and write them along with any batches which
+    /// previously failed. Batches which still fail after retrying are kept
+    /// for the next push, up to the buffer limit.
+    fn push(&mut self, shutdown: &Shutdown) {
+        self.snapshot.refresh();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|t| t.as_nanos() as u64)
+            .unwrap_or(0);
+        let samples = self.snapshot.samples(&Filter::default());
+        self.pending.push(&lines(&samples, &self.tags, timestamp));
+        let transport = &mut self.transport;
+        self.pending
+            .write(shutdown, |batch| transport.write(batch, shutdown));
+    }
+}
+
+/// A measurement and its sorted tags
+type Series = (String, Vec<(String, String)>);
+
+/// Renders samples as line protocol, with one line per measurement and set of
+/// tags. The measurement is the first component of the sample name and the
+/// field is the remainder, eg: `cpu/usage/user/count` is the `usage_user_count`
+/// field of the `cpu` measurement.
+pub(crate) fn lines(samples: &[Sample], tags: &[(String, String)], timestamp: u64) -> Vec<String> {
+    let mut series: BTreeMap<Series, Vec<String>> = BTreeMap::new();
+    for sample in samples {
+        let value = match field_value(&sample.value) {
+            Some(value) => value,
+            None => continue,
+        };
+        let (measurement, field) = sample
+            .name
+            .split_once('/')
+            .unwrap_or(("rezolus", &sample.name));
+        // labels take precedence over the configured tags
+        let mut series_tags: BTreeMap<String, String> = tags.iter().cloned().collect();
+        for (key, value) in sample.labels.iter() {
+            series_tags.insert(key.to_string(), value.to_string());
+        }
+        series
+            .entry((measurement.to_string(), series_tags.into_iter().collect()))
+            .or_default()
+            .push(format!("{}={}", escape(&field.replace('/', "_")), value));
+    }
+    series
+        .into_iter()
+        .map(|((measurement, tags), fields)| {
+            let mut line = escape_measurement(&measurement);
+            for (key, value) in tags.iter().filter(|(_, v)| !v.is_empty()) {
+                line += &format!(",{}={}", escape(key), escape(value));
+            }
+            format!("{} {} {}", line, fields.join(","), timestamp)
+        })
+        .collect()
+}
+
+/// Unsigned integers are written with the `u` suffix and signed integers with
+/// the `i` suffix, so that each field keeps a single type across its full
+/// range. Non finite floats are skipped as they can not be represented.
+fn field_value(value: &MetricValue) -> Option<String> {
+    match value {
+        MetricValue::Unsigned(value) => Some(format!("{}u", value)),
+        MetricValue::Signed(value) => Some(format!("{}i", value)),
+        MetricValue::Float(value) if value.is_finite() => Some(format!("{:?}", value)),
+        MetricValue::Float(_) => None,
+        MetricValue::Histogram(histogram) => Some(format!("{}u", histogram.count())),
+    }
+}
+
+/// Escapes a tag key, tag value, or field key
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+        .replace('\n', "\\n")
+}
+
+fn escape_measurement(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(' ', "\\ ")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exposition::tests::stand_in;
+    use crate::samplers::cpu::CpuStatistic;
+
+    fn metrics() -> Arc<Metrics> {
+        let metrics = Arc::new(Metrics::new());
+        for cpu in 0..2 {
+            let usage = Labeled::new(CpuStatistic::UsageUser, Labels::new().with("cpu", cpu));
+            metrics.add_output(&usage, Output::Reading);
+            metrics
+                .record_counter(&usage, crate::Instant::now(), 1000 * (cpu + 1))
+                .unwrap();
+        }
+        metrics
+    }
+
+    fn config(port: u16) -> Config {
+        toml::from_str(&format!(
+            "[exposition.influx]\n\
+             enabled = true\n\
+             address = \"http://127.0.0.1:{}/write?db=rezolus\"\n\
+             host_tag = false\n\
+             retries = 1\n\
+             tags = {{ dc = \"east\" }}\n",
+            port
+        ))
+        .unwrap()
+    }
+
+    fn sample(name: &str, labels: Labels, value: MetricValue) -> Sample {
+        Sample {
+            name: name.to_string(),
+            labels,
+            counter: false,
+            value,
+        }
+    }
+
+    #[test]
+    fn measurements() {
+        let tags = vec![
+            ("dc".to_string(), "east".to_string()),
+            ("role".to_string(), "web".to_string()),
+        ];
+        let samples = [
+            sample(
+                "cpu/usage/user",
+                Labels::new().with("cpu", 0),
+                MetricValue::Unsigned(1000),
+            ),
+            sample(
+                "cpu/usage/system",
+                Labels::new().with("cpu", 0),
+                MetricValue::Unsigned(500),
+            ),
+            sample("ntp/offset", Labels::new(), MetricValue::Signed(-25)),
+            // labels take precedence over the configured tags
+            sample(
+                "disk/read/bytes",
+                Labels::new()
+                    .with("device", "nvme0n1")
+                    .with("role", "data disk"),
+                MetricValue::Unsigned(4096),
+            ),
+        ];
+        assert_eq!(
+            lines(&samples, &tags, 1655856000000000000),
+            [
+                "cpu,cpu=0,dc=east,role=web usage_user=1000u,usage_system=500u 1655856000000000000",
+                "disk,dc=east,device=nvme0n1,role=data\\ disk read_bytes=4096u 1655856000000000000",
+                "ntp,dc=east,role=web offset=-25i 1655856000000000000",
+            ]
+        );
+    }
+
+    #[test]
+    fn retried() {
+        // the first write fails so that it is retried
+        let (port, server) = stand_in(&["503 Service Unavailable", "204 No Content"]);
+        let mut influx = Influx::new(&config(port), metrics());
+        influx.push(&Shutdown::new());
+        assert!(influx.pending.batches.is_empty());
+
+        let bodies = server.join().unwrap();
+        assert_eq!(bodies.len(), 2);
+        assert_eq!(bodies[0], bodies[1]);
+        let body = String::from_utf8(bodies[0].clone()).unwrap();
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("cpu,cpu=0,dc=east usage_user_count=1000u "));
+        assert!(lines[1].starts_with("cpu,cpu=1,dc=east usage_user_count=2000u "));
+    }
+
+    #[test]
+    fn rejected() {
+        // a rejected write is dropped rather than retried or kept for the next
+        // push
+        let (port, server) = stand_in(&["400 Bad Request", "204 No Content"]);
+        let mut influx = Influx::new(&config(port), metrics());
+        influx.push(&Shutdown::new());
+        assert!(influx.pending.batches.is_empty());
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        influx.push(&Shutdown::new());
+
+        let bodies = server.join().unwrap();
+        assert_eq!(bodies.len(), 2);
+        assert_ne!(bodies[0], bodies[1]);
+    }
+
+    #[test]
+    fn field_types() {
+        // unsigned values keep their type and value across the full range
+        assert_eq!(
+            field_value(&MetricValue::Unsigned(u64::MAX)),
+            Some(format!("{}u", u64::MAX))
+        );
+        assert_eq!(
+            field_value(&MetricValue::Unsigned(42)),
+            Some("42u".to_string())
+        );
+        assert_eq!(
+            field_value(&MetricValue::Signed(-42)),
+            Some("-42i".to_string())
+        );
+        assert_eq!(field_value(&MetricValue::Float(f64::NAN)), None);
+    }
+}
@@ -2,46 +2,287 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
+use std::collections::VecDeque;
 use std::convert::TryInto;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use crate::common::shutdown::Shutdown;
 use crate::*;
+use kafka::client::{Compression, RequiredAcks};
 use kafka::producer::{Producer, Record};
 
-use crate::config::Config;
-use crate::exposition::MetricsSnapshot;
+use crate::common::hostname;
+use crate::config::{Config, KafkaAcks, KafkaCompression, KafkaFormat};
+use crate::exposition::{influx, Exporter, Filter, MetricsSnapshot};
+use crate::samplers::rezolus::RezolusStatistic;
 
+/// Initial delay before reconnecting after a failed connection or send
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Sends a message with the metrics to a kafka topic each interval. Messages
+/// are buffered while the brokers are unavailable, up to a limit after which
+/// the oldest are dropped, and the producer is recreated with an exponential
+/// backoff. Failures are counted in the `rezolus/exposition/kafka` statistics,
+/// which are registered whether or not the `rezolus` sampler is enabled.
 pub struct KafkaProducer {
     snapshot: MetricsSnapshot,
-    producer: Producer,
+    metrics: Arc<Metrics>,
+    hosts: Vec<String>,
     topic: String,
+    format: KafkaFormat,
+    host: String,
+    key_by_host: bool,
+    compression: KafkaCompression,
+    acks: KafkaAcks,
+    ack_timeout: Duration,
     interval: Duration,
+    producer: Option<Producer>,
+    buffer: VecDeque<Vec<u8>>,
+    buffer_limit: usize,
+    backoff: Duration,
+    max_backoff: Duration,
+    next_connect: Instant,
+    errors: u64,
+    dropped: u64,
 }
 
 impl KafkaProducer {
-    pub fn new(config: Arc<Config>, metrics: Arc<Metrics>) -> Self {
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Self {
+        let kafka = config.exposition().kafka();
+        if kafka.hosts().is_empty() {
+            fatal!("kafka exposition requires at least one host");
+        }
+        let topic = match kafka.topic() {
+            Some(topic) => topic,
+            None => {
+                fatal!("kafka exposition requires a topic");
+            }
+        };
+        for statistic in [
+            RezolusStatistic::KafkaErrors,
+            RezolusStatistic::KafkaDropped,
+        ] {
+            metrics.add_output(&statistic, Output::Reading);
+        }
         Self {
-            snapshot: MetricsSnapshot::new(metrics, config.general().reading_suffix()),
-            producer: Producer::from_hosts(config.exposition().kafka().hosts())
-                .create()
-                .unwrap(),
-            topic: config.exposition().kafka().topic().unwrap(),
-            interval: Duration::from_millis(
-                config.exposition().kafka().interval().try_into().unwrap(),
-            ),
+            snapshot: MetricsSnapshot::new(metrics.clone(), config.general().reading_suffix()),
+            metrics,
+            hosts: kafka.hosts(),
+            topic,
+            format: kafka.format(),
+            host: hostname().unwrap_or_else(|| "unknown".to_string()),
+            key_by_host: kafka.key_by_host(),
+            compression: kafka.compression(),
+            acks: kafka.acks(),
+            ack_timeout: Duration::from_millis(kafka.ack_timeout().try_into().unwrap()),
+            interval: Duration::from_millis(kafka.interval().try_into().unwrap()),
+            producer: None,
+            buffer: VecDeque::new(),
+            buffer_limit: kafka.buffer().max(1),
+            backoff: MIN_BACKOFF,
+            max_backoff: Duration::from_millis(kafka.max_backoff().try_into().unwrap()),
+            next_connect: Instant::now(),
+            errors: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Send the buffered messages in order, creating the producer first if
+    /// needed. On failure the remaining messages stay buffered and the
+    /// producer is recreated after a backoff, unless shutting down. Once
+    /// shutdown has been triggered, the acks are only waited for until the
+    /// deadline and no more messages are sent after it.
+    fn flush(&mut self, shutdown: &Shutdown) {
+        if self.producer.is_none() {
+            if shutdown.is_triggered() {
+                debug!("not reconnecting to kafka during shutdown");
+                return;
+            }
+            if Instant::now() < self.next_connect {
+                return;
+            }
+            match self.connect(shutdown.limit(self.ack_timeout)) {
+                Ok(producer) => {
+                    debug!("connected to kafka");
+                    self.producer = Some(producer);
+                }
+                Err(e) => {
+                    error!("failed to connect to kafka: {}", e);
+                    self.failed();
+                    return;
+                }
+            }
+        }
+        while let Some(message) = self.buffer.front() {
+            if shutdown.remaining() == Some(Duration::ZERO) {
+                warn!(
+                    "shutdown deadline reached, {} kafka messages unsent",
+                    self.buffer.len()
+                );
+                return;
+            }
+            let producer = match self.producer.as_mut() {
+                Some(producer) => producer,
+                None => return,
+            };
+            let result = if self.key_by_host {
+                producer.send(&Record::from_key_value(
+                    &self.topic,
+                    self.host.as_bytes(),
+                    message.as_slice(),
+                ))
+            } else {
+                producer.send(&Record::from_value(&self.topic, message.as_slice()))
+            };
+            match result {
+                Ok(()) => {
+                    self.buffer.pop_front();
+                    self.backoff = MIN_BACKOFF;
+                }
+                Err(e) => {
+                    error!("failed to send to kafka: {}", e);
+                    self.producer = None;
+                    self.failed();
+                    return;
+                }
+            }
         }
     }
 
-    pub fn run(&mut self) {
-        let start = Instant::now();
+    fn failed(&mut self) {
+        self.errors += 1;
+        self.next_connect = Instant::now() + self.backoff;
+        self.backoff = (self.backoff * 2).min(self.max_backoff);
+    }
+
+    fn connect(&self, ack_timeout: Duration) -> Result<Producer, kafka::error::Error> {
+        Producer::from_hosts(self.hosts.clone())
+            .with_client_id(crate::config::NAME.to_string())
+            .with_compression(match self.compression {
+                KafkaCompression::None => Compression::NONE,
+                KafkaCompression::Gzip => Compression::GZIP,
+                KafkaCompression::Snappy => Compression::SNAPPY,
+            })
+            .with_required_acks(match self.acks {
+                KafkaAcks::None => RequiredAcks::None,
+                KafkaAcks::One => RequiredAcks::One,
+                KafkaAcks::All => RequiredAcks::All,
+            })
+            .with_ack_timeout(ack_timeout)
+            .create()
+    }
+}
+
+impl Exporter for KafkaProducer {
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Snapshot the metrics and send them after any buffered messages,
+    /// recording the errors and drops.
+    fn push(&mut self, shutdown: &Shutdown) {
         self.snapshot.refresh();
-        let _ = self
-            .producer
-            .send(&Record::from_value(&self.topic, self.snapshot.json(false)));
-        let stop = Instant::now();
-        if start + self.interval > stop {
-            std::thread::sleep(self.interval - (stop - start));
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        self.buffer
+            .push_back(payload(&self.snapshot, self.format, &self.host, timestamp));
+        if self.buffer.len() > self.buffer_limit {
+            let dropped = self.buffer.len() - self.buffer_limit;
+            self.buffer.drain(..dropped);
+            self.dropped += dropped as u64;
+            warn!("kafka buffer full, dropped {} messages", dropped);
         }
+        self.flush(shutdown);
+
+        let now = crate::Instant::now();
+        for (statistic, value) in [
+            (RezolusStatistic::KafkaErrors, self.errors),
+            (RezolusStatistic::KafkaDropped, self.dropped),
+        ] {
+            if let Err(e) = self.metrics.record_counter(&statistic, now, value) {
+                debug!("failed to record {}: {}", statistic.name(), e);
+            }
+        }
+    }
+}
+
+/// Renders the snapshot as a message in the configured format
+pub(crate) fn payload(
+    snapshot: &MetricsSnapshot,
+    format: KafkaFormat,
+    host: &str,
+    timestamp: Duration,
+) -> Vec<u8> {
+    let filter = Filter::default();
+    match format {
+        KafkaFormat::Flat => snapshot.json(false, false, &filter).into_bytes(),
+        KafkaFormat::Json => snapshot.json_record(host, timestamp).into_bytes(),
+        KafkaFormat::LineProtocol => influx::lines(
+            &snapshot.samples(&filter),
+            &[("host".to_string(), host.to_string())],
+            timestamp.as_nanos() as u64,
+        )
+        .join("\n")
+        .into_bytes(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::samplers::cpu::CpuStatistic;
+
+    fn snapshot() -> MetricsSnapshot {
+        let metrics = Arc::new(Metrics::new());
+        let usage = Labeled::new(CpuStatistic::UsageUser, Labels::new().with("cpu", 0));
+        metrics.add_output(&usage, Output::Reading);
+        metrics
+            .record_counter(&usage, crate::Instant::now(), 1000)
+            .unwrap();
+        metrics.add_output(&CpuStatistic::Frequency, Output::Reading);
+        metrics
+            .record_gauge(
+                &CpuStatistic::Frequency,
+                crate::Instant::now(),
+                2_400_000_000,
+            )
+            .unwrap();
+        let mut snapshot = MetricsSnapshot::new(metrics, None);
+        snapshot.refresh();
+        snapshot
+    }
+
+    #[test]
+    fn formats() {
+        let snapshot = snapshot();
+        let timestamp = Duration::from_millis(1655856000123);
+
+        let message = payload(&snapshot, KafkaFormat::Flat, "myhost", timestamp);
+        let message = json::parse(std::str::from_utf8(&message).unwrap()).unwrap();
+        assert_eq!(message["cpu/frequency"], 2_400_000_000_u64);
+
+        let message = payload(&snapshot, KafkaFormat::Json, "myhost", timestamp);
+        let message = json::parse(std::str::from_utf8(&message).unwrap()).unwrap();
+        assert_eq!(message["host"], "myhost");
+        assert_eq!(message["timestamp"], 1655856000123_u64);
+        assert_eq!(message["metrics"]["cpu/frequency"], 2_400_000_000_u64);
+
+        // line protocol is timestamped in nanoseconds and tagged with the host
+        let message = payload(&snapshot, KafkaFormat::LineProtocol, "myhost", timestamp);
+        let mut lines: Vec<String> = String::from_utf8(message)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+        lines.sort();
+        assert_eq!(
+            lines,
+            [
+                "cpu,cpu=0,host=myhost usage_user=1000u 1655856000123000000",
+                "cpu,host=myhost frequency=2400000000u 1655856000123000000",
+            ]
+        );
     }
 }
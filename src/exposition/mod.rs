@@ -2,25 +2,188 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::Instant;
 
+use crate::common::shutdown::Shutdown;
 use crate::*;
 
+mod auth;
+mod file;
+mod filter;
+mod graphite;
 mod http;
+mod influx;
 #[cfg(feature = "push_kafka")]
 mod kafka;
+mod otlp;
+mod remote_write;
+mod statsd;
+mod tls;
+mod unix;
 
+pub use self::file::FileSink;
+pub use self::filter::Filter;
+pub use self::graphite::Graphite;
 pub use self::http::Http;
+pub use self::influx::Influx;
 #[cfg(feature = "push_kafka")]
 pub use self::kafka::KafkaProducer;
+pub use self::otlp::Otlp;
+pub use self::remote_write::RemoteWrite;
+pub use self::statsd::StatsD;
+
+/// A single value from a snapshot. Names follow the human readable format,
+/// eg: `cpu/usage/user/count` or `disk/read/latency/histogram/p99`, with the
+/// labels kept separate. Histograms are flattened into cumulative bucket counts
+/// named by their upper bound, a count, and a sum.
+pub struct Sample {
+    pub name: String,
+    pub labels: Labels,
+    /// Set for the reading of a counter, which only increases until it is
+    /// reset
+    pub counter: bool,
+    pub value: MetricValue,
+}
+
+/// A push exporter, which is run on its own thread and sends a snapshot of the
+/// metrics on an interval
+pub trait Exporter: Send + 'static {
+    /// The time from the start of one push to the start of the next
+    fn interval(&self) -> std::time::Duration;
+
+    /// Snapshot the metrics and send them. Once shutdown has been triggered,
+    /// failed sends are not retried and any timeouts are limited to the time
+    /// left until the deadline.
+    fn push(&mut self, shutdown: &Shutdown);
+}
+
+/// Initial delay before retrying a failed write, doubled for each attempt
+const RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// The result of a failed write by a push exporter
+enum WriteError {
+    /// The write may succeed if retried
+    Retry(String),
+    /// The write was rejected and will not succeed if retried
+    Rejected(String),
+}
+
+/// Attempt a write, retrying up to `retries` times with an exponential
+/// backoff while it fails with an error which may succeed if retried. A write
+/// is not retried once shutdown has been triggered.
+fn write_with_retry<F>(
+    name: &str,
+    retries: usize,
+    shutdown: &Shutdown,
+    mut write: F,
+) -> Result<(), WriteError>
+where
+    F: FnMut() -> Result<(), WriteError>,
+{
+    let mut backoff = RETRY_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        match write() {
+            Err(WriteError::Retry(e)) if attempt < retries && !shutdown.is_triggered() => {
+                debug!("retrying {} write after error: {}", name, e);
+                shutdown.wait_timeout(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Batches which are waiting to be written by a push exporter, oldest first.
+/// Batches which fail are kept so that they are written in order once the
+/// endpoint recovers, up to a limit on the number of items after which the
+/// oldest batches are dropped.
+struct Pending<T> {
+    name: &'static str,
+    items: &'static str,
+    batches: VecDeque<Vec<T>>,
+    batch_size: usize,
+    retries: usize,
+    limit: usize,
+}
+
+impl<T: Clone> Pending<T> {
+    fn new(
+        name: &'static str,
+        items: &'static str,
+        batch_size: usize,
+        retries: usize,
+        limit: usize,
+    ) -> Self {
+        Self {
+            name,
+            items,
+            batches: VecDeque::new(),
+            batch_size: batch_size.max(1),
+            retries,
+            limit,
+        }
+    }
+
+    /// Queue the items in batches after those which are already pending
+    fn push(&mut self, items: &[T]) {
+        for batch in items.chunks(self.batch_size) {
+            self.batches.push_back(batch.to_vec());
+        }
+        let mut buffered: usize = self.batches.iter().map(|b| b.len()).sum();
+        while buffered > self.limit {
+            if let Some(batch) = self.batches.pop_front() {
+                buffered -= batch.len();
+                warn!(
+                    "{} buffer full, dropped {} {}",
+                    self.name,
+                    batch.len(),
+                    self.items
+                );
+            }
+        }
+    }
+
+    /// Write the pending batches in order, retrying each which fails. Batches
+    /// which are rejected are dropped, and writing stops at the first batch
+    /// which still fails so that it is written first on the next push.
+    fn write<F>(&mut self, shutdown: &Shutdown, mut write: F)
+    where
+        F: FnMut(&[T]) -> Result<(), WriteError>,
+    {
+        while let Some(batch) = self.batches.front() {
+            match write_with_retry(self.name, self.retries, shutdown, || write(batch)) {
+                Ok(()) => {
+                    self.batches.pop_front();
+                }
+                Err(WriteError::Rejected(e)) => {
+                    error!(
+                        "{} rejected {} {}: {}",
+                        self.name,
+                        batch.len(),
+                        self.items,
+                        e
+                    );
+                    self.batches.pop_front();
+                }
+                Err(WriteError::Retry(e)) => {
+                    error!("failed to write to {}: {}", self.name, e);
+                    break;
+                }
+            }
+        }
+    }
+}
 
 pub struct MetricsSnapshot {
     metrics: Arc<Metrics>,
-    snapshot: HashMap<Metric, u64>,
+    snapshot: HashMap<Metric, MetricValue>,
     refreshed: Instant,
     count_label: Option<String>,
+    histograms: bool,
 }
 
 impl<'a> MetricsSnapshot {
@@ -30,42 +193,247 @@ impl<'a> MetricsSnapshot {
             snapshot: HashMap::new(),
             refreshed: Instant::now(),
             count_label: count_label.map(std::string::ToString::to_string),
+            histograms: false,
         }
     }
 
+    /// Include the histogram of every distribution statistic in the snapshot,
+    /// for formats which always export them
+    pub fn with_histograms(mut self) -> Self {
+        self.histograms = true;
+        self
+    }
+
     pub fn refresh(&mut self) {
         self.snapshot = self.metrics.snapshot();
+        if self.histograms {
+            for (metric, value) in self.metrics.histograms() {
+                self.snapshot.entry(metric).or_insert(value);
+            }
+        }
         self.refreshed = Instant::now();
     }
 
-    pub fn prometheus(&self) -> String {
-        let mut data = Vec::new();
-        for (metric, value) in &self.snapshot {
-            let label = metric.statistic().name();
+    /// A new snapshot of the same metrics, leaving this one unchanged
+    pub fn refreshed(&self) -> Self {
+        let mut snapshot = Self::new(self.metrics.clone(), self.count_label.as_deref());
+        snapshot.histograms = self.histograms;
+        snapshot.refresh();
+        snapshot
+    }
+
+    /// The moments of a statistic across the summary window. These are read
+    /// from the metrics when called rather than from the snapshot.
+    pub fn moments(&self, statistic: &dyn Statistic) -> Option<Moments> {
+        self.metrics.moments(statistic).ok()
+    }
+
+    /// The time since the snapshot was last refreshed
+    pub fn age(&self) -> std::time::Duration {
+        self.refreshed.elapsed()
+    }
+
+    /// The metrics in the snapshot which are selected by the filter
+    fn filtered<'b>(
+        &'b self,
+        filter: &'b Filter,
+    ) -> impl Iterator<Item = (&'b Metric, &'b MetricValue)> {
+        self.snapshot
+            .iter()
+            .filter(move |(metric, _)| filter.matches(metric))
+    }
+
+    /// Flattens the snapshot into individual samples for the push exporters,
+    /// sorted by name and labels.
+    pub fn samples(&self, filter: &Filter) -> Vec<Sample> {
+        let mut samples = Vec::new();
+        for (metric, value) in self.filtered(filter) {
+            let statistic = metric.statistic();
+            let name = statistic.name();
+            let labels = statistic.labels();
             let output = metric.output();
+            let mut push = |name: String, value: MetricValue| {
+                samples.push(Sample {
+                    name,
+                    labels: labels.clone(),
+                    counter: matches!(output, Output::Reading)
+                        && statistic.source() == Source::Counter,
+                    value,
+                })
+            };
+            match (output, value) {
+                (Output::Reading, _) => {
+                    let name = match self.count_label {
+                        Some(ref count_label) => format!("{}/{}", name, count_label),
+                        None => name.to_string(),
+                    };
+                    push(name, value.clone());
+                }
+                (Output::Percentile(percentile), _) => {
+                    push(
+                        format!("{}/histogram/p{:02}", name, percentile),
+                        value.clone(),
+                    );
+                }
+                (Output::Histogram, MetricValue::Histogram(histogram)) => {
+                    for bucket in histogram.buckets() {
+                        push(
+                            format!("{}/histogram/bucket/{}", name, bucket.upper()),
+                            MetricValue::Unsigned(bucket.count()),
+                        );
+                    }
+                    push(
+                        format!("{}/histogram/count", name),
+                        MetricValue::Unsigned(histogram.count()),
+                    );
+                    push(
+                        format!("{}/histogram/sum", name),
+                        MetricValue::Unsigned(histogram.sum()),
+                    );
+                }
+                (Output::Histogram, _) => {}
+                (_, _) => {
+                    push(
+                        format!("{}/histogram/{}", name, summary_name(output)),
+                        value.clone(),
+                    );
+                }
+            }
+        }
+        samples.sort_by(|a, b| (&a.name, &a.labels).cmp(&(&b.name, &b.labels)));
+        samples
+    }
+
+    /// Renders the snapshot in the Prometheus text format (version 0.0.4)
+    pub fn prometheus(&self, filter: &Filter) -> String {
+        self.prometheus_text(false, filter)
+    }
+
+    /// Renders the snapshot in the OpenMetrics text format (version 1.0.0)
+    pub fn openmetrics(&self, filter: &Filter) -> String {
+        self.prometheus_text(true, filter)
+    }
+
+    fn prometheus_text(&self, openmetrics: bool, filter: &Filter) -> String {
+        let mut families: BTreeMap<String, Family> = BTreeMap::new();
+        for (metric, value) in self.filtered(filter) {
+            let statistic = metric.statistic();
+            let name = prometheus_name(statistic.name());
+            let labels = prometheus_labels(statistic.labels());
+            let output = metric.output();
+            let family_name = match output {
+                Output::Reading if statistic.source() == Source::Counter => {
+                    // counters are named without the suffix in OpenMetrics,
+                    // but the suffix is part of the name in Prometheus
+                    let name = name.strip_suffix("_total").unwrap_or(&name);
+                    if openmetrics {
+                        name.to_string()
+                    } else {
+                        format!("{}_total", name)
+                    }
+                }
+                Output::Reading | Output::Histogram => name.clone(),
+                Output::Percentile(_) => summary_family_name(&name, statistic),
+                _ => format!("{}_{}", name, summary_name(output)),
+            };
+            let family = families
+                .entry(family_name.clone())
+                .or_insert_with(|| Family::new(&family_name, statistic, output, openmetrics));
             match output {
                 Output::Reading => {
-                    data.push(format!("# TYPE {} gauge\n{} {}", label, label, value));
+                    let sample = if statistic.source() == Source::Counter && openmetrics {
+                        format!("{}_total", family_name)
+                    } else {
+                        family_name
+                    };
+                    family.push(
+                        &labels,
+                        0,
+                        vec![format!("{}{} {}", sample, render_labels(&labels), value)],
+                    );
                 }
                 Output::Percentile(percentile) => {
-                    data.push(format!(
-                        "# TYPE {} gauge\n{}{{percentile=\"{:02}\"}} {}",
-                        label, label, percentile, value
-                    ));
+                    let mut sample_labels = labels.clone();
+                    sample_labels.push(("quantile".to_string(), quantile(percentile)));
+                    family.push(
+                        &labels,
+                        (percentile * 1_000_000.0) as u64,
+                        vec![format!(
+                            "{}{} {}",
+                            family_name,
+                            render_labels(&sample_labels),
+                            value
+                        )],
+                    );
+                }
+                Output::Min | Output::Max | Output::Mean | Output::Stddev | Output::Samples => {
+                    family.push(
+                        &labels,
+                        0,
+                        vec![format!(
+                            "{}{} {}",
+                            family_name,
+                            render_labels(&labels),
+                            value
+                        )],
+                    );
+                }
+                Output::Histogram => {
+                    if let MetricValue::Histogram(histogram) = value {
+                        let mut lines = Vec::new();
+                        for bucket in histogram.buckets() {
+                            let mut bucket_labels = labels.clone();
+                            bucket_labels.push(("le".to_string(), bucket.upper().to_string()));
+                            lines.push(format!(
+                                "{}_bucket{} {}",
+                                family_name,
+                                render_labels(&bucket_labels),
+                                bucket.count()
+                            ));
+                        }
+                        let mut inf = labels.clone();
+                        inf.push(("le".to_string(), "+Inf".to_string()));
+                        lines.push(format!(
+                            "{}_bucket{} {}",
+                            family_name,
+                            render_labels(&inf),
+                            histogram.count()
+                        ));
+                        lines.push(format!(
+                            "{}_sum{} {}",
+                            family_name,
+                            render_labels(&labels),
+                            histogram.sum()
+                        ));
+                        lines.push(format!(
+                            "{}_count{} {}",
+                            family_name,
+                            render_labels(&labels),
+                            histogram.count()
+                        ));
+                        family.push(&labels, 0, lines);
+                    }
                 }
             }
         }
-        data.sort();
-        let mut content = data.join("\n");
-        content += "\n";
-        let parts: Vec<&str> = content.split('/').collect();
-        parts.join("_")
+        let mut content = String::new();
+        for family in families.values_mut() {
+            content += &family.render();
+        }
+        if openmetrics {
+            content += "# EOF\n";
+        }
+        content
     }
 
-    pub fn human(&self) -> String {
+    pub fn human(&self, filter: &Filter) -> String {
         let mut data = Vec::new();
-        for (metric, value) in &self.snapshot {
-            let label = metric.statistic().name();
+        for (metric, value) in self.filtered(filter) {
+            let label = format!(
+                "{}{}",
+                metric.statistic().name(),
+                labels_suffix(metric.statistic().labels())
+            );
             let output = metric.output();
             match output {
                 Output::Reading => {
@@ -78,6 +446,30 @@ impl<'a> MetricsSnapshot {
                 Output::Percentile(percentile) => {
                     data.push(format!("{}/histogram/p{:02}: {}", label, percentile, value));
                 }
+                Output::Min | Output::Max | Output::Mean | Output::Stddev | Output::Samples => {
+                    data.push(format!(
+                        "{}/histogram/{}: {}",
+                        label,
+                        summary_name(output),
+                        value
+                    ));
+                }
+                Output::Histogram => {
+                    if let MetricValue::Histogram(histogram) = value {
+                        let buckets: Vec<String> = histogram
+                            .buckets()
+                            .iter()
+                            .map(|b| format!("{}:{}", b.upper(), b.count()))
+                            .collect();
+                        data.push(format!(
+                            "{}/histogram/buckets: {}",
+                            label,
+                            buckets.join(" ")
+                        ));
+                        data.push(format!("{}/histogram/count: {}", label, histogram.count()));
+                        data.push(format!("{}/histogram/sum: {}", label, histogram.sum()));
+                    }
+                }
             }
         }
         data.sort();
@@ -86,32 +478,71 @@ impl<'a> MetricsSnapshot {
         content
     }
 
-    fn json(&self, pretty: bool) -> String {
+    fn json(&self, pretty: bool, metadata: bool, filter: &Filter) -> String {
         let mut head = "{".to_owned();
         if pretty {
             head += "\n  ";
         }
         let mut data = Vec::new();
-        for (metric, value) in &self.snapshot {
-            let label = metric.statistic().name();
+        // names and label values may contain characters which must be escaped
+        // within a json key
+        let mut push = |key: String, value: String| {
+            data.push(format!("{}: {}", json::stringify(key), value));
+        };
+        for (metric, value) in self.filtered(filter) {
+            let label = format!(
+                "{}{}",
+                metric.statistic().name(),
+                labels_suffix(metric.statistic().labels())
+            );
             let output = metric.output();
             match output {
                 Output::Reading => {
                     if let Some(ref count_label) = self.count_label {
-                        data.push(format!("\"{}/{}\": {}", label, count_label, value));
+                        push(format!("{}/{}", label, count_label), json_value(value));
                     } else {
-                        data.push(format!("\"{}\": {}", label, value));
+                        push(label, json_value(value));
                     }
                 }
                 Output::Percentile(percentile) => {
-                    data.push(format!(
-                        "\"{}/histogram/p{:02}\": {}",
-                        label, percentile, value
-                    ));
+                    push(
+                        format!("{}/histogram/p{:02}", label, percentile),
+                        json_value(value),
+                    );
+                }
+                Output::Min | Output::Max | Output::Mean | Output::Stddev | Output::Samples => {
+                    push(
+                        format!("{}/histogram/{}", label, summary_name(output)),
+                        json_value(value),
+                    );
+                }
+                Output::Histogram => {
+                    if let MetricValue::Histogram(histogram) = value {
+                        let buckets: Vec<String> = histogram
+                            .buckets()
+                            .iter()
+                            .map(|b| format!("[{},{}]", b.upper(), b.count()))
+                            .collect();
+                        push(
+                            format!("{}/histogram/buckets", label),
+                            format!("[{}]", buckets.join(",")),
+                        );
+                        push(
+                            format!("{}/histogram/count", label),
+                            histogram.count().to_string(),
+                        );
+                        push(
+                            format!("{}/histogram/sum", label),
+                            histogram.sum().to_string(),
+                        );
+                    }
                 }
             }
         }
         data.sort();
+        if metadata {
+            data.push(self.json_metadata(filter));
+        }
         let body = if pretty {
             data.join(",\n  ")
         } else {
@@ -125,4 +556,479 @@ impl<'a> MetricsSnapshot {
         content += "}";
         content
     }
+
+    /// Renders a single line JSON object with the hostname, the timestamp in
+    /// milliseconds since the unix epoch, and the flat metrics object under
+    /// `metrics`
+    fn json_record(&self, host: &str, timestamp: std::time::Duration) -> String {
+        format!(
+            "{{\"host\":{},\"timestamp\":{},\"metrics\":{}}}",
+            json::stringify(host),
+            timestamp.as_millis(),
+            self.json(false, false, &Filter::default())
+        )
+    }
+
+    /// Renders a block which maps statistic names to their unit and
+    /// description, for the statistics in the snapshot which have them
+    fn json_metadata(&self, filter: &Filter) -> String {
+        let mut statistics = BTreeMap::new();
+        for (metric, _) in self.filtered(filter) {
+            let statistic = metric.statistic();
+            let mut fields = Vec::new();
+            if let Some(unit) = statistic.unit() {
+                fields.push(format!("\"unit\":{}", json::stringify(unit)));
+            }
+            if let Some(description) = statistic.description() {
+                fields.push(format!("\"description\":{}", json::stringify(description)));
+            }
+            if !fields.is_empty() {
+                statistics.insert(statistic.name().to_string(), fields.join(","));
+            }
+        }
+        let statistics: Vec<String> = statistics
+            .iter()
+            .map(|(name, fields)| format!("{}:{{{}}}", json::stringify(name.as_str()), fields))
+            .collect();
+        format!("\"metadata\": {{{}}}", statistics.join(","))
+    }
+}
+
+/// The name used for summary outputs, which is appended to the statistic name
+fn summary_name(output: Output) -> &'static str {
+    match output {
+        Output::Min => "min",
+        Output::Max => "max",
+        Output::Mean => "mean",
+        Output::Stddev => "stddev",
+        Output::Samples => "samples",
+        _ => unreachable!(),
+    }
+}
+
+/// Converts a single value to floating point, which is the only numeric type
+/// in some formats. Histograms have no single value.
+fn float_value(value: &MetricValue) -> Option<f64> {
+    match value {
+        MetricValue::Unsigned(value) => Some(*value as f64),
+        MetricValue::Signed(value) => Some(*value as f64),
+        MetricValue::Float(value) => Some(*value),
+        MetricValue::Histogram(_) => None,
+    }
+}
+
+/// Renders a value for the JSON format. JSON has no representation for non
+/// finite numbers, so they are rendered as `null`.
+fn json_value(value: &MetricValue) -> String {
+    match value {
+        MetricValue::Float(value) if !value.is_finite() => "null".to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// A group of samples which share a name, type, and metadata. Prometheus
+/// requires all the samples of a family to be contiguous and to follow the
+/// `# TYPE` line for the family.
+struct Family {
+    metadata: String,
+    samples: Vec<((String, u64), Vec<String>)>,
+}
+
+impl Family {
+    fn new(name: &str, statistic: &dyn Statistic, output: Output, openmetrics: bool) -> Self {
+        let kind = match output {
+            Output::Reading if statistic.source() == Source::Counter => "counter",
+            Output::Percentile(_) => "summary",
+            Output::Histogram => "histogram",
+            _ => "gauge",
+        };
+        // summaries of counters are secondly rates, and sample counts are
+        // unitless, so the unit of the statistic only applies to some outputs
+        let unit = match output {
+            Output::Reading | Output::Histogram => statistic.unit(),
+            Output::Samples => None,
+            _ => {
+                if statistic.source() == Source::Counter {
+                    None
+                } else {
+                    statistic.unit()
+                }
+            }
+        };
+        // OpenMetrics requires the unit to be a suffix of the name
+        let unit = if openmetrics {
+            unit.filter(|unit| name.ends_with(&format!("_{}", unit)))
+        } else {
+            unit
+        };
+        let mut metadata = String::new();
+        if let Some(description) = statistic.description() {
+            let mut description = description.replace('\\', "\\\\").replace('\n', "\\n");
+            if openmetrics {
+                description = description.replace('"', "\\\"");
+            }
+            metadata += &format!("# HELP {} {}\n", name, description);
+        }
+        metadata += &format!("# TYPE {} {}\n", name, kind);
+        if let Some(unit) = unit {
+            metadata += &format!("# UNIT {} {}\n", name, unit);
+        }
+        Self {
+            metadata,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Add the lines for a sample. Samples are ordered by their labels, and
+    /// then by the provided order, which keeps the quantiles of a summary
+    /// together and in increasing order.
+    fn push(&mut self, labels: &[(String, String)], order: u64, lines: Vec<String>) {
+        self.samples.push(((render_labels(labels), order), lines));
+    }
+
+    fn render(&mut self) -> String {
+        self.samples.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut content = self.metadata.clone();
+        for (_, lines) in &self.samples {
+            for line in lines {
+                content += line;
+                content += "\n";
+            }
+        }
+        content
+    }
+}
+
+/// The name of the summary family which holds the percentiles for a statistic.
+/// The summary is named with a suffix so that it does not collide with the
+/// reading or histogram of the statistic.
+fn summary_family_name(name: &str, statistic: &dyn Statistic) -> String {
+    if statistic.source() == Source::Counter {
+        format!("{}_rate", name)
+    } else {
+        format!("{}_summary", name)
+    }
+}
+
+/// Renders a percentile as a quantile label value, eg: `99.9` as `0.999`
+fn quantile(percentile: f64) -> String {
+    let quantile = format!("{:.8}", percentile / 100.0);
+    let quantile = quantile.trim_end_matches('0').trim_end_matches('.');
+    quantile.to_string()
+}
+
+/// Converts a statistic name to a valid Prometheus metric name by replacing any
+/// invalid characters with underscores, eg: `cpu/usage/user` becomes
+/// `cpu_usage_user`. Colons are valid, but are reserved for recording rules.
+fn prometheus_name(name: &str) -> String {
+    let mut name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// Converts labels to valid Prometheus label names with escaped values
+fn prometheus_labels(labels: &Labels) -> Vec<(String, String)> {
+    labels
+        .iter()
+        .map(|(k, v)| {
+            (
+                prometheus_name(k),
+                v.replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n"),
+            )
+        })
+        .collect()
+}
+
+/// Renders labels in the Prometheus exposition format, eg: `{cpu="0"}`
+fn render_labels(labels: &[(String, String)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, v))
+        .collect();
+    format!("{{{}}}", labels.join(","))
+}
+
+/// Expands `{hostname}` in the configured prefix for a push exporter and
+/// normalizes it to end with a `.` separator unless it is empty. The hostname
+/// is sanitized in the same way as the other components of the path.
+fn prefix(prefix: &str, component: fn(&str) -> String) -> String {
+    let host = crate::common::hostname().unwrap_or_else(|| "unknown".to_string());
+    let prefix = prefix.replace("{hostname}", &component(&host));
+    let prefix = prefix.trim_matches('.');
+    if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("{}.", prefix)
+    }
+}
+
+/// Renders labels as a suffix for the statistic name in the human and JSON
+/// formats, eg: `{cpu=0,node=0}`
+fn labels_suffix(labels: &Labels) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let labels: Vec<String> = labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    format!("{{{}}}", labels.join(","))
+}
+
+/// Groups lines into newline separated packets no larger than the limit. A
+/// line which exceeds the limit on its own is sent in a packet by itself.
+fn packets(lines: &[String], max_packet_size: usize) -> Vec<String> {
+    let mut packets = Vec::new();
+    let mut packet = String::new();
+    for line in lines {
+        if !packet.is_empty() && packet.len() + 1 + line.len() > max_packet_size {
+            packets.push(std::mem::take(&mut packet));
+        }
+        if !packet.is_empty() {
+            packet.push('\n');
+        }
+        packet.push_str(line);
+    }
+    if !packet.is_empty() {
+        packets.push(packet);
+    }
+    packets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{Duration, Labeled, Nanoseconds, Summary};
+
+    enum TestStat {
+        Requests,
+        Latency,
+    }
+
+    impl Statistic for TestStat {
+        fn name(&self) -> &str {
+            match self {
+                Self::Requests => "test/requests",
+                Self::Latency => "test/latency",
+            }
+        }
+
+        fn source(&self) -> Source {
+            match self {
+                Self::Requests => Source::Counter,
+                Self::Latency => Source::Gauge,
+            }
+        }
+
+        fn summary(&self) -> Option<Summary> {
+            Some(Summary::stream(100))
+        }
+
+        fn unit(&self) -> Option<&str> {
+            match self {
+                Self::Latency => Some("nanoseconds"),
+                _ => None,
+            }
+        }
+
+        fn description(&self) -> Option<&str> {
+            Some("a \"test\" statistic")
+        }
+    }
+
+    fn snapshot() -> MetricsSnapshot {
+        let metrics = Arc::new(Metrics::new());
+        let requests = Labeled::new(TestStat::Requests, Labels::new().with("0cpu", 0));
+        metrics.add_output(&requests, Output::Reading);
+        metrics.add_output(&requests, Output::Percentile(50.0));
+        metrics.add_output(&requests, Output::Percentile(99.9));
+        metrics.add_output(&TestStat::Latency, Output::Reading);
+        let start = rustcommon_time::Instant::<Nanoseconds<u64>>::now();
+        metrics.record_counter(&requests, start, 0).unwrap();
+        metrics
+            .record_counter(&requests, start + Duration::from_secs(1), 10)
+            .unwrap();
+        metrics
+            .record_gauge(&TestStat::Latency, start, 100)
+            .unwrap();
+        let mut snapshot = MetricsSnapshot::new(metrics, None);
+        snapshot.refresh();
+        snapshot
+    }
+
+    #[test]
+    fn prometheus() {
+        let content = snapshot().prometheus(&Filter::default());
+        assert_eq!(
+            content,
+            "# HELP test_latency a \"test\" statistic\n\
+             # TYPE test_latency gauge\n\
+             # UNIT test_latency nanoseconds\n\
+             test_latency 100\n\
+             # HELP test_requests_rate a \"test\" statistic\n\
+             # TYPE test_requests_rate summary\n\
+             test_requests_rate{_0cpu=\"0\",quantile=\"0.5\"} 10\n\
+             test_requests_rate{_0cpu=\"0\",quantile=\"0.999\"} 10\n\
+             # HELP test_requests_total a \"test\" statistic\n\
+             # TYPE test_requests_total counter\n\
+             test_requests_total{_0cpu=\"0\"} 10\n"
+        );
+    }
+
+    #[test]
+    fn openmetrics() {
+        let content = snapshot().openmetrics(&Filter::default());
+        assert!(content.contains("# HELP test_latency a \\\"test\\\" statistic\n"));
+        // the unit is omitted as it is not a suffix of the name
+        assert!(!content.contains("# UNIT"));
+        assert!(
+            content.contains("# TYPE test_requests counter\ntest_requests_total{_0cpu=\"0\"} 10\n")
+        );
+        assert!(content.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn json_escaping() {
+        let metrics = Arc::new(Metrics::new());
+        let requests = Labeled::new(TestStat::Requests, Labels::new().with("path", "a\"b\\"));
+        metrics.add_output(&requests, Output::Reading);
+        metrics
+            .record_counter(&requests, rustcommon_time::Instant::now(), 1)
+            .unwrap();
+        let mut snapshot = MetricsSnapshot::new(metrics, None);
+        snapshot.refresh();
+        let parsed = json::parse(&snapshot.json(true, true, &Filter::default())).unwrap();
+        assert_eq!(parsed["test/requests{path=a\"b\\}"], 1);
+        assert_eq!(
+            parsed["metadata"]["test/requests"]["description"],
+            "a \"test\" statistic"
+        );
+    }
+
+    #[test]
+    fn filter() {
+        let snapshot = snapshot();
+        let filter = Filter::from_query("prefix=test%2Freq&percentiles=99.9").unwrap();
+        assert_eq!(
+            snapshot.human(&filter),
+            "test/requests{0cpu=0}/histogram/p99.9: 10\ntest/requests{0cpu=0}: 10\n"
+        );
+        let filter = Filter::from_query("match=latency$&outputs=reading").unwrap();
+        assert_eq!(snapshot.human(&filter), "test/latency: 100\n");
+        let filter = Filter::from_query("outputs=percentile&metadata").unwrap();
+        assert_eq!(
+            snapshot
+                .json(false, false, &filter)
+                .matches("/histogram/p")
+                .count(),
+            2
+        );
+        assert!(Filter::from_query("match=(").is_err());
+        assert!(Filter::from_query("outputs=bogus").is_err());
+        assert!(Filter::from_query("percentiles=101").is_err());
+    }
+
+    #[test]
+    fn history() {
+        let mut metrics = Metrics::new();
+        metrics.set_history(10, |statistic| statistic.name() == "test/latency");
+        metrics.add_output(&TestStat::Latency, Output::Reading);
+        metrics.add_output(&TestStat::Requests, Output::Reading);
+        let start = rustcommon_time::Instant::<Nanoseconds<u64>>::now();
+        metrics
+            .record_gauge(&TestStat::Latency, start, 100)
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let since = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        metrics
+            .record_gauge(&TestStat::Latency, crate::Instant::now(), 200)
+            .unwrap();
+
+        let content = http::history(&metrics, "metric=test%2Flatency")
+            .unwrap()
+            .unwrap();
+        let parsed = json::parse(&content).unwrap();
+        assert_eq!(parsed["metric"], "test/latency");
+        let readings = &parsed["series"][0]["readings"];
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings[0][1], 100);
+        assert_eq!(readings[1][1], 200);
+        assert!(readings[0][0].as_u64().unwrap() < readings[1][0].as_u64().unwrap());
+
+        let content = http::history(&metrics, &format!("metric=test/latency&since={}", since))
+            .unwrap()
+            .unwrap();
+        let parsed = json::parse(&content).unwrap();
+        assert_eq!(parsed["series"][0]["readings"].len(), 1);
+        assert_eq!(parsed["series"][0]["readings"][0][1], 200);
+
+        assert_eq!(http::history(&metrics, "metric=test/requests"), Ok(None));
+        assert!(http::history(&metrics, "since=0").is_err());
+        assert!(http::history(&metrics, "metric=test/latency&since=x").is_err());
+    }
+
+    /// A stand-in for an http endpoint which answers each request with the
+    /// next status and returns the request bodies
+    pub(super) fn stand_in(
+        statuses: &'static [&'static str],
+    ) -> (u16, std::thread::JoinHandle<Vec<Vec<u8>>>) {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let mut bodies = Vec::new();
+            for status in statuses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((k, v)) = line.split_once(':') {
+                        if k.eq_ignore_ascii_case("content-length") {
+                            length = v.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                bodies.push(body);
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    status
+                )
+                .unwrap();
+            }
+            bodies
+        });
+        (port, server)
+    }
+
+    #[test]
+    fn packet_batching() {
+        let lines: Vec<String> = ["a:1|g", "b:22|g", "c:333|c", "dddddddddd:1|g"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(
+            packets(&lines, 14),
+            vec!["a:1|g\nb:22|g", "c:333|c", "dddddddddd:1|g"]
+        );
+    }
 }
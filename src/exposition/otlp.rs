@@ -0,0 +1,808 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::BTreeMap;
+use std::convert::{TryFrom, TryInto};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hyper::body::HttpBody;
+use hyper::client::HttpConnector;
+use hyper::header::{HeaderMap, HeaderName, HeaderValue};
+use prost::Message;
+
+use crate::common::shutdown::Shutdown;
+use crate::*;
+
+use crate::common::hostname;
+use crate::config::{Config, OtlpProtocol};
+use crate::exposition::{
+    float_value, quantile, summary_name, write_with_retry, Exporter, Filter, MetricsSnapshot,
+    WriteError,
+};
+
+use self::proto::{
+    any_value, metric, number_data_point, AnyValue, ExportMetricsServiceRequest, Gauge,
+    HistogramDataPoint, InstrumentationScope, KeyValue, NumberDataPoint, Resource, ResourceMetrics,
+    ScopeMetrics, Sum, SummaryDataPoint, ValueAtQuantile, AGGREGATION_TEMPORALITY_CUMULATIVE,
+};
+
+/// The gRPC method which receives metrics
+const GRPC_PATH: &str = "/opentelemetry.proto.collector.metrics.v1.MetricsService/Export";
+
+/// Pushes metrics to an OpenTelemetry collector using OTLP. Counters are
+/// exported as cumulative sums, gauges as gauges, distributions as explicit
+/// bucket histograms, and percentiles as summaries. A failed export is not
+/// buffered, as the cumulative values of the next export supersede it.
+pub struct Otlp {
+    snapshot: MetricsSnapshot,
+    transport: Transport,
+    resource: Resource,
+    start: u64,
+    window: u64,
+    interval: Duration,
+    timeout: Duration,
+    retries: usize,
+}
+
+// there is a single transport per exporter, so the size is not a concern
+#[allow(clippy::large_enum_variant)]
+enum Transport {
+    Http {
+        client: reqwest::blocking::Client,
+        url: String,
+    },
+    Grpc {
+        runtime: tokio::runtime::Runtime,
+        client: hyper::Client<HttpConnector>,
+        uri: hyper::Uri,
+        headers: HeaderMap,
+    },
+}
+
+impl Otlp {
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Self {
+        let otlp = config.exposition().otlp();
+        let timeout = Duration::from_millis(otlp.timeout().try_into().unwrap());
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in otlp.headers() {
+            match (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+                (Ok(name), Ok(value)) => {
+                    headers.insert(name, value);
+                }
+                _ => {
+                    fatal!("invalid otlp header: {}", name);
+                }
+            }
+        }
+
+        let transport = match otlp.protocol() {
+            OtlpProtocol::Http => {
+                let client = match reqwest::blocking::ClientBuilder::new()
+                    .timeout(timeout)
+                    .default_headers(headers)
+                    .build()
+                {
+                    Ok(client) => client,
+                    Err(e) => {
+                        fatal!("failed to create http client for otlp: {}", e);
+                    }
+                };
+                Transport::Http {
+                    client,
+                    url: otlp.endpoint().to_string(),
+                }
+            }
+            OtlpProtocol::Grpc => {
+                let endpoint = otlp.endpoint().trim_end_matches('/');
+                if !endpoint.starts_with("http://") {
+                    fatal!("otlp grpc endpoint must start with http://: {}", endpoint);
+                }
+                let uri = match format!("{}{}", endpoint, GRPC_PATH).parse() {
+                    Ok(uri) => uri,
+                    Err(e) => {
+                        fatal!("invalid otlp endpoint: {}: {}", endpoint, e);
+                    }
+                };
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        fatal!("failed to create runtime for otlp: {}", e);
+                    }
+                };
+                Transport::Grpc {
+                    runtime,
+                    client: hyper::Client::builder().http2_only(true).build_http(),
+                    uri,
+                    headers,
+                }
+            }
+        };
+
+        // configured attributes take precedence over the defaults
+        let mut attributes = BTreeMap::new();
+        if let Some(host) = hostname() {
+            attributes.insert("host.name".to_string(), host);
+        }
+        attributes.insert("service.name".to_string(), otlp.service_name().to_string());
+        attributes.insert(
+            "service.version".to_string(),
+            crate::config::VERSION.to_string(),
+        );
+        for (key, value) in otlp.resource_attributes() {
+            attributes.insert(key.to_string(), value.to_string());
+        }
+
+        Self {
+            snapshot: MetricsSnapshot::new(metrics, config.general().reading_suffix())
+                .with_histograms(),
+            transport,
+            resource: Resource {
+                attributes: attributes
+                    .iter()
+                    .map(|(key, value)| key_value(key, value))
+                    .collect(),
+            },
+            start: now(),
+            window: config.general().window() as u64 * 1_000_000_000,
+            interval: Duration::from_millis(otlp.interval().try_into().unwrap()),
+            timeout,
+            retries: otlp.retries(),
+        }
+    }
+
+    fn export(&self, body: &[u8], shutdown: &Shutdown) -> Result<(), WriteError> {
+        let timeout = shutdown.limit(self.timeout);
+        match &self.transport {
+            Transport::Http { client, url } => {
+                let response = client
+                    .post(url)
+                    .timeout(timeout)
+                    .header("Content-Type", "application/x-protobuf")
+                    .body(body.to_vec())
+                    .send()
+                    .map_err(|e| WriteError::Retry(e.to_string()))?;
+                let status = response.status();
+                if status.is_success() {
+                    Ok(())
+                } else if matches!(status.as_u16(), 429 | 502 | 503 | 504) {
+                    Err(WriteError::Retry(format!("status: {}", status)))
+                } else {
+                    Err(WriteError::Rejected(format!("status: {}", status)))
+                }
+            }
+            Transport::Grpc {
+                runtime,
+                client,
+                uri,
+                headers,
+            } => {
+                // messages are prefixed with an uncompressed flag and length
+                let mut message = Vec::with_capacity(body.len() + 5);
+                message.push(0);
+                message.extend_from_slice(&(body.len() as u32).to_be_bytes());
+                message.extend_from_slice(body);
+
+                let mut request = hyper::Request::post(uri.clone())
+                    .header("content-type", "application/grpc")
+                    .header("te", "trailers")
+                    .body(hyper::Body::from(message))
+                    .map_err(|e| WriteError::Rejected(e.to_string()))?;
+                request.headers_mut().extend(headers.clone());
+
+                let (parts, trailers) = runtime
+                    .block_on(async {
+                        tokio::time::timeout(timeout, async {
+                            let response = client.request(request).await?;
+                            let (parts, mut body) = response.into_parts();
+                            while let Some(chunk) = body.data().await {
+                                chunk?;
+                            }
+                            let trailers = body.trailers().await?;
+                            Ok::<_, hyper::Error>((parts, trailers))
+                        })
+                        .await
+                    })
+                    .map_err(|_| WriteError::Retry("timed out".to_string()))?
+                    .map_err(|e| WriteError::Retry(e.to_string()))?;
+
+                if !parts.status.is_success() {
+                    return Err(WriteError::Retry(format!("status: {}", parts.status)));
+                }
+                // the status is in the headers if there is no response body
+                let status = trailers
+                    .as_ref()
+                    .and_then(|t| t.get("grpc-status"))
+                    .or_else(|| parts.headers.get("grpc-status"))
+                    .and_then(|s| s.to_str().ok())
+                    .and_then(|s| s.parse::<u32>().ok());
+                match status {
+                    Some(0) => Ok(()),
+                    // cancelled, deadline exceeded, aborted, out of range,
+                    // unavailable, and data loss may be retried
+                    Some(code @ (1 | 4 | 10 | 11 | 14 | 15)) => {
+                        Err(WriteError::Retry(format!("grpc status: {}", code)))
+                    }
+                    Some(code) => Err(WriteError::Rejected(format!("grpc status: {}", code))),
+                    None => Err(WriteError::Retry("missing grpc status".to_string())),
+                }
+            }
+        }
+    }
+}
+
+impl Exporter for Otlp {
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Snapshot the metrics and export them, retrying with a backoff if the
+    /// collector is unavailable.
+    fn push(&mut self, shutdown: &Shutdown) {
+        self.snapshot.refresh();
+        let request = request(
+            &self.snapshot,
+            self.resource.clone(),
+            self.start,
+            self.window,
+            now(),
+        );
+        let body = request.encode_to_vec();
+
+        match write_with_retry("otlp", self.retries, shutdown, || {
+            self.export(&body, shutdown)
+        }) {
+            Ok(()) => {}
+            Err(WriteError::Retry(e)) => {
+                error!("failed to export to otlp: {}", e);
+            }
+            Err(WriteError::Rejected(e)) => {
+                error!("otlp rejected export: {}", e);
+            }
+        }
+    }
+}
+
+/// The current time in nanoseconds since the unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|t| t.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+fn key_value(key: &str, value: &str) -> KeyValue {
+    KeyValue {
+        key: key.to_string(),
+        value: Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value.to_string())),
+        }),
+    }
+}
+
+/// The representation of a data point follows the kind of the statistic, so
+/// every point of a metric has the same type. Unsigned values are always
+/// written as integers, saturating at `i64::MAX`.
+fn number(value: &MetricValue) -> Option<number_data_point::Value> {
+    match value {
+        MetricValue::Unsigned(value) => Some(number_data_point::Value::AsInt(
+            (*value).min(i64::MAX as u64) as i64,
+        )),
+        MetricValue::Signed(value) => Some(number_data_point::Value::AsInt(*value)),
+        MetricValue::Float(value) => Some(number_data_point::Value::AsDouble(*value)),
+        MetricValue::Histogram(_) => None,
+    }
+}
+
+/// Builds the export request for a snapshot. Statistic names are converted to
+/// the dotted OpenTelemetry style, eg: `cpu/usage/user` becomes
+/// `cpu.usage.user`, and labels become data point attributes. Percentiles are
+/// grouped into a summary along with the min and max as the 0 and 1 quantiles,
+/// and the count and sum of the samples across the window. Times are in
+/// nanoseconds.
+pub(crate) fn request(
+    snapshot: &MetricsSnapshot,
+    resource: Resource,
+    start: u64,
+    window: u64,
+    time: u64,
+) -> ExportMetricsServiceRequest {
+    // keyed by the type as well, so that the data points of each metric are
+    // all of the same type
+    let mut metrics: BTreeMap<(String, &'static str), proto::Metric> = BTreeMap::new();
+    for (metric, value) in snapshot.filtered(&Filter::default()) {
+        let statistic = metric.statistic();
+        let name = statistic.name().replace('/', ".");
+        let output = metric.output();
+        let attributes: Vec<KeyValue> = statistic
+            .labels()
+            .iter()
+            .map(|(k, v)| key_value(k, v))
+            .collect();
+
+        let (name, kind, empty): (String, &'static str, fn() -> metric::Data) = match output {
+            Output::Reading if statistic.source() == Source::Counter => (name, "sum", || {
+                metric::Data::Sum(Sum {
+                    data_points: Vec::new(),
+                    aggregation_temporality: AGGREGATION_TEMPORALITY_CUMULATIVE,
+                    is_monotonic: true,
+                })
+            }),
+            Output::Reading => (name, "gauge", || metric::Data::Gauge(Gauge::default())),
+            Output::Histogram => (name, "histogram", || {
+                metric::Data::Histogram(proto::Histogram {
+                    data_points: Vec::new(),
+                    aggregation_temporality: AGGREGATION_TEMPORALITY_CUMULATIVE,
+                })
+            }),
+            Output::Percentile(_) | Output::Min | Output::Max => {
+                let name = if statistic.source() == Source::Counter {
+                    format!("{}.rate", name)
+                } else {
+                    format!("{}.summary", name)
+                };
+                (
+                    name,
+                    "summary",
+                    || metric::Data::Summary(Default::default()),
+                )
+            }
+            Output::Mean | Output::Stddev | Output::Samples => (
+                format!("{}.{}", name, summary_name(output)),
+                "gauge",
+                || metric::Data::Gauge(Gauge::default()),
+            ),
+        };
+        let entry = metrics
+            .entry((name.clone(), kind))
+            .or_insert_with(|| proto::Metric {
+                name,
+                description: statistic.description().unwrap_or_default().to_string(),
+                unit: statistic.unit().unwrap_or_default().to_string(),
+                data: Some(empty()),
+            });
+
+        match (entry.data.as_mut(), output, value) {
+            (Some(metric::Data::Sum(sum)), _, value) => {
+                sum.data_points.push(NumberDataPoint {
+                    attributes,
+                    start_time_unix_nano: start,
+                    time_unix_nano: time,
+                    value: number(value),
+                });
+            }
+            (Some(metric::Data::Gauge(gauge)), _, value) => {
+                gauge.data_points.push(NumberDataPoint {
+                    attributes,
+                    start_time_unix_nano: 0,
+                    time_unix_nano: time,
+                    value: number(value),
+                });
+            }
+            (Some(metric::Data::Histogram(data)), _, MetricValue::Histogram(histogram)) => {
+                // buckets are cumulative in the snapshot but not in otlp, and
+                // include a final bucket for values above the last bound
+                let mut previous = 0;
+                let mut bucket_counts = Vec::new();
+                for bucket in histogram.buckets() {
+                    bucket_counts.push(bucket.count().saturating_sub(previous));
+                    previous = bucket.count();
+                }
+                bucket_counts.push(histogram.count().saturating_sub(previous));
+                data.data_points.push(HistogramDataPoint {
+                    attributes,
+                    start_time_unix_nano: start,
+                    time_unix_nano: time,
+                    count: histogram.count(),
+                    sum: Some(histogram.sum() as f64),
+                    bucket_counts,
+                    explicit_bounds: histogram
+                        .buckets()
+                        .iter()
+                        .map(|b| b.upper() as f64)
+                        .collect(),
+                });
+            }
+            (Some(metric::Data::Summary(summary)), output, value) => {
+                let quantile = match output {
+                    // parsed from the label value to match the precision
+                    // of the other formats, eg: `0.999` rather than
+                    // `0.9990000000000001`
+                    Output::Percentile(percentile) => {
+                        quantile(percentile).parse().unwrap_or(percentile / 100.0)
+                    }
+                    Output::Min => 0.0,
+                    _ => 1.0,
+                };
+                let value = match float_value(value) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                let index = match summary
+                    .data_points
+                    .iter()
+                    .position(|p| p.attributes == attributes)
+                {
+                    Some(index) => index,
+                    None => {
+                        let (count, sum) = match snapshot.moments(statistic) {
+                            Some(moments) => {
+                                (moments.samples(), moments.mean() * moments.samples() as f64)
+                            }
+                            None => (0, 0.0),
+                        };
+                        summary.data_points.push(SummaryDataPoint {
+                            attributes,
+                            start_time_unix_nano: time.saturating_sub(window).max(start),
+                            time_unix_nano: time,
+                            count,
+                            sum,
+                            ..Default::default()
+                        });
+                        summary.data_points.len() - 1
+                    }
+                };
+                let point = &mut summary.data_points[index];
+                point
+                    .quantile_values
+                    .push(ValueAtQuantile { quantile, value });
+                point
+                    .quantile_values
+                    .sort_by(|a, b| a.quantile.total_cmp(&b.quantile));
+            }
+            _ => {}
+        }
+    }
+
+    ExportMetricsServiceRequest {
+        resource_metrics: vec![ResourceMetrics {
+            resource: Some(resource),
+            scope_metrics: vec![ScopeMetrics {
+                scope: Some(InstrumentationScope {
+                    name: crate::config::NAME.to_string(),
+                    version: crate::config::VERSION.to_string(),
+                }),
+                metrics: metrics.into_values().collect(),
+            }],
+        }],
+    }
+}
+
+/// The subset of the OTLP metrics protocol which is exported, see:
+/// https://github.com/open-telemetry/opentelemetry-proto
+pub(crate) mod proto {
+    pub const AGGREGATION_TEMPORALITY_CUMULATIVE: i32 = 2;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExportMetricsServiceRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub resource_metrics: Vec<ResourceMetrics>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ResourceMetrics {
+        #[prost(message, optional, tag = "1")]
+        pub resource: Option<Resource>,
+        #[prost(message, repeated, tag = "2")]
+        pub scope_metrics: Vec<ScopeMetrics>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Resource {
+        #[prost(message, repeated, tag = "1")]
+        pub attributes: Vec<KeyValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ScopeMetrics {
+        #[prost(message, optional, tag = "1")]
+        pub scope: Option<InstrumentationScope>,
+        #[prost(message, repeated, tag = "2")]
+        pub metrics: Vec<Metric>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct InstrumentationScope {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub version: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct KeyValue {
+        #[prost(string, tag = "1")]
+        pub key: String,
+        #[prost(message, optional, tag = "2")]
+        pub value: Option<AnyValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AnyValue {
+        #[prost(oneof = "any_value::Value", tags = "1")]
+        pub value: Option<any_value::Value>,
+    }
+
+    pub mod any_value {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Value {
+            #[prost(string, tag = "1")]
+            StringValue(String),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Metric {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub description: String,
+        #[prost(string, tag = "3")]
+        pub unit: String,
+        #[prost(oneof = "metric::Data", tags = "5, 7, 9, 11")]
+        pub data: Option<metric::Data>,
+    }
+
+    pub mod metric {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Data {
+            #[prost(message, tag = "5")]
+            Gauge(super::Gauge),
+            #[prost(message, tag = "7")]
+            Sum(super::Sum),
+            #[prost(message, tag = "9")]
+            Histogram(super::Histogram),
+            #[prost(message, tag = "11")]
+            Summary(super::Summary),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Gauge {
+        #[prost(message, repeated, tag = "1")]
+        pub data_points: Vec<NumberDataPoint>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Sum {
+        #[prost(message, repeated, tag = "1")]
+        pub data_points: Vec<NumberDataPoint>,
+        #[prost(int32, tag = "2")]
+        pub aggregation_temporality: i32,
+        #[prost(bool, tag = "3")]
+        pub is_monotonic: bool,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Histogram {
+        #[prost(message, repeated, tag = "1")]
+        pub data_points: Vec<HistogramDataPoint>,
+        #[prost(int32, tag = "2")]
+        pub aggregation_temporality: i32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Summary {
+        #[prost(message, repeated, tag = "1")]
+        pub data_points: Vec<SummaryDataPoint>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct NumberDataPoint {
+        #[prost(message, repeated, tag = "7")]
+        pub attributes: Vec<KeyValue>,
+        #[prost(fixed64, tag = "2")]
+        pub start_time_unix_nano: u64,
+        #[prost(fixed64, tag = "3")]
+        pub time_unix_nano: u64,
+        #[prost(oneof = "number_data_point::Value", tags = "4, 6")]
+        pub value: Option<number_data_point::Value>,
+    }
+
+    pub mod number_data_point {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Value {
+            #[prost(double, tag = "4")]
+            AsDouble(f64),
+            #[prost(sfixed64, tag = "6")]
+            AsInt(i64),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct HistogramDataPoint {
+        #[prost(message, repeated, tag = "9")]
+        pub attributes: Vec<KeyValue>,
+        #[prost(fixed64, tag = "2")]
+        pub start_time_unix_nano: u64,
+        #[prost(fixed64, tag = "3")]
+        pub time_unix_nano: u64,
+        #[prost(fixed64, tag = "4")]
+        pub count: u64,
+        #[prost(double, optional, tag = "5")]
+        pub sum: Option<f64>,
+        #[prost(fixed64, repeated, tag = "6")]
+        pub bucket_counts: Vec<u64>,
+        #[prost(double, repeated, tag = "7")]
+        pub explicit_bounds: Vec<f64>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SummaryDataPoint {
+        #[prost(message, repeated, tag = "7")]
+        pub attributes: Vec<KeyValue>,
+        #[prost(fixed64, tag = "2")]
+        pub start_time_unix_nano: u64,
+        #[prost(fixed64, tag = "3")]
+        pub time_unix_nano: u64,
+        #[prost(fixed64, tag = "4")]
+        pub count: u64,
+        #[prost(double, tag = "5")]
+        pub sum: f64,
+        #[prost(message, repeated, tag = "6")]
+        pub quantile_values: Vec<ValueAtQuantile>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ValueAtQuantile {
+        #[prost(double, tag = "1")]
+        pub quantile: f64,
+        #[prost(double, tag = "2")]
+        pub value: f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::Summary;
+    use crate::samplers::cpu::CpuStatistic;
+    use crate::samplers::disk::DiskStatistic;
+    use prost::Message;
+
+    fn snapshot() -> MetricsSnapshot {
+        let metrics = Arc::new(Metrics::new());
+        let usage = Labeled::new(CpuStatistic::UsageUser, Labels::new().with("cpu", 0));
+        metrics.add_output(&usage, Output::Reading);
+        metrics.add_summary(&usage, Summary::stream(60));
+        metrics.add_output(&usage, Output::Percentile(50.0));
+        metrics.add_output(&usage, Output::Percentile(99.0));
+        metrics.add_output(&CpuStatistic::Frequency, Output::Reading);
+        metrics.add_output(&DiskStatistic::LatencyRead, Output::Percentile(50.0));
+        metrics.add_summary(
+            &DiskStatistic::LatencyRead,
+            Summary::heatmap(
+                1_000_000_000,
+                2,
+                crate::Duration::from_secs(60),
+                crate::Duration::from_secs(1),
+            ),
+        );
+
+        let start = crate::Instant::now();
+        metrics.record_counter(&usage, start, 0).unwrap();
+        metrics
+            .record_counter(&usage, start + crate::Duration::from_secs(1), 250_000_000)
+            .unwrap();
+        metrics
+            .record_gauge(&CpuStatistic::Frequency, start, 2_400_000_000)
+            .unwrap();
+        metrics
+            .record_bucket(&DiskStatistic::LatencyRead, start, 100_000, 3)
+            .unwrap();
+        let mut snapshot = MetricsSnapshot::new(metrics, None).with_histograms();
+        snapshot.refresh();
+        snapshot
+    }
+
+    #[test]
+    fn metrics() {
+        let request = request(&snapshot(), Resource::default(), 1, 60, 100);
+        let request = ExportMetricsServiceRequest::decode(&*request.encode_to_vec()).unwrap();
+        let metrics = &request.resource_metrics[0].scope_metrics[0].metrics;
+        let names: Vec<&str> = metrics.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "cpu.frequency",
+                "cpu.usage.user",
+                "cpu.usage.user.rate",
+                "disk.read.latency",
+                "disk.read.latency.summary",
+            ]
+        );
+
+        let attributes = vec![key_value("cpu", "0")];
+        match &metrics[0].data {
+            Some(metric::Data::Gauge(gauge)) => {
+                assert_eq!(metrics[0].unit, "hertz");
+                assert_eq!(
+                    gauge.data_points[0].value,
+                    Some(number_data_point::Value::AsInt(2_400_000_000))
+                );
+            }
+            data => panic!("expected a gauge: {:?}", data),
+        }
+        match &metrics[1].data {
+            Some(metric::Data::Sum(sum)) => {
+                assert_eq!(metrics[1].unit, "nanoseconds");
+                assert!(sum.is_monotonic);
+                assert_eq!(
+                    sum.aggregation_temporality,
+                    AGGREGATION_TEMPORALITY_CUMULATIVE
+                );
+                assert_eq!(sum.data_points[0].attributes, attributes);
+                assert_eq!(sum.data_points[0].start_time_unix_nano, 1);
+                assert_eq!(sum.data_points[0].time_unix_nano, 100);
+                assert_eq!(
+                    sum.data_points[0].value,
+                    Some(number_data_point::Value::AsInt(250_000_000))
+                );
+            }
+            data => panic!("expected a sum: {:?}", data),
+        }
+        match &metrics[2].data {
+            Some(metric::Data::Summary(summary)) => {
+                assert_eq!(summary.data_points.len(), 1);
+                let point = &summary.data_points[0];
+                assert_eq!(point.attributes, attributes);
+                // the window starts after the exporter
+                assert_eq!(point.start_time_unix_nano, 40);
+                assert_eq!(point.time_unix_nano, 100);
+                assert_eq!(point.count, 1);
+                assert_eq!(point.sum, 250_000_000.0);
+                let quantiles: Vec<(f64, f64)> = point
+                    .quantile_values
+                    .iter()
+                    .map(|q| (q.quantile, q.value))
+                    .collect();
+                assert_eq!(quantiles, [(0.5, 250_000_000.0), (0.99, 250_000_000.0)]);
+            }
+            data => panic!("expected a summary: {:?}", data),
+        }
+    }
+
+    #[test]
+    fn histograms() {
+        // histograms are exported without the histogram output
+        let request = request(&snapshot(), Resource::default(), 1, 60, 100);
+        let metrics = &request.resource_metrics[0].scope_metrics[0].metrics;
+        let latency = metrics
+            .iter()
+            .find(|m| m.name == "disk.read.latency")
+            .expect("missing histogram");
+        match &latency.data {
+            Some(metric::Data::Histogram(histogram)) => {
+                let point = &histogram.data_points[0];
+                assert_eq!(point.count, 3);
+                assert_eq!(point.sum, Some(300_000.0));
+                // buckets are not cumulative, with one more than the bounds
+                assert_eq!(point.bucket_counts.len(), point.explicit_bounds.len() + 1);
+                assert_eq!(point.bucket_counts.iter().sum::<u64>(), 3);
+            }
+            data => panic!("expected a histogram: {:?}", data),
+        }
+    }
+
+    #[test]
+    fn unsigned_overflow() {
+        // large values keep the integer type of the metric
+        assert_eq!(
+            number(&MetricValue::Unsigned(u64::MAX)),
+            Some(number_data_point::Value::AsInt(i64::MAX))
+        );
+        assert_eq!(
+            number(&MetricValue::Unsigned(42)),
+            Some(number_data_point::Value::AsInt(42))
+        );
+        assert_eq!(
+            number(&MetricValue::Float(0.5)),
+            Some(number_data_point::Value::AsDouble(0.5))
+        );
+    }
+}
@@ -0,0 +1,432 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::BTreeMap;
+use std::convert::{TryFrom, TryInto};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use prost::Message;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::common::shutdown::Shutdown;
+use crate::*;
+
+use crate::config::Config;
+use crate::exposition::{
+    float_value, prometheus_name, quantile, summary_family_name, summary_name, Exporter, Filter,
+    MetricsSnapshot, Pending, WriteError,
+};
+
+use self::proto::{Label, Sample, TimeSeries, WriteRequest};
+
+/// Pushes metrics using the Prometheus remote write protocol. Series are named
+/// as they are in the Prometheus exposition format, and each push is queued so
+/// that writes which fail while the endpoint is unavailable are sent in order
+/// once it recovers, up to a limit after which the oldest are dropped.
+pub struct RemoteWrite {
+    snapshot: MetricsSnapshot,
+    endpoint: Endpoint,
+    external_labels: Vec<(String, String)>,
+    interval: Duration,
+    pending: Pending<TimeSeries>,
+}
+
+/// The endpoint which receives the write requests
+struct Endpoint {
+    client: reqwest::blocking::Client,
+    url: String,
+    timeout: Duration,
+}
+
+impl RemoteWrite {
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Self {
+        let remote_write = config.exposition().remote_write();
+        let url = match remote_write.url() {
+            Some(url) => url.to_string(),
+            None => {
+                fatal!("remote_write exposition requires a url");
+            }
+        };
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in remote_write.headers() {
+            match (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+                (Ok(name), Ok(value)) => {
+                    headers.insert(name, value);
+                }
+                _ => {
+                    fatal!("invalid remote_write header: {}", name);
+                }
+            }
+        }
+        let timeout = Duration::from_millis(remote_write.timeout().try_into().unwrap());
+        let client = match reqwest::blocking::ClientBuilder::new()
+            .timeout(timeout)
+            .user_agent(format!(
+                "{}/{}",
+                crate::config::NAME,
+                crate::config::VERSION
+            ))
+            .default_headers(headers)
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                fatal!("failed to create http client for remote_write: {}", e);
+            }
+        };
+
+        Self {
+            snapshot: MetricsSnapshot::new(metrics, config.general().reading_suffix()),
+            endpoint: Endpoint {
+                client,
+                url,
+                timeout,
+            },
+            external_labels: remote_write
+                .external_labels()
+                .iter()
+                .map(|(k, v)| (prometheus_name(k), v.to_string()))
+                .collect(),
+            interval: Duration::from_millis(remote_write.interval().try_into().unwrap()),
+            pending: Pending::new(
+                "remote_write",
+                "samples",
+                remote_write.batch_size(),
+                remote_write.retries(),
+                remote_write.buffer(),
+            ),
+        }
+    }
+}
+
+impl Endpoint {
+    fn write(&self, batch: &[TimeSeries], shutdown: &Shutdown) -> Result<(), WriteError> {
+        let request = WriteRequest {
+            timeseries: batch.to_vec(),
+        };
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&request.encode_to_vec())
+            .map_err(|e| WriteError::Rejected(e.to_string()))?;
+        let response = self
+            .client
+            .post(&self.url)
+            .timeout(shutdown.limit(self.timeout))
+            .header("Content-Encoding", "snappy")
+            .header("Content-Type", "application/x-protobuf")
+            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+            .body(body)
+            .send()
+            .map_err(|e| WriteError::Retry(e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else if status.is_server_error() || status.as_u16() == 429 {
+            Err(WriteError::Retry(format!("status: {}", status)))
+        } else {
+            let body = response.text().unwrap_or_default();
+            Err(WriteError::Rejected(format!("status: {} {}", status, body)))
+        }
+    }
+}
+
+impl Exporter for RemoteWrite {
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Snapshot the metrics and write them after any batches which previously
+    /// failed. Batches which still fail after retrying are kept for the next
+    /// push, up to the buffer limit.
+    fn push(&mut self, shutdown: &Shutdown) {
+        self.snapshot.refresh();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|t| t.as_millis() as i64)
+            .unwrap_or(0);
+        self.pending
+            .push(&series(&self.snapshot, &self.external_labels, timestamp));
+        let endpoint = &self.endpoint;
+        self.pending
+            .write(shutdown, |batch| endpoint.write(batch, shutdown));
+    }
+}
+
+/// Converts the snapshot to series named as in the Prometheus exposition
+/// format, each with a single sample at the given timestamp in milliseconds.
+/// External labels are added unless the series already has that label.
+pub(crate) fn series(
+    snapshot: &MetricsSnapshot,
+    external_labels: &[(String, String)],
+    timestamp: i64,
+) -> Vec<TimeSeries> {
+    let mut series = Vec::new();
+    for (metric, value) in snapshot.filtered(&Filter::default()) {
+        let statistic = metric.statistic();
+        let name = prometheus_name(statistic.name());
+        let labels: BTreeMap<String, String> = statistic
+            .labels()
+            .iter()
+            .map(|(k, v)| (prometheus_name(k), v.to_string()))
+            .collect();
+        let output = metric.output();
+
+        let mut push = |name: String, extra: Option<(&str, String)>, value: f64| {
+            let mut labels = labels.clone();
+            for (key, value) in external_labels {
+                labels
+                    .entry(key.to_string())
+                    .or_insert_with(|| value.to_string());
+            }
+            if let Some((key, value)) = extra {
+                labels.insert(key.to_string(), value);
+            }
+            labels.insert("__name__".to_string(), name);
+            series.push(TimeSeries {
+                labels: labels
+                    .into_iter()
+                    .map(|(name, value)| Label { name, value })
+                    .collect(),
+                samples: vec![Sample { value, timestamp }],
+            });
+        };
+
+        match (output, value) {
+            (Output::Histogram, MetricValue::Histogram(histogram)) => {
+                for bucket in histogram.buckets() {
+                    push(
+                        format!("{}_bucket", name),
+                        Some(("le", bucket.upper().to_string())),
+                        bucket.count() as f64,
+                    );
+                }
+                push(
+                    format!("{}_bucket", name),
+                    Some(("le", "+Inf".to_string())),
+                    histogram.count() as f64,
+                );
+                push(format!("{}_sum", name), None, histogram.sum() as f64);
+                push(format!("{}_count", name), None, histogram.count() as f64);
+            }
+            (_, value) => {
+                let value = match float_value(value) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                match output {
+                    Output::Reading if statistic.source() == Source::Counter => {
+                        let name = name.strip_suffix("_total").unwrap_or(&name);
+                        push(format!("{}_total", name), None, value);
+                    }
+                    Output::Reading => push(name, None, value),
+                    Output::Percentile(percentile) => push(
+                        summary_family_name(&name, statistic),
+                        Some(("quantile", quantile(percentile))),
+                        value,
+                    ),
+                    _ => push(format!("{}_{}", name, summary_name(output)), None, value),
+                }
+            }
+        }
+    }
+    series
+}
+
+/// The remote write protocol messages, see:
+/// https://github.com/prometheus/prometheus/blob/main/prompb/remote.proto
+pub(crate) mod proto {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct WriteRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub timeseries: Vec<TimeSeries>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TimeSeries {
+        /// Sorted by name, including the metric name as `__name__`
+        #[prost(message, repeated, tag = "1")]
+        pub labels: Vec<Label>,
+        #[prost(message, repeated, tag = "2")]
+        pub samples: Vec<Sample>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Label {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub value: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Sample {
+        #[prost(double, tag = "1")]
+        pub value: f64,
+        /// Milliseconds since the unix epoch
+        #[prost(int64, tag = "2")]
+        pub timestamp: i64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exposition::tests::stand_in;
+    use crate::exposition::RETRY_BACKOFF;
+    use crate::samplers::cpu::CpuStatistic;
+
+    fn metrics() -> Arc<Metrics> {
+        let metrics = Arc::new(Metrics::new());
+        for cpu in 0..2 {
+            let usage = Labeled::new(CpuStatistic::UsageUser, Labels::new().with("cpu", cpu));
+            metrics.add_output(&usage, Output::Reading);
+            metrics
+                .record_counter(&usage, crate::Instant::now(), 1000 * (cpu + 1))
+                .unwrap();
+        }
+        metrics.add_output(&CpuStatistic::Frequency, Output::Reading);
+        metrics
+            .record_gauge(
+                &CpuStatistic::Frequency,
+                crate::Instant::now(),
+                2_400_000_000,
+            )
+            .unwrap();
+        metrics
+    }
+
+    fn config(url: &str, options: &str) -> Config {
+        toml::from_str(&format!(
+            "[exposition.remote_write]\n\
+             enabled = true\n\
+             url = \"{}\"\n\
+             {}",
+            url, options
+        ))
+        .unwrap()
+    }
+
+    fn decode(body: &[u8]) -> WriteRequest {
+        let body = snap::raw::Decoder::new().decompress_vec(body).unwrap();
+        WriteRequest::decode(&*body).unwrap()
+    }
+
+    #[test]
+    fn queued_retry() {
+        // the first push fails without retrying, so it is queued and sent
+        // ahead of the second
+        let (port, server) = stand_in(&[
+            "503 Service Unavailable",
+            "204 No Content",
+            "204 No Content",
+        ]);
+        let config = config(
+            &format!("http://127.0.0.1:{}/api/v1/write", port),
+            "retries = 0\nexternal_labels = { dc = \"east\", cpu = \"all\" }\n",
+        );
+        let mut remote_write = RemoteWrite::new(&config, metrics());
+        remote_write.push(&Shutdown::new());
+        assert_eq!(remote_write.pending.batches.len(), 1);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        remote_write.push(&Shutdown::new());
+        assert!(remote_write.pending.batches.is_empty());
+
+        let requests: Vec<WriteRequest> = server
+            .join()
+            .unwrap()
+            .iter()
+            .map(|body| decode(body))
+            .collect();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0], requests[1]);
+        assert!(
+            requests[1].timeseries[0].samples[0].timestamp
+                < requests[2].timeseries[0].samples[0].timestamp
+        );
+
+        // external labels are added unless the series has that label
+        let mut series: Vec<String> = requests[2]
+            .timeseries
+            .iter()
+            .map(|s| {
+                let labels: Vec<String> = s
+                    .labels
+                    .iter()
+                    .map(|l| format!("{}={}", l.name, l.value))
+                    .collect();
+                format!("{} {}", labels.join(","), s.samples[0].value)
+            })
+            .collect();
+        series.sort();
+        assert_eq!(
+            series,
+            [
+                "__name__=cpu_frequency,cpu=all,dc=east 2400000000",
+                "__name__=cpu_usage_user_total,cpu=0,dc=east 1000",
+                "__name__=cpu_usage_user_total,cpu=1,dc=east 2000",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejected() {
+        // a rejected write is dropped rather than sent ahead of the next push
+        let (port, server) = stand_in(&["400 Bad Request", "204 No Content"]);
+        let config = config(&format!("http://127.0.0.1:{}/api/v1/write", port), "");
+        let mut remote_write = RemoteWrite::new(&config, metrics());
+        remote_write.push(&Shutdown::new());
+        assert!(remote_write.pending.batches.is_empty());
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        remote_write.push(&Shutdown::new());
+
+        let bodies = server.join().unwrap();
+        assert_eq!(bodies.len(), 2);
+        assert_ne!(bodies[0], bodies[1]);
+        assert!(remote_write.pending.batches.is_empty());
+    }
+
+    #[test]
+    fn buffer_overflow() {
+        // nothing listens on the port, so writes fail and are buffered
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = config(
+            &format!("http://127.0.0.1:{}/api/v1/write", port),
+            "retries = 0\nbuffer = 4\n",
+        );
+        let mut remote_write = RemoteWrite::new(&config, metrics());
+        remote_write.push(&Shutdown::new());
+        assert_eq!(remote_write.pending.batches.len(), 1);
+        let first = remote_write.pending.batches[0][0].samples[0].timestamp;
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        remote_write.push(&Shutdown::new());
+
+        // the oldest batch is dropped to make room for the latest
+        assert_eq!(remote_write.pending.batches.len(), 1);
+        assert!(remote_write.pending.batches[0][0].samples[0].timestamp > first);
+    }
+
+    #[test]
+    fn shutdown() {
+        // once shutdown has been triggered a failed write is not retried
+        let (port, server) = stand_in(&["503 Service Unavailable"]);
+        let config = config(
+            &format!("http://127.0.0.1:{}/api/v1/write", port),
+            "retries = 3\n",
+        );
+        let mut remote_write = RemoteWrite::new(&config, metrics());
+        let shutdown = Shutdown::new();
+        shutdown.trigger(std::time::Instant::now() + std::time::Duration::from_secs(5));
+        let start = std::time::Instant::now();
+        remote_write.push(&shutdown);
+
+        assert_eq!(server.join().unwrap().len(), 1);
+        assert_eq!(remote_write.pending.batches.len(), 1);
+        assert!(start.elapsed() < RETRY_BACKOFF);
+    }
+}
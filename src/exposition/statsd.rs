@@ -0,0 +1,300 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::net::UdpSocket;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::common::shutdown::Shutdown;
+use crate::*;
+
+use crate::config::Config;
+use crate::exposition::{packets, prefix, Exporter, Filter, MetricsSnapshot, Sample};
+
+/// Pushes metrics to a StatsD or DogStatsD agent over UDP. Counters are sent
+/// as the increase since the previous push, and all other values as gauges.
+pub struct StatsD {
+    snapshot: MetricsSnapshot,
+    socket: UdpSocket,
+    address: String,
+    connected: bool,
+    prefix: String,
+    interval: Duration,
+    dogstatsd: bool,
+    max_packet_size: usize,
+    counters: HashMap<(String, Labels), u64>,
+}
+
+impl StatsD {
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Self {
+        let statsd = config.exposition().statsd();
+        let socket = match UdpSocket::bind("0.0.0.0:0") {
+            Ok(socket) => socket,
+            Err(e) => {
+                fatal!("failed to open socket for statsd: {}", e);
+            }
+        };
+        let mut exporter = Self {
+            snapshot: MetricsSnapshot::new(metrics, config.general().reading_suffix()),
+            socket,
+            address: statsd.address().to_string(),
+            connected: false,
+            prefix: prefix(statsd.prefix(), component),
+            interval: Duration::from_millis(statsd.interval().try_into().unwrap()),
+            dogstatsd: statsd.dogstatsd(),
+            max_packet_size: statsd.max_packet_size(),
+            counters: HashMap::new(),
+        };
+        exporter.connect();
+        exporter
+    }
+
+    /// Connect the socket to the agent, so that the address is only resolved
+    /// once rather than for every packet. Returns whether it is connected.
+    fn connect(&mut self) -> bool {
+        if !self.connected {
+            match self.socket.connect(&self.address) {
+                Ok(()) => self.connected = true,
+                Err(e) => error!("failed to connect to statsd at {}: {}", self.address, e),
+            }
+        }
+        self.connected
+    }
+
+    /// Renders a sample as StatsD lines. Counters are not sent until there is
+    /// a previous value to calculate the increase from.
+    fn lines(&mut self, sample: Sample) -> Vec<String> {
+        let (name, tags) = if self.dogstatsd {
+            let tags: Vec<String> = sample
+                .labels
+                .iter()
+                .map(|(k, v)| format!("{}:{}", tag(k), tag(v)))
+                .collect();
+            let tags = if tags.is_empty() {
+                String::new()
+            } else {
+                format!("|#{}", tags.join(","))
+            };
+            (format!("{}{}", self.prefix, path(&sample.name, None)), tags)
+        } else {
+            // without tags, labeled series are distinguished by their name
+            (
+                format!(
+                    "{}{}",
+                    self.prefix,
+                    path(&sample.name, Some(&sample.labels))
+                ),
+                String::new(),
+            )
+        };
+
+        if sample.counter {
+            if let MetricValue::Unsigned(value) = sample.value {
+                let previous = self.counters.insert((sample.name, sample.labels), value);
+                return match previous {
+                    // a decrease means the counter was reset
+                    Some(previous) => {
+                        let delta = value.checked_sub(previous).unwrap_or(value);
+                        vec![format!("{}:{}|c{}", name, delta, tags)]
+                    }
+                    None => Vec::new(),
+                };
+            }
+        }
+
+        let negative = match sample.value {
+            MetricValue::Float(value) if !value.is_finite() => return Vec::new(),
+            MetricValue::Float(value) => value < 0.0,
+            MetricValue::Signed(value) => value < 0,
+            _ => false,
+        };
+        if negative && !self.dogstatsd {
+            // a leading sign is a relative change in plain StatsD, so the
+            // gauge is zeroed before applying the negative value
+            vec![
+                format!("{}:0|g{}", name, tags),
+                format!("{}:{}|g{}", name, sample.value, tags),
+            ]
+        } else {
+            vec![format!("{}:{}|g{}", name, sample.value, tags)]
+        }
+    }
+}
+
+impl Exporter for StatsD {
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Snapshot the metrics and send them in packets no larger than the
+    /// limit.
+    fn push(&mut self, _shutdown: &Shutdown) {
+        self.snapshot.refresh();
+        let mut lines = Vec::new();
+        for sample in self.snapshot.samples(&Filter::default()) {
+            lines.extend(self.lines(sample));
+        }
+        if !self.connect() {
+            return;
+        }
+        for packet in packets(&lines, self.max_packet_size) {
+            if let Err(e) = self.socket.send(packet.as_bytes()) {
+                debug!("failed to send to statsd at {}: {}", self.address, e);
+                // the address is resolved again on the next push, in case it
+                // has changed
+                self.connected = false;
+            }
+        }
+    }
+}
+
+/// The sample name with `/` translated to `.`, optionally followed by the
+/// labels as `key_value` components
+fn path(name: &str, labels: Option<&Labels>) -> String {
+    let mut components: Vec<String> = name.split('/').map(component).collect();
+    if let Some(labels) = labels {
+        for (key, value) in labels.iter() {
+            components.push(format!("{}_{}", component(key), component(value)));
+        }
+    }
+    components.join(".")
+}
+
+/// Replaces characters which separate components or delimit fields
+fn component(component: &str) -> String {
+    component
+        .chars()
+        .map(|c| match c {
+            '.' | ':' | '|' | '@' | '#' | ',' | ' ' | '\n' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Replaces characters which delimit tags or fields
+fn tag(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            ':' | '|' | '@' | '#' | ',' | ' ' | '\n' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exporter(dogstatsd: bool) -> StatsD {
+        let config: Config = toml::from_str(&format!(
+            "[exposition.statsd]\n\
+             enabled = true\n\
+             prefix = \"rezolus\"\n\
+             dogstatsd = {}\n",
+            dogstatsd
+        ))
+        .expect("invalid config");
+        StatsD::new(&config, Arc::new(Metrics::new()))
+    }
+
+    fn sample(name: &str, labels: Labels, counter: bool, value: MetricValue) -> Sample {
+        Sample {
+            name: name.to_string(),
+            labels,
+            counter,
+            value,
+        }
+    }
+
+    #[test]
+    fn counter_deltas() {
+        let mut statsd = exporter(false);
+        let cpu = Labels::new().with("cpu", 0);
+        let usage = |value| {
+            sample(
+                "cpu/usage/user",
+                cpu.clone(),
+                true,
+                MetricValue::Unsigned(value),
+            )
+        };
+
+        // nothing is sent until there is an increase to report
+        assert!(statsd.lines(usage(1000)).is_empty());
+        assert_eq!(
+            statsd.lines(usage(1250)),
+            ["rezolus.cpu.usage.user.cpu_0:250|c"]
+        );
+        // after a reset the whole value is the increase
+        assert_eq!(
+            statsd.lines(usage(100)),
+            ["rezolus.cpu.usage.user.cpu_0:100|c"]
+        );
+    }
+
+    #[test]
+    fn dogstatsd_tags() {
+        let mut statsd = exporter(true);
+        let labels = Labels::new()
+            .with("device", "nvme0n1")
+            .with("model", "SSD 970|EVO");
+        assert_eq!(
+            statsd.lines(sample(
+                "disk/read/bytes",
+                labels,
+                false,
+                MetricValue::Unsigned(4096)
+            )),
+            ["rezolus.disk.read.bytes:4096|g|#device:nvme0n1,model:SSD_970_EVO"]
+        );
+        assert_eq!(
+            statsd.lines(sample(
+                "ntp/offset",
+                Labels::new(),
+                false,
+                MetricValue::Signed(1024)
+            )),
+            ["rezolus.ntp.offset:1024|g"]
+        );
+    }
+
+    #[test]
+    fn negative_gauges() {
+        let offset = || {
+            sample(
+                "ntp/offset",
+                Labels::new().with("source", "chrony"),
+                false,
+                MetricValue::Signed(-5),
+            )
+        };
+
+        // plain statsd would apply a signed value as a relative change
+        let mut statsd = exporter(false);
+        assert_eq!(
+            statsd.lines(offset()),
+            [
+                "rezolus.ntp.offset.source_chrony:0|g",
+                "rezolus.ntp.offset.source_chrony:-5|g",
+            ]
+        );
+
+        let mut dogstatsd = exporter(true);
+        assert_eq!(
+            dogstatsd.lines(offset()),
+            ["rezolus.ntp.offset:-5|g|#source:chrony"]
+        );
+        assert!(dogstatsd
+            .lines(sample(
+                "ntp/offset",
+                Labels::new(),
+                false,
+                MetricValue::Float(f64::NAN)
+            ))
+            .is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use rustcommon_logger::*;
+use tokio::runtime::Runtime;
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+use crate::common::shutdown::Shutdown;
+
+/// How often the certificate and private key are checked for changes
+const RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Holds the TLS configuration for the stats listeners. The certificate and
+/// private key are reloaded when either file is modified, so that renewed
+/// certificates are picked up without a restart.
+pub struct Tls {
+    certificate: PathBuf,
+    private_key: PathBuf,
+    config: RwLock<Arc<ServerConfig>>,
+    modified: Mutex<(Option<SystemTime>, Option<SystemTime>)>,
+}
+
+impl Tls {
+    pub fn new(certificate: &str, private_key: &str) -> Result<Self, anyhow::Error> {
+        let certificate = PathBuf::from(certificate);
+        let private_key = PathBuf::from(private_key);
+        let modified = (modified(&certificate), modified(&private_key));
+        let config = load(&certificate, &private_key)?;
+        Ok(Self {
+            certificate,
+            private_key,
+            config: RwLock::new(Arc::new(config)),
+            modified: Mutex::new(modified),
+        })
+    }
+
+    /// An acceptor using the most recently loaded certificate
+    pub fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.config.read().unwrap().clone())
+    }
+
+    /// Periodically reload the certificate and private key on the runtime
+    /// until shutdown
+    pub fn spawn(self: Arc<Self>, runtime: &Runtime, shutdown: Arc<Shutdown>) {
+        runtime.spawn(async move {
+            let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => self.reload(),
+                    _ = shutdown.wait() => break,
+                }
+            }
+        });
+    }
+
+    /// Reload the certificate and private key if either has been modified.
+    /// The previous configuration is kept if they fail to load.
+    fn reload(&self) {
+        let current = (modified(&self.certificate), modified(&self.private_key));
+        let mut previous = self.modified.lock().unwrap();
+        if current == *previous {
+            return;
+        }
+        match load(&self.certificate, &self.private_key) {
+            Ok(config) => {
+                info!("reloaded TLS certificate: {:?}", self.certificate);
+                *self.config.write().unwrap() = Arc::new(config);
+                *previous = current;
+            }
+            Err(e) => {
+                error!("failed to reload TLS certificate: {}", e);
+            }
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Load a PEM encoded certificate chain and private key
+fn load(certificate: &Path, private_key: &Path) -> Result<ServerConfig, anyhow::Error> {
+    let mut reader = BufReader::new(
+        File::open(certificate).map_err(|e| anyhow!("failed to open {:?}: {}", certificate, e))?,
+    );
+    let certificates: Vec<Certificate> = rustls_pemfile::certs(&mut reader)?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+    if certificates.is_empty() {
+        return Err(anyhow!("no certificates found in {:?}", certificate));
+    }
+
+    let mut reader = BufReader::new(
+        File::open(private_key).map_err(|e| anyhow!("failed to open {:?}: {}", private_key, e))?,
+    );
+    let key = rustls_pemfile::read_all(&mut reader)?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("no private key found in {:?}", private_key))?;
+
+    let mut config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certificates, key)?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(config)
+}
@@ -0,0 +1,159 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::ffi::CString;
+use std::io::{Error, ErrorKind};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+
+use crate::config::General;
+
+/// Bind a unix domain socket, replacing a stale socket left at the path by a
+/// previous run, and apply the configured mode and ownership.
+pub fn bind(path: &Path, config: &General) -> Result<UnixListener, Error> {
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                "path exists and is not a socket",
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+
+    let restricted = config.unix_socket_mode().is_some()
+        || config.unix_socket_owner().is_some()
+        || config.unix_socket_group().is_some();
+    let listener = if restricted {
+        // bind inside a directory only accessible by this user and move the
+        // socket into place once the configured mode and ownership apply, so
+        // that it is never reachable with looser permissions
+        let dir = staging_dir(path);
+        std::fs::DirBuilder::new().mode(0o700).create(&dir)?;
+        let staged = dir.join("socket");
+        let listener = UnixListener::bind(&staged)
+            .and_then(|listener| restrict(&staged, config).map(|_| listener))
+            .and_then(|listener| std::fs::rename(&staged, path).map(|_| listener));
+        let _ = std::fs::remove_file(&staged);
+        let _ = std::fs::remove_dir(&dir);
+        listener?
+    } else {
+        UnixListener::bind(path)?
+    };
+    listener.set_nonblocking(true)?;
+
+    Ok(listener)
+}
+
+/// A directory next to the socket path, so that the socket can be renamed
+/// into place on the same filesystem
+fn staging_dir(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.tmp", std::process::id()));
+    path.with_file_name(name)
+}
+
+/// Apply the configured mode and ownership to the socket
+fn restrict(path: &Path, config: &General) -> Result<(), Error> {
+    if let Some(mode) = config.unix_socket_mode() {
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    }
+
+    let owner = config.unix_socket_owner().map(user_id).transpose()?;
+    let group = config.unix_socket_group().map(group_id).transpose()?;
+    if owner.is_some() || group.is_some() {
+        let path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| Error::from(ErrorKind::InvalidInput))?;
+        // an id of -1 leaves that part of the ownership unchanged
+        let owner = owner.unwrap_or(libc::uid_t::MAX);
+        let group = group.unwrap_or(libc::gid_t::MAX);
+        if unsafe { libc::chown(path.as_ptr(), owner, group) } != 0 {
+            return Err(Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+/// Look up a user by name or numeric id
+fn user_id(user: &str) -> Result<libc::uid_t, Error> {
+    if let Ok(id) = user.parse() {
+        return Ok(id);
+    }
+    let name = CString::new(user).map_err(|_| Error::from(ErrorKind::InvalidInput))?;
+    let passwd = unsafe { libc::getpwnam(name.as_ptr()) };
+    if passwd.is_null() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("unknown user: {}", user),
+        ));
+    }
+    Ok(unsafe { (*passwd).pw_uid })
+}
+
+/// Look up a group by name or numeric id
+fn group_id(group: &str) -> Result<libc::gid_t, Error> {
+    if let Ok(id) = group.parse() {
+        return Ok(id);
+    }
+    let name = CString::new(group).map_err(|_| Error::from(ErrorKind::InvalidInput))?;
+    let entry = unsafe { libc::getgrnam(name.as_ptr()) };
+    if entry.is_null() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("unknown group: {}", group),
+        ));
+    }
+    Ok(unsafe { (*entry).gr_gid })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mode: Option<u32>) -> crate::config::Config {
+        let mode = mode
+            .map(|mode| format!("unix_socket_mode = {}", mode))
+            .unwrap_or_default();
+        toml::from_str(&format!("[general]\n{}\n", mode)).expect("invalid config")
+    }
+
+    fn socket_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rezolus-{}-{}.sock", name, std::process::id()))
+    }
+
+    #[test]
+    fn socket_mode() {
+        let path = socket_path("mode");
+        let _ = std::fs::remove_file(&path);
+
+        let _listener = bind(&path, config(Some(0o660)).general()).expect("failed to bind");
+        let metadata = std::fs::symlink_metadata(&path).expect("missing socket");
+        assert!(metadata.file_type().is_socket());
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o660);
+        assert!(!staging_dir(&path).exists());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn stale_socket() {
+        let path = socket_path("stale");
+        let _ = std::fs::remove_file(&path);
+
+        // a socket left behind by a previous run is replaced
+        drop(UnixListener::bind(&path).expect("failed to bind"));
+        let listener = bind(&path, config(None).general()).expect("failed to rebind");
+        std::os::unix::net::UnixStream::connect(&path).expect("failed to connect");
+        drop(listener);
+        std::fs::remove_file(&path).unwrap();
+
+        // but any other file is left in place
+        std::fs::write(&path, "").unwrap();
+        assert!(bind(&path, config(None).general()).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
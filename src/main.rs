@@ -8,10 +8,8 @@ extern crate rustcommon_logger;
 #[macro_use]
 extern crate anyhow;
 
-use rustcommon_atomics::{Atomic, Ordering};
 use std::sync::Arc;
 
-use rustcommon_atomics::AtomicBool;
 use rustcommon_logger::Logger;
 use tokio::runtime::Builder;
 
@@ -21,8 +19,11 @@ mod exposition;
 mod metrics;
 mod samplers;
 
+use common::shutdown::Shutdown;
+use common::status::Status;
 use common::*;
 use config::Config;
+use exposition::Exporter;
 use metrics::*;
 use samplers::*;
 
@@ -45,19 +46,43 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("----------");
     debug!("host cores: {}", hardware_threads().unwrap_or(1));
 
-    let runnable = Arc::new(AtomicBool::new(true));
-    let r = runnable.clone();
+    let (shutdown, signal) = std::sync::mpsc::channel();
 
     // initialize signal handler
     debug!("initializing signal handler");
     ctrlc::set_handler(move || {
-        r.store(false, Ordering::Relaxed);
+        let _ = shutdown.send(());
     })
     .expect("Failed to set handler for SIGINT / SIGTERM");
 
     // initialize metrics
     debug!("initializing metrics");
-    let metrics = Arc::new(Metrics::new());
+    let mut metrics = Metrics::new();
+    if !config.general().history().is_empty() {
+        let patterns: Vec<regex::Regex> = config
+            .general()
+            .history()
+            .iter()
+            .map(|pattern| match regex::Regex::new(pattern) {
+                Ok(pattern) => pattern,
+                Err(e) => {
+                    fatal!("invalid history pattern: {}", e);
+                }
+            })
+            .collect();
+        metrics.set_history(config.general().history_depth(), move |statistic| {
+            patterns
+                .iter()
+                .any(|pattern| pattern.is_match(statistic.name()))
+        });
+    }
+    if let Some(bounds) = config.general().histogram_bounds() {
+        if bounds.is_empty() || bounds.windows(2).any(|pair| pair[0] >= pair[1]) {
+            fatal!("histogram_bounds must be in increasing order");
+        }
+        metrics.set_histogram_bounds(bounds);
+    }
+    let metrics = Arc::new(metrics);
 
     // initialize async runtime
     debug!("initializing async runtime");
@@ -73,7 +98,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // spawn samplers
     debug!("spawning samplers");
-    let common = Common::new(config.clone(), metrics.clone(), runtime);
+    let status = Arc::new(Status::new());
+    let samplers = Arc::new(Shutdown::new());
+    let common = Common::new(
+        config.clone(),
+        metrics.clone(),
+        runtime.clone(),
+        samplers.clone(),
+        status.clone(),
+    );
     Cpu::spawn(common.clone());
     Disk::spawn(common.clone());
     Ext4::spawn(common.clone());
@@ -95,29 +128,116 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Usercall::spawn(common.clone());
     Xfs::spawn(common);
 
+    let exporters = Arc::new(Shutdown::new());
+    if config.exposition().file().enabled() {
+        spawn_exporter(
+            "file",
+            exposition::FileSink::new(&config, metrics.clone()),
+            &exporters,
+        );
+    }
+
+    if config.exposition().graphite().enabled() {
+        spawn_exporter(
+            "graphite",
+            exposition::Graphite::new(&config, metrics.clone()),
+            &exporters,
+        );
+    }
+
+    if config.exposition().influx().enabled() {
+        spawn_exporter(
+            "influx",
+            exposition::Influx::new(&config, metrics.clone()),
+            &exporters,
+        );
+    }
+
+    if config.exposition().otlp().enabled() {
+        spawn_exporter(
+            "otlp",
+            exposition::Otlp::new(&config, metrics.clone()),
+            &exporters,
+        );
+    }
+
+    if config.exposition().remote_write().enabled() {
+        spawn_exporter(
+            "remote_write",
+            exposition::RemoteWrite::new(&config, metrics.clone()),
+            &exporters,
+        );
+    }
+
+    if config.exposition().statsd().enabled() {
+        spawn_exporter(
+            "statsd",
+            exposition::StatsD::new(&config, metrics.clone()),
+            &exporters,
+        );
+    }
+
     #[cfg(feature = "push_kafka")]
     {
         if config.exposition().kafka().enabled() {
-            let mut kafka_producer =
-                exposition::KafkaProducer::new(config.clone(), metrics.clone());
-            let _ = std::thread::Builder::new()
-                .name("kafka".to_string())
-                .spawn(move || loop {
-                    kafka_producer.run();
-                });
+            spawn_exporter(
+                "kafka",
+                exposition::KafkaProducer::new(&config, metrics.clone()),
+                &exporters,
+            );
         }
     }
 
     debug!("beginning stats exposition");
-    let mut http = exposition::Http::new(
-        config.listen().expect("no listen address"),
-        metrics,
-        config.general().reading_suffix(),
-    );
-
-    while runnable.load(Ordering::Relaxed) {
-        http.run();
+    let listeners = Arc::new(Shutdown::new());
+    let http = exposition::Http::new(&config, metrics, status, listeners.clone());
+    http.spawn(&runtime);
+
+    // block until a signal is received
+    let _ = signal.recv();
+
+    // the samplers are stopped first so that the exporters push the final
+    // readings, and the listeners are closed last so the metrics can be
+    // scraped until then
+    info!("shutting down");
+    let deadline = std::time::Instant::now()
+        + std::time::Duration::from_millis(config.general().shutdown_timeout() as u64);
+    for (name, stage) in [
+        ("samplers", samplers),
+        ("exporters", exporters),
+        ("listeners", listeners),
+    ] {
+        stage.trigger(deadline);
+        if !stage.finished(deadline) {
+            error!("shutdown timed out waiting for {} to stop", name);
+            std::process::exit(1);
+        }
+        debug!("stopped {}", name);
     }
 
     Ok(())
 }
+
+/// Run a push exporter on its own thread, pushing on its interval until
+/// shutdown, and then push once more before the shutdown deadline so that the
+/// final readings are not lost
+fn spawn_exporter<T: Exporter>(name: &str, mut exporter: T, shutdown: &Arc<Shutdown>) {
+    let guard = shutdown.register();
+    let shutdown = shutdown.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name(name.to_string())
+        .spawn(move || {
+            while !shutdown.is_triggered() {
+                let start = std::time::Instant::now();
+                exporter.push(&shutdown);
+                if let Some(remaining) = exporter.interval().checked_sub(start.elapsed()) {
+                    shutdown.wait_timeout(remaining);
+                }
+            }
+            exporter.push(&shutdown);
+            drop(guard);
+        })
+    {
+        fatal!("failed to spawn {} exporter: {}", name, e);
+    }
+}
@@ -2,12 +2,18 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
-use crate::metrics::entry::Entry;
+use crate::metrics::histogram::{AtomicBuckets, Histogram};
+use crate::metrics::history::History;
+use crate::metrics::metrics::Readings;
 use crate::metrics::outputs::ApproxOutput;
 use crate::metrics::summary::SummaryStruct;
 use crate::metrics::traits::*;
+use crate::metrics::Kind;
+use crate::metrics::MetricValue;
 use crate::metrics::MetricsError;
+use crate::metrics::Moments;
 use crate::metrics::Output;
+use crate::metrics::Source;
 use crate::metrics::Summary;
 use rustcommon_atomics::Arithmetic;
 use rustcommon_atomics::AtomicU64;
@@ -17,40 +23,69 @@ use crossbeam::atomic::AtomicCell;
 use dashmap::DashSet;
 use rustcommon_atomics::{Atomic, AtomicBool, Ordering};
 
+use std::sync::Arc;
+
 /// Internal type which stores fields necessary to track a corresponding
 /// statistic.
 pub struct Channel {
     refreshed: AtomicCell<Instant<Nanoseconds<u64>>>,
-    statistic: Entry,
+    source: Source,
+    unit: Option<String>,
+    description: Option<String>,
     empty: AtomicBool,
+    kind: Kind,
     reading: AtomicU64,
     summary: Option<SummaryStruct>,
+    histogram: Option<AtomicBuckets>,
+    history: Option<History>,
+    wraps_at: Option<u64>,
     outputs: DashSet<ApproxOutput>,
 }
 
 impl Channel {
-    /// Creates an empty channel for a statistic.
-    pub fn new(statistic: &dyn Statistic) -> Self {
-        let summary = statistic.summary().map(|v| v.build());
+    /// Creates an empty channel for a statistic. Distributions export a
+    /// histogram with the provided bucket bounds.
+    pub fn new(statistic: &dyn Statistic, histogram_bounds: &Arc<[u64]>) -> Self {
+        let kind = if statistic.source() == Source::Gauge {
+            statistic.kind()
+        } else {
+            Kind::Unsigned
+        };
+        let summary = statistic.summary().map(|v| v.build(kind));
+        let histogram = if statistic.source() == Source::Distribution {
+            Some(AtomicBuckets::new(histogram_bounds.clone()))
+        } else {
+            None
+        };
         Self {
             empty: AtomicBool::new(true),
-            statistic: Entry::from(statistic),
+            source: statistic.source(),
+            unit: statistic.unit().map(|v| v.to_string()),
+            description: statistic.description().map(|v| v.to_string()),
+            kind,
             reading: Default::default(),
             refreshed: AtomicCell::new(Instant::<Nanoseconds<u64>>::now()),
             summary,
+            histogram,
+            history: None,
+            wraps_at: statistic.wraps_at(),
             outputs: Default::default(),
         }
     }
 
-    /// Records a bucket value + count pair into the summary.
+    /// Records a bucket value + count pair into the summary and the exported
+    /// histogram.
     pub fn record_bucket(
         &self,
         time: Instant<Nanoseconds<u64>>,
         value: u64,
         count: u32,
     ) -> Result<(), MetricsError> {
+        if let Some(histogram) = &self.histogram {
+            histogram.increment(value, count);
+        }
         if let Some(summary) = &self.summary {
-            summary.increment(time, value, count);
+            summary.increment(time, value, value.to_float(), count);
             Ok(())
         } else {
             Err(MetricsError::NoSummary)
@@ -58,28 +93,44 @@ impl Channel {
     }
 
     /// Updates a counter to a new value if the reading is newer than the stored
-    /// reading.
-    pub fn record_counter(&self, time: Instant<Nanoseconds<u64>>, value: u64) {
+    /// reading. Returns `true` if the counter was reset, in which case the new
+    /// value becomes the baseline and no rate is recorded into the summary.
+    pub fn record_counter(&self, time: Instant<Nanoseconds<u64>>, value: u64) -> bool {
         let t0 = self.refreshed.load();
         if time <= t0 {
-            return;
+            return false;
         }
         if !self.empty.load(Ordering::Relaxed) {
-            if let Some(summary) = &self.summary {
-                self.refreshed.store(time);
-                let v0 = self.reading.load(Ordering::Relaxed);
-                let dt = time - t0;
-                let dv = (value - v0).to_float();
-                let rate = (dv
-                    / (dt.as_secs() as f64 + dt.subsec_nanos() as f64 / 1_000_000_000.0))
-                    .ceil();
-                summary.increment(time, u64::from_float(rate), 1_u8.into());
-            }
+            let v0 = self.reading.load(Ordering::Relaxed);
+            let delta = if value >= v0 {
+                Some(value - v0)
+            } else {
+                match self.wraps_at {
+                    Some(max) if v0 < max => Some(max - v0 + value),
+                    _ => None,
+                }
+            };
+            self.refreshed.store(time);
             self.reading.store(value, Ordering::Relaxed);
+            self.record_history(time, value);
+            if let Some(delta) = delta {
+                if let Some(summary) = &self.summary {
+                    let dt = time - t0;
+                    let rate = (delta.to_float()
+                        / (dt.as_secs() as f64 + dt.subsec_nanos() as f64 / 1_000_000_000.0))
+                        .ceil();
+                    summary.increment(time, u64::from_float(rate), rate, 1_u8.into());
+                }
+                false
+            } else {
+                true
+            }
         } else {
             self.reading.store(value, Ordering::Relaxed);
             self.empty.store(false, Ordering::Relaxed);
             self.refreshed.store(time);
+            self.record_history(time, value);
+            false
         }
     }
 
@@ -91,6 +142,25 @@ impl Channel {
 
     /// Updates a gauge reading if the new value is newer than the stored value.
     pub fn record_gauge(&self, time: Instant<Nanoseconds<u64>>, value: u64) {
+        self.store_gauge(time, self.kind.encode_unsigned(value));
+    }
+
+    /// Updates a gauge reading with a signed value if the new value is newer
+    /// than the stored value.
+    pub fn record_gauge_signed(&self, time: Instant<Nanoseconds<u64>>, value: i64) {
+        self.store_gauge(time, self.kind.encode_signed(value));
+    }
+
+    /// Updates a gauge reading with a floating point value if the new value is
+    /// newer than the stored value.
+    pub fn record_gauge_float(&self, time: Instant<Nanoseconds<u64>>, value: f64) {
+        self.store_gauge(time, self.kind.encode_float(value));
+    }
+
+    /// Stores the bits of a gauge reading which has already been converted to
+    /// the kind of the channel. Values which are not a number are not recorded
+    /// into the summary.
+    fn store_gauge(&self, time: Instant<Nanoseconds<u64>>, bits: u64) {
         {
             let t0 = self.refreshed.load();
             if time <= t0 {
@@ -98,34 +168,102 @@ impl Channel {
             }
         }
         if let Some(summary) = &self.summary {
-            summary.increment(time, value, 1_u8.into());
+            let value = self.kind.decode_float(bits);
+            if !value.is_nan() {
+                summary.increment(time, self.kind.key(bits), value, 1_u8.into());
+            }
         }
-        self.reading.store(value, Ordering::Relaxed);
+        self.reading.store(bits, Ordering::Relaxed);
         self.empty.store(false, Ordering::Relaxed);
         self.refreshed.store(time);
+        self.record_history(time, bits);
+    }
+
+    fn record_history(&self, time: Instant<Nanoseconds<u64>>, bits: u64) {
+        if let Some(history) = &self.history {
+            history.push(time, bits);
+        }
     }
 
     /// Returns a percentile across stored readings/rates/...
-    pub fn percentile(&self, percentile: f64) -> Result<u64, MetricsError> {
+    pub fn percentile(&self, percentile: f64) -> Result<MetricValue, MetricsError> {
+        if let Some(summary) = &self.summary {
+            summary
+                .percentile(percentile)
+                .map(|key| self.kind.value(self.kind.decode_key(key)))
+                .map_err(MetricsError::from)
+        } else {
+            Err(MetricsError::NoSummary)
+        }
+    }
+
+    /// Returns the min, max, mean, standard deviation, and sample count across
+    /// stored readings/rates/...
+    pub fn moments(&self) -> Result<Moments, MetricsError> {
         if let Some(summary) = &self.summary {
-            summary.percentile(percentile).map_err(MetricsError::from)
+            summary.moments().map_err(MetricsError::from)
         } else {
             Err(MetricsError::NoSummary)
         }
     }
 
+    /// Returns the time of the most recent reading
+    pub fn refreshed(&self) -> Instant<Nanoseconds<u64>> {
+        self.refreshed.load()
+    }
+
     /// Returns the main reading for the channel (eg: counter, gauge)
-    pub fn reading(&self) -> Result<u64, MetricsError> {
+    pub fn reading(&self) -> Result<MetricValue, MetricsError> {
         if !self.empty.load(Ordering::Relaxed) {
-            Ok(self.reading.load(Ordering::Relaxed))
+            Ok(self.kind.value(self.reading.load(Ordering::Relaxed)))
         } else {
             Err(MetricsError::Empty)
         }
     }
 
+    /// Returns the cumulative bucket counts for a distribution channel
+    pub fn histogram(&self) -> Result<Histogram, MetricsError> {
+        if let Some(histogram) = &self.histogram {
+            let histogram = histogram.load();
+            if histogram.count() > 0 {
+                Ok(histogram)
+            } else {
+                Err(MetricsError::Empty)
+            }
+        } else {
+            Err(MetricsError::SourceMismatch)
+        }
+    }
+
+    /// Returns the retained readings with the time each was recorded and the
+    /// corresponding wall-clock time, oldest first. Empty if history is not enabled for the channel.
+    pub fn history(&self) -> Readings {
+        match &self.history {
+            Some(history) => history
+                .readings()
+                .into_iter()
+                .map(|(time, wall_clock, bits)| (time, wall_clock, self.kind.value(bits)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Whether the channel retains a history of readings
+    pub fn has_history(&self) -> bool {
+        self.history.is_some()
+    }
+
+    /// Retain up to `depth` of the most recent readings. Readings are only
+    /// retained for counters and gauges which are recorded with a time.
+    pub fn enable_history(&mut self, depth: usize) {
+        if self.source != Source::Distribution {
+            self.history = Some(History::new(depth));
+        }
+    }
+
     /// Set a summary to be used for an existing channel
     pub fn set_summary(&mut self, summary: Summary) {
-        let summary = summary.build();
+        let summary = summary.build(self.kind);
         self.summary = Some(summary);
     }
 
@@ -136,8 +274,25 @@ impl Channel {
         }
     }
 
-    pub fn statistic(&self) -> &dyn Statistic {
-        &self.statistic
+    /// The kind of values stored in the channel
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// The source of the statistic
+    pub fn source(&self) -> Source {
+        self.source
+    }
+
+    /// The unit of the statistic, included as metadata in the exposition
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+
+    /// The description of the statistic, included as metadata in the
+    /// exposition
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
     }
 
     pub fn outputs(&self) -> Vec<ApproxOutput> {
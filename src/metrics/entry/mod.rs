@@ -2,14 +2,123 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
+use core::borrow::Borrow;
 use core::hash::Hash;
 use core::hash::Hasher;
 
+use crate::metrics::channel::Channel;
 use crate::metrics::*;
 
+/// Internal type which identifies a channel by the name and labels of its
+/// statistic. These are the only fields which are hashed and compared.
+pub struct Key {
+    name: String,
+    labels: Labels,
+}
+
+impl Key {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn labels(&self) -> &Labels {
+        &self.labels
+    }
+}
+
+impl From<&dyn Statistic> for Key {
+    fn from(statistic: &dyn Statistic) -> Self {
+        Self {
+            name: statistic.name().to_string(),
+            labels: statistic.labels().clone(),
+        }
+    }
+}
+
+/// Borrowed form of a `Key`, which allows a channel to be looked up by a
+/// statistic without allocating an owned key.
+pub trait AsKey {
+    fn name(&self) -> &str;
+    fn labels(&self) -> &Labels;
+}
+
+impl AsKey for Key {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn labels(&self) -> &Labels {
+        &self.labels
+    }
+}
+
+impl AsKey for &dyn Statistic {
+    fn name(&self) -> &str {
+        Statistic::name(*self)
+    }
+
+    fn labels(&self) -> &Labels {
+        Statistic::labels(*self)
+    }
+}
+
+impl<'a> Borrow<dyn AsKey + 'a> for Key {
+    fn borrow(&self) -> &(dyn AsKey + 'a) {
+        self
+    }
+}
+
+impl Hash for dyn AsKey + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name().hash(state);
+        self.labels().hash(state);
+    }
+}
+
+impl PartialEq for dyn AsKey + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name() && self.labels() == other.labels()
+    }
+}
+
+impl Eq for dyn AsKey + '_ {}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self as &dyn AsKey).hash(state);
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.labels == other.labels
+    }
+}
+
+impl Eq for Key {}
+
+/// Internal type which holds a copy of a statistic for use in snapshots
 pub struct Entry {
     name: String,
     source: Source,
+    kind: Kind,
+    labels: Labels,
+    unit: Option<String>,
+    description: Option<String>,
+}
+
+impl Entry {
+    /// Copy the statistic which is tracked by a channel
+    pub fn new(key: &Key, channel: &Channel) -> Self {
+        Self {
+            name: key.name.clone(),
+            source: channel.source(),
+            kind: channel.kind(),
+            labels: key.labels.clone(),
+            unit: channel.unit().map(|v| v.to_string()),
+            description: channel.description().map(|v| v.to_string()),
+        }
+    }
 }
 
 impl Clone for Entry {
@@ -17,6 +126,10 @@ impl Clone for Entry {
         Self {
             name: self.name.clone(),
             source: self.source,
+            kind: self.kind,
+            labels: self.labels.clone(),
+            unit: self.unit.clone(),
+            description: self.description.clone(),
         }
     }
 }
@@ -29,25 +142,34 @@ impl Statistic for Entry {
     fn source(&self) -> Source {
         self.source
     }
+
+    fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    fn labels(&self) -> &Labels {
+        &self.labels
+    }
+
+    fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
 }
 
 impl Hash for Entry {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.name.hash(state);
+        self.labels.hash(state);
     }
 }
 
-impl From<&dyn Statistic> for Entry {
-    fn from(statistic: &dyn Statistic) -> Self {
-        Self {
-            name: statistic.name().to_string(),
-            source: statistic.source(),
-        }
-    }
-}
 impl PartialEq for Entry {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name
+        self.name == other.name && self.labels == other.labels
     }
 }
 
@@ -0,0 +1,134 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::sync::Arc;
+
+use rustcommon_atomics::{Arithmetic, Atomic, AtomicU64, Ordering};
+
+/// The number of finite buckets in an exported histogram with the default
+/// bounds
+const BUCKETS: usize = 39;
+
+/// Default inclusive upper bounds for the finite buckets of an exported
+/// histogram. These follow a 1-2-5 series from 1 to 5 * 10^12, giving three
+/// buckets per decade. The same bounds are used for every statistic so that
+/// histograms from different hosts and different statistics can be aggregated
+/// by downstream systems.
+pub const BUCKET_BOUNDS: [u64; BUCKETS] = bucket_bounds();
+
+const fn bucket_bounds() -> [u64; BUCKETS] {
+    let mut bounds = [0; BUCKETS];
+    let mut scale = 1;
+    let mut i = 0;
+    while i < BUCKETS {
+        bounds[i] = scale;
+        bounds[i + 1] = 2 * scale;
+        bounds[i + 2] = 5 * scale;
+        scale *= 10;
+        i += 3;
+    }
+    bounds
+}
+
+/// Internal type which tracks the bucket counts, sum, and count for a
+/// distribution. Unlike the heatmap summary, these counts are never aged out
+/// and are cumulative since startup, which allows consumers to compute rates
+/// and aggregate across hosts.
+pub(crate) struct AtomicBuckets {
+    bounds: Arc<[u64]>,
+    // one counter per finite bucket plus a trailing overflow bucket
+    buckets: Vec<AtomicU64>,
+    sum: AtomicU64,
+    count: AtomicU64,
+}
+
+impl AtomicBuckets {
+    /// Create empty buckets with the provided inclusive upper bounds, which
+    /// must be in increasing order
+    pub fn new(bounds: Arc<[u64]>) -> Self {
+        Self {
+            buckets: (0..=bounds.len()).map(|_| Default::default()).collect(),
+            bounds,
+            sum: Default::default(),
+            count: Default::default(),
+        }
+    }
+
+    /// Increment the bucket containing `value` by `count`. The sum and count
+    /// wrap around on overflow.
+    pub fn increment(&self, value: u64, count: u32) {
+        let index = self.bounds.partition_point(|bound| *bound < value);
+        self.buckets[index].fetch_add(count as u64, Ordering::Relaxed);
+        self.sum
+            .fetch_add(value.wrapping_mul(count as u64), Ordering::Relaxed);
+        self.count.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// Produce a point-in-time view with cumulative bucket counts.
+    pub fn load(&self) -> Histogram {
+        let mut cumulative = 0;
+        let buckets = self
+            .bounds
+            .iter()
+            .zip(self.buckets.iter())
+            .map(|(upper, bucket)| {
+                cumulative += bucket.load(Ordering::Relaxed);
+                Bucket {
+                    upper: *upper,
+                    count: cumulative,
+                }
+            })
+            .collect();
+        Histogram {
+            buckets,
+            sum: self.sum.load(Ordering::Relaxed),
+            count: self.count.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A point-in-time view of a distribution as cumulative bucket counts. The
+/// implicit `+Inf` bucket is equal to the total count.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Histogram {
+    buckets: Vec<Bucket>,
+    sum: u64,
+    count: u64,
+}
+
+impl Histogram {
+    /// The finite buckets, in order of increasing upper bound
+    pub fn buckets(&self) -> &[Bucket] {
+        &self.buckets
+    }
+
+    /// The sum of all values recorded into the histogram
+    pub fn sum(&self) -> u64 {
+        self.sum
+    }
+
+    /// The total number of values recorded into the histogram
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+/// A single histogram bucket with a cumulative count
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bucket {
+    upper: u64,
+    count: u64,
+}
+
+impl Bucket {
+    /// The inclusive upper bound of the bucket
+    pub fn upper(&self) -> u64 {
+        self.upper
+    }
+
+    /// The number of values less than or equal to the upper bound
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use rustcommon_time::{Instant, Nanoseconds};
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The time a reading was recorded, the corresponding wall-clock time, and the
+/// raw bits of the reading
+type Reading = (Instant<Nanoseconds<u64>>, SystemTime, u64);
+
+/// Internal type which retains the most recent readings of a statistic, with
+/// the time each was recorded and the wall-clock time at which that was.
+/// Readings are stored as the raw bits of the channel so they can be decoded
+/// according to its kind.
+pub(crate) struct History {
+    depth: usize,
+    readings: Mutex<VecDeque<Reading>>,
+}
+
+impl History {
+    /// A buffer which holds up to `depth` readings, dropping the oldest once
+    /// full
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            readings: Mutex::new(VecDeque::with_capacity(depth)),
+        }
+    }
+
+    pub fn push(&self, time: Instant<Nanoseconds<u64>>, bits: u64) {
+        if self.depth == 0 {
+            return;
+        }
+        let mut readings = self.readings.lock().unwrap();
+        if readings.len() >= self.depth {
+            readings.pop_front();
+        }
+        readings.push_back((time, wall_clock(time), bits));
+    }
+
+    /// The retained readings, oldest first
+    pub fn readings(&self) -> Vec<Reading> {
+        self.readings.lock().unwrap().iter().copied().collect()
+    }
+}
+
+/// The wall-clock time of a reading which was timed with the monotonic clock.
+/// This is derived from the age of the reading, so it should be called as the
+/// reading is recorded rather than later, when the wall clock may have been
+/// adjusted.
+pub(crate) fn wall_clock(time: Instant<Nanoseconds<u64>>) -> SystemTime {
+    let now = Instant::<Nanoseconds<u64>>::now();
+    let age = if time < now {
+        Duration::from_nanos((now - time).as_nanos())
+    } else {
+        Duration::ZERO
+    };
+    SystemTime::now().checked_sub(age).unwrap_or(UNIX_EPOCH)
+}
@@ -0,0 +1,112 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::metrics::traits::FloatConvert;
+use crate::metrics::MetricValue;
+
+const SIGN: u64 = 1 << 63;
+
+/// Defines the numeric type of the values recorded for a statistic. Counters
+/// and distributions are always unsigned, but gauges may hold signed or
+/// floating point values.
+#[derive(PartialEq, Eq, Debug, Hash, Copy, Clone)]
+pub enum Kind {
+    /// Values are unsigned 64bit integers.
+    Unsigned,
+    /// Values are signed 64bit integers.
+    Signed,
+    /// Values are 64bit floating point numbers.
+    Float,
+}
+
+// Values of every kind are stored as the raw bits of a `u64` so that they can
+// share the same atomic reading. Summaries are keyed by a transformation of
+// those bits which preserves the ordering of the values, which allows
+// percentiles to be calculated for negative and fractional values.
+impl Kind {
+    /// Convert an unsigned value to the stored bits for this kind
+    pub(crate) fn encode_unsigned(self, value: u64) -> u64 {
+        match self {
+            Self::Unsigned => value,
+            Self::Signed => value.min(i64::MAX as u64),
+            Self::Float => value.to_float().to_bits(),
+        }
+    }
+
+    /// Convert a signed value to the stored bits for this kind. Negative
+    /// values saturate to zero for unsigned statistics.
+    pub(crate) fn encode_signed(self, value: i64) -> u64 {
+        match self {
+            Self::Unsigned => value.max(0) as u64,
+            Self::Signed => value as u64,
+            Self::Float => value.to_float().to_bits(),
+        }
+    }
+
+    /// Convert a floating point value to the stored bits for this kind.
+    /// Fractional values are truncated and out of range values saturate for
+    /// integer statistics.
+    pub(crate) fn encode_float(self, value: f64) -> u64 {
+        match self {
+            Self::Unsigned => u64::from_float(value),
+            Self::Signed => i64::from_float(value) as u64,
+            Self::Float => value.to_bits(),
+        }
+    }
+
+    /// Interpret stored bits as a floating point value
+    pub(crate) fn decode_float(self, bits: u64) -> f64 {
+        match self {
+            Self::Unsigned => bits.to_float(),
+            Self::Signed => (bits as i64).to_float(),
+            Self::Float => f64::from_bits(bits),
+        }
+    }
+
+    /// Interpret stored bits as a typed value for a snapshot
+    pub(crate) fn value(self, bits: u64) -> MetricValue {
+        match self {
+            Self::Unsigned => MetricValue::Unsigned(bits),
+            Self::Signed => MetricValue::Signed(bits as i64),
+            Self::Float => MetricValue::Float(f64::from_bits(bits)),
+        }
+    }
+
+    /// Convert a floating point aggregate, such as a minimum or maximum, to a
+    /// typed value for a snapshot
+    pub(crate) fn float_value(self, value: f64) -> MetricValue {
+        self.value(self.encode_float(value))
+    }
+
+    /// Map stored bits to a summary key. Keys sort in the same order as the
+    /// values they represent.
+    pub(crate) fn key(self, bits: u64) -> u64 {
+        match self {
+            Self::Unsigned => bits,
+            Self::Signed => bits ^ SIGN,
+            Self::Float => {
+                if bits & SIGN == 0 {
+                    bits | SIGN
+                } else {
+                    !bits
+                }
+            }
+        }
+    }
+
+    /// Map a summary key back to the stored bits
+    pub(crate) fn decode_key(self, key: u64) -> u64 {
+        match self {
+            Self::Unsigned => key,
+            Self::Signed => key ^ SIGN,
+            Self::Float => {
+                if key & SIGN == 0 {
+                    !key
+                } else {
+                    key & !SIGN
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::metrics::{Kind, Source, Statistic, Summary};
+
+use std::collections::BTreeMap;
+
+/// A set of key-value pairs which further identify a statistic. This allows a
+/// single statistic to be tracked for multiple entities, such as per-CPU or
+/// per-device, without encoding the entity into the statistic name. Labels are
+/// kept sorted by key so that the same set of labels always hashes and renders
+/// identically.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Labels {
+    inner: BTreeMap<String, String>,
+}
+
+impl Labels {
+    /// Create a new empty set of labels
+    pub const fn new() -> Self {
+        Self {
+            inner: BTreeMap::new(),
+        }
+    }
+
+    /// Builder-style method to add a label to the set
+    pub fn with<T: ToString>(mut self, key: &str, value: T) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Add a label to the set, replacing any existing value for the key
+    pub fn insert<T: ToString>(&mut self, key: &str, value: T) {
+        self.inner.insert(key.to_string(), value.to_string());
+    }
+
+    /// Get the value for a label, if present
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.inner.get(key).map(|v| v.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Iterate over the key-value pairs, sorted by key
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.inner.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+/// Wraps an existing statistic with a set of labels. This allows samplers to
+/// reuse their statistic definitions when reporting per-entity breakdowns.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Labeled<S> {
+    statistic: S,
+    labels: Labels,
+}
+
+impl<S: Statistic> Labeled<S> {
+    pub fn new(statistic: S, labels: Labels) -> Self {
+        Self { statistic, labels }
+    }
+
+    /// Get the underlying unlabeled statistic
+    pub fn statistic(&self) -> &S {
+        &self.statistic
+    }
+}
+
+impl<S: Statistic> Statistic for Labeled<S> {
+    fn name(&self) -> &str {
+        self.statistic.name()
+    }
+
+    fn source(&self) -> Source {
+        self.statistic.source()
+    }
+
+    fn kind(&self) -> Kind {
+        self.statistic.kind()
+    }
+
+    fn summary(&self) -> Option<Summary> {
+        self.statistic.summary()
+    }
+
+    fn labels(&self) -> &Labels {
+        &self.labels
+    }
+
+    fn wraps_at(&self) -> Option<u64> {
+        self.statistic.wraps_at()
+    }
+
+    fn unit(&self) -> Option<&str> {
+        self.statistic.unit()
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.statistic.description()
+    }
+}
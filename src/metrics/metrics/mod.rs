@@ -3,16 +3,29 @@
 // http://www.apache.org/licenses/LICENSE-2.0
 
 use crate::metrics::channel::Channel;
-use crate::metrics::entry::Entry;
+use crate::metrics::entry::{AsKey, Entry, Key};
+use crate::metrics::histogram::BUCKET_BOUNDS;
 use crate::metrics::outputs::ApproxOutput;
+use crate::metrics::subscription::{Subscriber, Subscription};
 use crate::metrics::*;
 
 use core::hash::{Hash, Hasher};
 
 use dashmap::DashMap;
+use rustcommon_atomics::{Arithmetic, AtomicBool, Ordering};
 use rustcommon_time::Instant;
 
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+/// Selects a subset of the statistics, such as those which retain a history
+/// of their readings
+pub(crate) type Selector = Box<dyn Fn(&dyn Statistic) -> bool + Send + Sync>;
+
+/// Readings with the time each was recorded and the corresponding wall-clock
+/// time, oldest first
+pub type Readings = Vec<(Instant<Nanoseconds<u64>>, SystemTime, MetricValue)>;
 
 /// `Metrics` serves as a registry of outputs which are included in snapshots.
 /// In addition, it serves as the core storage of measurements and summary
@@ -20,13 +33,23 @@ use std::collections::HashMap;
 /// making it useful for serving as a unified metrics library in multi-threaded
 /// applications.
 pub struct Metrics {
-    channels: DashMap<String, Channel>,
+    channels: DashMap<Key, Channel>,
+    counter_resets: AtomicU64,
+    history: Option<(usize, Selector)>,
+    histogram_bounds: Arc<[u64]>,
+    subscribers: RwLock<Vec<Subscriber>>,
+    subscribed: AtomicBool,
 }
 
 impl Default for Metrics {
     fn default() -> Self {
         Self {
             channels: DashMap::new(),
+            counter_resets: Default::default(),
+            history: None,
+            histogram_bounds: Arc::from(&BUCKET_BOUNDS[..]),
+            subscribers: Default::default(),
+            subscribed: AtomicBool::new(false),
         }
     }
 }
@@ -40,15 +63,108 @@ impl Metrics {
     /// Begin tracking a new statistic without a corresponding output. Useful if
     /// metrics will be retrieved and reported manually in a command-line tool.
     pub fn register(&self, statistic: &dyn Statistic) {
-        if !self.channels.contains_key(statistic.name()) {
-            let channel = Channel::new(statistic);
-            self.channels.insert(statistic.name().to_string(), channel);
+        if !self.channels.contains_key(&statistic as &dyn AsKey) {
+            let mut channel = Channel::new(statistic, &self.histogram_bounds);
+            if let Some((depth, ref select)) = self.history {
+                if select(statistic) {
+                    channel.enable_history(depth);
+                }
+            }
+            self.channels.insert(Key::from(statistic), channel);
+        }
+    }
+
+    /// Retain up to `depth` of the most recent readings for each statistic
+    /// which is selected, so that they can be retrieved with `history`. This
+    /// only applies to statistics which are registered afterwards.
+    pub fn set_history<F>(&mut self, depth: usize, select: F)
+    where
+        F: Fn(&dyn Statistic) -> bool + Send + Sync + 'static,
+    {
+        self.history = Some((depth, Box::new(select)));
+    }
+
+    /// Use these inclusive upper bounds, in increasing order, for the buckets
+    /// of the exported histograms instead of the default 1-2-5 series. This
+    /// only applies to distributions which are registered afterwards.
+    pub fn set_histogram_bounds(&mut self, bounds: &[u64]) {
+        self.histogram_bounds = Arc::from(bounds);
+    }
+
+    /// Subscribe to the readings of counters and gauges which are selected,
+    /// as they are recorded. Up to `capacity` updates are buffered for the
+    /// subscription, beyond which they are dropped so that recording is never
+    /// blocked by a slow subscriber.
+    pub fn subscribe<F>(&self, capacity: usize, select: F) -> Subscription
+    where
+        F: Fn(&dyn Statistic) -> bool + Send + Sync + 'static,
+    {
+        let (subscriber, subscription) = Subscriber::new(capacity, select);
+        let mut subscribers = self.subscribers.write().unwrap();
+        subscribers.push(subscriber);
+        self.subscribed.store(true, Ordering::Relaxed);
+        subscription
+    }
+
+    /// Subscribe as with `subscribe`, unless there are already `limit`
+    /// subscriptions. The limit is checked while holding the same lock as the
+    /// subscription is added with, so concurrent callers can not exceed it.
+    pub fn try_subscribe<F>(&self, limit: usize, capacity: usize, select: F) -> Option<Subscription>
+    where
+        F: Fn(&dyn Statistic) -> bool + Send + Sync + 'static,
+    {
+        let mut subscribers = self.subscribers.write().unwrap();
+        subscribers.retain(|subscriber| !subscriber.is_closed());
+        if subscribers.len() >= limit {
+            return None;
         }
+        let (subscriber, subscription) = Subscriber::new(capacity, select);
+        subscribers.push(subscriber);
+        self.subscribed.store(true, Ordering::Relaxed);
+        Some(subscription)
+    }
+
+    /// The number of subscriptions which have not been dropped
+    pub fn subscriptions(&self) -> usize {
+        self.unsubscribe_closed();
+        self.subscribers.read().unwrap().len()
+    }
+
+    /// Send a reading to the subscribers if it was stored, as readings which
+    /// are older than the current reading are ignored
+    fn publish(
+        &self,
+        statistic: &dyn Statistic,
+        channel: &Channel,
+        time: Instant<Nanoseconds<u64>>,
+    ) {
+        if !self.subscribed.load(Ordering::Relaxed) || channel.refreshed() != time {
+            return;
+        }
+        let value = match channel.reading() {
+            Ok(value) => value,
+            Err(_) => return,
+        };
+        let mut closed = false;
+        for subscriber in self.subscribers.read().unwrap().iter() {
+            subscriber.publish(statistic, time, &value);
+            closed |= subscriber.is_closed();
+        }
+        if closed {
+            self.unsubscribe_closed();
+        }
+    }
+
+    fn unsubscribe_closed(&self) {
+        let mut subscribers = self.subscribers.write().unwrap();
+        subscribers.retain(|subscriber| !subscriber.is_closed());
+        self.subscribed
+            .store(!subscribers.is_empty(), Ordering::Relaxed);
     }
 
     /// Stop tracking a statistics and any corresponding outputs.
     pub fn deregister(&self, statistic: &dyn Statistic) {
-        self.channels.remove(statistic.name());
+        self.channels.remove(&statistic as &dyn AsKey);
     }
 
     /// Adds a new output to the registry which will be included in future
@@ -56,7 +172,7 @@ impl Metrics {
     /// registered.
     pub fn add_output(&self, statistic: &dyn Statistic, output: Output) {
         self.register(statistic);
-        if let Some(channel) = self.channels.get_mut(statistic.name()) {
+        if let Some(channel) = self.channels.get_mut(&statistic as &dyn AsKey) {
             channel.add_output(output);
         }
     }
@@ -66,7 +182,7 @@ impl Metrics {
     /// the statistic even if no outputs remain. Use `deregister` method to stop
     /// tracking a statistic entirely.
     pub fn remove_output(&self, statistic: &dyn Statistic, output: Output) {
-        if let Some(channel) = self.channels.get_mut(statistic.name()) {
+        if let Some(channel) = self.channels.get_mut(&statistic as &dyn AsKey) {
             channel.remove_output(output);
         }
     }
@@ -76,7 +192,7 @@ impl Metrics {
     /// a sampling rate is user configurable at runtime, the number of samples
     /// may need to be higher for stream summaries.
     pub fn set_summary(&self, statistic: &dyn Statistic, summary: Summary) {
-        if let Some(mut channel) = self.channels.get_mut(statistic.name()) {
+        if let Some(mut channel) = self.channels.get_mut(&statistic as &dyn AsKey) {
             channel.set_summary(summary);
         }
     }
@@ -85,7 +201,7 @@ impl Metrics {
     /// set. This may be used for dynamically registered statistic types to
     /// prevent clearing an existing summary.
     pub fn add_summary(&self, statistic: &dyn Statistic, summary: Summary) {
-        if let Some(mut channel) = self.channels.get_mut(statistic.name()) {
+        if let Some(mut channel) = self.channels.get_mut(&statistic as &dyn AsKey) {
             channel.add_summary(summary);
         }
     }
@@ -106,7 +222,7 @@ impl Metrics {
         count: u32,
     ) -> Result<(), MetricsError> {
         if statistic.source() == Source::Distribution {
-            if let Some(channel) = self.channels.get(statistic.name()) {
+            if let Some(channel) = self.channels.get(&statistic as &dyn AsKey) {
                 channel.record_bucket(time, value, count)
             } else {
                 // statistic not registered
@@ -120,7 +236,9 @@ impl Metrics {
 
     /// Record a counter observation for counter based statistics. May be used
     /// with any summary type. Summaries will track secondly rates for counter
-    /// changes.
+    /// changes. If the counter has decreased, and it is not expected to wrap,
+    /// it is treated as a reset: the value becomes the new baseline and the
+    /// reset is counted.
     pub fn record_counter(
         &self,
         statistic: &dyn Statistic,
@@ -128,8 +246,11 @@ impl Metrics {
         value: u64,
     ) -> Result<(), MetricsError> {
         if statistic.source() == Source::Counter {
-            if let Some(channel) = self.channels.get(statistic.name()) {
-                channel.record_counter(time, value);
+            if let Some(channel) = self.channels.get(&statistic as &dyn AsKey) {
+                if channel.record_counter(time, value) {
+                    self.counter_resets.fetch_add(1, Ordering::Relaxed);
+                }
+                self.publish(statistic, &channel, time);
                 Ok(())
             } else {
                 // statistic not registered
@@ -150,7 +271,7 @@ impl Metrics {
         value: u64,
     ) -> Result<(), MetricsError> {
         if statistic.source() == Source::Counter {
-            if let Some(channel) = self.channels.get(statistic.name()) {
+            if let Some(channel) = self.channels.get(&statistic as &dyn AsKey) {
                 channel.increment_counter(value);
                 Ok(())
             } else {
@@ -172,8 +293,9 @@ impl Metrics {
         value: u64,
     ) -> Result<(), MetricsError> {
         if statistic.source() == Source::Gauge {
-            if let Some(channel) = self.channels.get(statistic.name()) {
+            if let Some(channel) = self.channels.get(&statistic as &dyn AsKey) {
                 channel.record_gauge(time, value);
+                self.publish(statistic, &channel, time);
                 Ok(())
             } else {
                 // statistic not registered
@@ -185,49 +307,169 @@ impl Metrics {
         }
     }
 
+    /// Record a signed gauge observation. The value is converted to the kind
+    /// of the statistic, so negative values saturate to zero for unsigned
+    /// gauges.
+    pub fn record_gauge_signed(
+        &self,
+        statistic: &dyn Statistic,
+        time: Instant<Nanoseconds<u64>>,
+        value: i64,
+    ) -> Result<(), MetricsError> {
+        if statistic.source() == Source::Gauge {
+            if let Some(channel) = self.channels.get(&statistic as &dyn AsKey) {
+                channel.record_gauge_signed(time, value);
+                self.publish(statistic, &channel, time);
+                Ok(())
+            } else {
+                // statistic not registered
+                Err(MetricsError::NotRegistered)
+            }
+        } else {
+            // source mismatch
+            Err(MetricsError::SourceMismatch)
+        }
+    }
+
+    /// Record a floating point gauge observation. The value is converted to
+    /// the kind of the statistic, so fractional values are truncated for
+    /// integer gauges.
+    pub fn record_gauge_float(
+        &self,
+        statistic: &dyn Statistic,
+        time: Instant<Nanoseconds<u64>>,
+        value: f64,
+    ) -> Result<(), MetricsError> {
+        if statistic.source() == Source::Gauge {
+            if let Some(channel) = self.channels.get(&statistic as &dyn AsKey) {
+                channel.record_gauge_float(time, value);
+                self.publish(statistic, &channel, time);
+                Ok(())
+            } else {
+                // statistic not registered
+                Err(MetricsError::NotRegistered)
+            }
+        } else {
+            // source mismatch
+            Err(MetricsError::SourceMismatch)
+        }
+    }
+
+    /// Return the total number of counter resets which have been detected
+    /// across all statistics.
+    pub fn counter_resets(&self) -> u64 {
+        self.counter_resets.load(Ordering::Relaxed)
+    }
+
     /// Return a percentile for the given statistic. For counters, it is the
     /// percentile of secondly rates across the summary. For gauges, it is the
     /// percentile of gauge readings observed across the summary. For
-    /// distributions it is the percentile across the configured summary.
+    /// distributions it is the percentile across the configured summary. Use
+    /// `value` for signed and floating point statistics.
     pub fn percentile(
         &self,
         statistic: &dyn Statistic,
         percentile: f64,
     ) -> Result<u64, MetricsError> {
-        if let Some(channel) = self.channels.get(statistic.name()) {
-            channel.percentile(percentile)
+        if let Some(channel) = self.channels.get(&statistic as &dyn AsKey) {
+            channel.percentile(percentile).and_then(unsigned)
+        } else {
+            Err(MetricsError::NotRegistered)
+        }
+    }
+
+    /// Return the min, max, mean, standard deviation, and number of samples
+    /// for the given statistic. These are calculated across the same window
+    /// and values as percentiles.
+    pub fn moments(&self, statistic: &dyn Statistic) -> Result<Moments, MetricsError> {
+        if let Some(channel) = self.channels.get(&statistic as &dyn AsKey) {
+            channel.moments()
         } else {
             Err(MetricsError::NotRegistered)
         }
     }
 
     /// Return the reading for the statistic. For counters and gauges, this is
-    /// the most recent measurement recorded.
+    /// the most recent measurement recorded. Use `value` for signed and
+    /// floating point statistics.
     // TODO: decide on how to handle distribution channels
     pub fn reading(&self, statistic: &dyn Statistic) -> Result<u64, MetricsError> {
-        if let Some(channel) = self.channels.get(statistic.name()) {
-            channel.reading()
+        if let Some(channel) = self.channels.get(&statistic as &dyn AsKey) {
+            channel.reading().and_then(unsigned)
+        } else {
+            Err(MetricsError::NotRegistered)
+        }
+    }
+
+    /// Return the value of an output for the statistic, typed according to the
+    /// kind of the statistic. The output does not need to be registered.
+    pub fn value(
+        &self,
+        statistic: &dyn Statistic,
+        output: Output,
+    ) -> Result<MetricValue, MetricsError> {
+        if let Some(channel) = self.channels.get(&statistic as &dyn AsKey) {
+            value(&channel, output)
+        } else {
+            Err(MetricsError::NotRegistered)
+        }
+    }
+
+    /// Return the cumulative bucket counts for a distribution statistic. The
+    /// counts are never reset, so consumers should compute rates from them.
+    pub fn histogram(&self, statistic: &dyn Statistic) -> Result<Histogram, MetricsError> {
+        if let Some(channel) = self.channels.get(&statistic as &dyn AsKey) {
+            channel.histogram()
         } else {
             Err(MetricsError::NotRegistered)
         }
     }
 
+    /// Return the retained readings, oldest first, for each set of labels of
+    /// the statistic with the given name. Statistics which do not retain a
+    /// history are omitted.
+    pub fn history(&self, name: &str) -> Vec<(Labels, Readings)> {
+        let mut result: Vec<_> = self
+            .channels
+            .iter()
+            .filter(|entry| entry.key().name() == name)
+            .filter(|entry| entry.value().has_history())
+            .map(|entry| (entry.key().labels().clone(), entry.value().history()))
+            .collect();
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+        result
+    }
+
+    /// Generates a point-in-time snapshot of the bucket counts of every
+    /// distribution statistic, whether or not it has the histogram output.
+    pub fn histograms(&self) -> HashMap<Metric, MetricValue> {
+        let mut result = HashMap::new();
+        for entry in &self.channels {
+            let (key, channel) = entry.pair();
+            if let Ok(histogram) = channel.histogram() {
+                result.insert(
+                    Metric {
+                        statistic: Entry::new(key, channel),
+                        output: ApproxOutput::from(Output::Histogram),
+                    },
+                    MetricValue::Histogram(histogram),
+                );
+            }
+        }
+        result
+    }
+
     /// Generates a point-in-time snapshot of metric and value pairs.
-    pub fn snapshot(&self) -> HashMap<Metric, u64> {
+    pub fn snapshot(&self) -> HashMap<Metric, MetricValue> {
         #[allow(unused_mut)]
         let mut result = HashMap::new();
         for entry in &self.channels {
-            let (_name, channel) = entry.pair();
+            let (key, channel) = entry.pair();
             for output in channel.outputs() {
-                if let Ok(value) = match Output::from(output) {
-                    Output::Reading => self.reading(channel.statistic() as &dyn Statistic),
-                    Output::Percentile(percentile) => {
-                        self.percentile(channel.statistic(), percentile)
-                    }
-                } {
+                if let Ok(value) = value(channel, Output::from(output)) {
                     result.insert(
                         Metric {
-                            statistic: Entry::from(channel.statistic()),
+                            statistic: Entry::new(key, channel),
                             output,
                         },
                         value,
@@ -239,6 +481,67 @@ impl Metrics {
     }
 }
 
+/// Calculate the value of an output for a channel
+fn value(channel: &Channel, output: Output) -> Result<MetricValue, MetricsError> {
+    let kind = channel.kind();
+    match output {
+        Output::Reading => channel.reading(),
+        Output::Percentile(percentile) => channel.percentile(percentile),
+        Output::Histogram => channel.histogram().map(MetricValue::Histogram),
+        Output::Min => channel.moments().map(|m| kind.float_value(m.min())),
+        Output::Max => channel.moments().map(|m| kind.float_value(m.max())),
+        Output::Mean => channel.moments().map(|m| MetricValue::Float(m.mean())),
+        Output::Stddev => channel.moments().map(|m| MetricValue::Float(m.stddev())),
+        Output::Samples => channel
+            .moments()
+            .map(|m| MetricValue::Unsigned(m.samples())),
+    }
+}
+
+/// Unwrap an unsigned value, for the methods which predate signed and floating
+/// point statistics
+fn unsigned(value: MetricValue) -> Result<u64, MetricsError> {
+    match value {
+        MetricValue::Unsigned(value) => Ok(value),
+        _ => Err(MetricsError::SourceMismatch),
+    }
+}
+
+/// The value of a metric in a snapshot
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetricValue {
+    /// A single unsigned value, such as a reading or percentile
+    Unsigned(u64),
+    /// A single signed value, such as a reading of a signed gauge
+    Signed(i64),
+    /// A floating point value, such as a mean
+    Float(f64),
+    /// Cumulative bucket counts for a distribution
+    Histogram(Histogram),
+}
+
+impl core::fmt::Display for MetricValue {
+    /// Formats the value for text based exposition. Histograms are rendered
+    /// as their total count, as they require format specific handling. Non
+    /// finite floating point values are rendered as `NaN`, `+Inf`, and `-Inf`.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unsigned(value) => write!(f, "{}", value),
+            Self::Signed(value) => write!(f, "{}", value),
+            Self::Float(value) => {
+                if value.is_nan() {
+                    write!(f, "NaN")
+                } else if value.is_infinite() {
+                    write!(f, "{}Inf", if *value > 0.0 { "+" } else { "-" })
+                } else {
+                    write!(f, "{}", value)
+                }
+            }
+            Self::Histogram(histogram) => write!(f, "{}", histogram.count()),
+        }
+    }
+}
+
 /// A statistic and output pair which has a corresponding value
 // #[derive(PartialEq, Eq, Hash)]
 pub struct Metric {
@@ -248,14 +551,14 @@ pub struct Metric {
 
 impl Hash for Metric {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.statistic.name().hash(state);
+        self.statistic.hash(state);
         self.output.hash(state);
     }
 }
 
 impl PartialEq for Metric {
     fn eq(&self, other: &Self) -> bool {
-        self.statistic.name() == other.statistic.name() && self.output == other.output
+        self.statistic == other.statistic && self.output == other.output
     }
 }
 
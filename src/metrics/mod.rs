@@ -7,17 +7,28 @@
 mod channel;
 mod entry;
 mod error;
+mod histogram;
+mod history;
+mod kind;
+mod labels;
 #[allow(clippy::module_inception)]
 mod metrics;
+mod moments;
 mod outputs;
 mod source;
+mod subscription;
 mod summary;
 mod traits;
 
 pub use error::MetricsError;
-pub use metrics::{Metric, Metrics};
+pub use histogram::Histogram;
+pub use kind::Kind;
+pub use labels::{Labeled, Labels};
+pub use metrics::{Metric, MetricValue, Metrics};
+pub use moments::Moments;
 pub use outputs::Output;
 pub use source::Source;
+pub use subscription::Update;
 pub use summary::Summary;
 pub use traits::{Count, Primitive, Statistic, Value};
 
@@ -28,28 +39,76 @@ pub use rustcommon_time::*;
 
 #[cfg(test)]
 mod tests {
+    use super::histogram::BUCKET_BOUNDS;
     use super::*;
 
     enum TestStat {
         Alpha,
+        Beta,
+        Gamma,
+        Delta,
+        Epsilon,
     }
 
     impl Statistic for TestStat {
         fn name(&self) -> &str {
             match self {
                 Self::Alpha => "alpha",
+                Self::Beta => "beta",
+                Self::Gamma => "gamma",
+                Self::Delta => "delta",
+                Self::Epsilon => "epsilon",
             }
         }
 
         fn source(&self) -> Source {
             match self {
-                Self::Alpha => Source::Counter,
+                Self::Alpha | Self::Gamma => Source::Counter,
+                Self::Beta => Source::Distribution,
+                Self::Delta | Self::Epsilon => Source::Gauge,
+            }
+        }
+
+        fn kind(&self) -> Kind {
+            match self {
+                Self::Delta => Kind::Float,
+                Self::Epsilon => Kind::Signed,
+                _ => Kind::Unsigned,
             }
         }
 
         fn summary(&self) -> Option<Summary> {
             match self {
-                Self::Alpha => Some(Summary::stream(1000)),
+                Self::Alpha | Self::Gamma | Self::Delta | Self::Epsilon => {
+                    Some(Summary::stream(1000))
+                }
+                Self::Beta => Some(Summary::heatmap(
+                    10_000_000_000_000_000,
+                    2,
+                    Duration::from_secs(60),
+                    Duration::from_secs(1),
+                )),
+            }
+        }
+
+        fn wraps_at(&self) -> Option<u64> {
+            match self {
+                Self::Gamma => Some(1 << 32),
+                _ => None,
+            }
+        }
+
+        fn unit(&self) -> Option<&str> {
+            match self {
+                Self::Beta => Some("nanoseconds"),
+                _ => None,
+            }
+        }
+
+        fn description(&self) -> Option<&str> {
+            match self {
+                Self::Beta => Some("a test distribution"),
+                _ => None,
             }
         }
     }
@@ -117,6 +176,22 @@ mod tests {
         assert_ne!(metrics.reading(&TestStat::Alpha), Ok(42));
     }
 
+    #[test]
+    fn labels() {
+        let metrics = Metrics::new();
+        let cpu0 = Labeled::new(TestStat::Alpha, Labels::new().with("cpu", 0));
+        let cpu1 = Labeled::new(TestStat::Alpha, Labels::new().with("cpu", 1));
+        metrics.add_output(&cpu0, Output::Reading);
+        metrics.add_output(&cpu1, Output::Reading);
+        let now = Instant::<Nanoseconds<u64>>::now();
+        metrics.record_counter(&cpu0, now, 1).unwrap();
+        metrics.record_counter(&cpu1, now, 2).unwrap();
+        assert!(metrics.reading(&TestStat::Alpha).is_err());
+        assert_eq!(metrics.reading(&cpu0), Ok(1));
+        assert_eq!(metrics.reading(&cpu1), Ok(2));
+        assert_eq!(metrics.snapshot().len(), 2);
+    }
+
     #[test]
     fn increment_counter() {
         let metrics = Metrics::new();
@@ -129,4 +204,293 @@ mod tests {
         metrics.increment_counter(&TestStat::Alpha, 10).unwrap();
         assert_eq!(metrics.reading(&TestStat::Alpha), Ok(11));
     }
+
+    #[test]
+    fn histogram() {
+        let metrics = Metrics::new();
+        metrics.add_output(&TestStat::Beta, Output::Histogram);
+        assert_eq!(metrics.histogram(&TestStat::Beta), Err(MetricsError::Empty));
+        assert_eq!(
+            metrics.histogram(&TestStat::Alpha),
+            Err(MetricsError::NotRegistered)
+        );
+        let now = Instant::<Nanoseconds<u64>>::now();
+        metrics.record_bucket(&TestStat::Beta, now, 1, 1).unwrap();
+        metrics.record_bucket(&TestStat::Beta, now, 150, 2).unwrap();
+        metrics
+            .record_bucket(&TestStat::Beta, now, 6_000_000_000_000, 1)
+            .unwrap();
+        let histogram = metrics.histogram(&TestStat::Beta).unwrap();
+        assert_eq!(histogram.count(), 4);
+        assert_eq!(histogram.buckets().len(), BUCKET_BOUNDS.len());
+        let buckets = histogram.buckets();
+        assert_eq!((buckets[0].upper(), buckets[0].count()), (1, 1));
+        assert_eq!((buckets[6].upper(), buckets[6].count()), (100, 1));
+        assert_eq!((buckets[7].upper(), buckets[7].count()), (200, 3));
+        // values beyond the last finite bucket only appear in the total
+        assert_eq!(buckets[BUCKET_BOUNDS.len() - 1].count(), 3);
+        assert_eq!(metrics.snapshot().len(), 1);
+    }
+
+    #[test]
+    fn histogram_bounds() {
+        let mut metrics = Metrics::new();
+        metrics.set_histogram_bounds(&[1_000, 1_000_000]);
+        metrics.add_output(&TestStat::Beta, Output::Histogram);
+        let now = Instant::<Nanoseconds<u64>>::now();
+        metrics.record_bucket(&TestStat::Beta, now, 500, 1).unwrap();
+        metrics
+            .record_bucket(&TestStat::Beta, now, 1_000, 1)
+            .unwrap();
+        metrics
+            .record_bucket(&TestStat::Beta, now, 50_000, 2)
+            .unwrap();
+        metrics
+            .record_bucket(&TestStat::Beta, now, 2_000_000, 1)
+            .unwrap();
+        let histogram = metrics.histogram(&TestStat::Beta).unwrap();
+        let buckets: Vec<(u64, u64)> = histogram
+            .buckets()
+            .iter()
+            .map(|bucket| (bucket.upper(), bucket.count()))
+            .collect();
+        assert_eq!(buckets, [(1_000, 2), (1_000_000, 4)]);
+        assert_eq!(histogram.count(), 5);
+    }
+
+    #[test]
+    fn moments() {
+        let metrics = Metrics::new();
+        metrics.add_output(&TestStat::Alpha, Output::Max);
+        metrics.add_output(&TestStat::Alpha, Output::Mean);
+        assert_eq!(metrics.moments(&TestStat::Alpha), Err(MetricsError::Empty));
+        let start = Instant::<Nanoseconds<u64>>::now();
+        metrics.record_counter(&TestStat::Alpha, start, 0).unwrap();
+        metrics
+            .record_counter(&TestStat::Alpha, start + Duration::from_secs(1), 1000)
+            .unwrap();
+        metrics
+            .record_counter(&TestStat::Alpha, start + Duration::from_secs(2), 3000)
+            .unwrap();
+        let moments = metrics.moments(&TestStat::Alpha).unwrap();
+        assert_eq!(moments.samples(), 2);
+        assert_eq!(moments.min(), 1000.0);
+        assert_eq!(moments.max(), 2000.0);
+        assert_eq!(moments.mean(), 1500.0);
+        assert_eq!(moments.stddev(), 500.0);
+        assert_eq!(metrics.snapshot().len(), 2);
+    }
+
+    #[test]
+    fn counter_reset() {
+        let metrics = Metrics::new();
+        metrics.register(&TestStat::Alpha);
+        let start = Instant::<Nanoseconds<u64>>::now();
+        metrics
+            .record_counter(&TestStat::Alpha, start, 1000)
+            .unwrap();
+        metrics
+            .record_counter(&TestStat::Alpha, start + Duration::from_secs(1), 10)
+            .unwrap();
+        assert_eq!(metrics.reading(&TestStat::Alpha), Ok(10));
+        assert_eq!(metrics.counter_resets(), 1);
+        assert_eq!(metrics.moments(&TestStat::Alpha), Err(MetricsError::Empty));
+        metrics
+            .record_counter(&TestStat::Alpha, start + Duration::from_secs(2), 20)
+            .unwrap();
+        assert_eq!(metrics.percentile(&TestStat::Alpha, 100.0), Ok(10));
+    }
+
+    #[test]
+    fn counter_wrap() {
+        let metrics = Metrics::new();
+        metrics.register(&TestStat::Gamma);
+        let start = Instant::<Nanoseconds<u64>>::now();
+        metrics
+            .record_counter(&TestStat::Gamma, start, u32::MAX as u64 - 9)
+            .unwrap();
+        metrics
+            .record_counter(&TestStat::Gamma, start + Duration::from_secs(1), 10)
+            .unwrap();
+        assert_eq!(metrics.reading(&TestStat::Gamma), Ok(10));
+        assert_eq!(metrics.counter_resets(), 0);
+        assert_eq!(metrics.percentile(&TestStat::Gamma, 100.0), Ok(20));
+    }
+
+    #[test]
+    fn float_gauge() {
+        let metrics = Metrics::new();
+        metrics.add_output(&TestStat::Delta, Output::Reading);
+        metrics.add_output(&TestStat::Delta, Output::Min);
+        let start = Instant::<Nanoseconds<u64>>::now();
+        metrics
+            .record_gauge_float(&TestStat::Delta, start, -0.5)
+            .unwrap();
+        metrics
+            .record_gauge_float(&TestStat::Delta, start + Duration::from_secs(1), 1.25)
+            .unwrap();
+        metrics
+            .record_gauge(&TestStat::Delta, start + Duration::from_secs(2), 2)
+            .unwrap();
+        assert_eq!(
+            metrics.value(&TestStat::Delta, Output::Reading),
+            Ok(MetricValue::Float(2.0))
+        );
+        assert_eq!(
+            metrics.value(&TestStat::Delta, Output::Percentile(0.0)),
+            Ok(MetricValue::Float(-0.5))
+        );
+        assert_eq!(
+            metrics.value(&TestStat::Delta, Output::Percentile(50.0)),
+            Ok(MetricValue::Float(1.25))
+        );
+        assert_eq!(
+            metrics.value(&TestStat::Delta, Output::Min),
+            Ok(MetricValue::Float(-0.5))
+        );
+        assert_eq!(
+            metrics.reading(&TestStat::Delta),
+            Err(MetricsError::SourceMismatch)
+        );
+        assert_eq!(metrics.snapshot().len(), 2);
+    }
+
+    #[test]
+    fn signed_gauge() {
+        let metrics = Metrics::new();
+        metrics.register(&TestStat::Epsilon);
+        let start = Instant::<Nanoseconds<u64>>::now();
+        metrics
+            .record_gauge_signed(&TestStat::Epsilon, start, -100)
+            .unwrap();
+        metrics
+            .record_gauge_signed(&TestStat::Epsilon, start + Duration::from_secs(1), 50)
+            .unwrap();
+        metrics
+            .record_gauge_float(&TestStat::Epsilon, start + Duration::from_secs(2), -10.7)
+            .unwrap();
+        assert_eq!(
+            metrics.value(&TestStat::Epsilon, Output::Reading),
+            Ok(MetricValue::Signed(-10))
+        );
+        assert_eq!(
+            metrics.value(&TestStat::Epsilon, Output::Percentile(0.0)),
+            Ok(MetricValue::Signed(-100))
+        );
+        assert_eq!(
+            metrics.value(&TestStat::Epsilon, Output::Max),
+            Ok(MetricValue::Signed(50))
+        );
+        let moments = metrics.moments(&TestStat::Epsilon).unwrap();
+        assert_eq!(moments.mean(), -20.0);
+    }
+
+    #[test]
+    fn metadata() {
+        let metrics = Metrics::new();
+        let statistic = Labeled::new(TestStat::Beta, Labels::new().with("cpu", 0));
+        metrics.add_output(&statistic, Output::Histogram);
+        let now = Instant::<Nanoseconds<u64>>::now();
+        metrics.record_bucket(&statistic, now, 1, 1).unwrap();
+        let snapshot = metrics.snapshot();
+        let metric = snapshot.keys().next().unwrap();
+        assert_eq!(metric.statistic().unit(), Some("nanoseconds"));
+        assert_eq!(
+            metric.statistic().description(),
+            Some("a test distribution")
+        );
+    }
+
+    #[test]
+    fn history() {
+        let mut metrics = Metrics::new();
+        metrics.set_history(2, |statistic| statistic.name() != "gamma");
+        let cpu0 = Labeled::new(TestStat::Alpha, Labels::new().with("cpu", 0));
+        let cpu1 = Labeled::new(TestStat::Alpha, Labels::new().with("cpu", 1));
+        metrics.register(&cpu1);
+        metrics.register(&cpu0);
+        metrics.register(&TestStat::Gamma);
+        metrics.register(&TestStat::Epsilon);
+        let start = Instant::<Nanoseconds<u64>>::now();
+        for (i, value) in [1, 2, 3].iter().enumerate() {
+            let time = start + Duration::from_secs(i as u64);
+            metrics.record_counter(&cpu0, time, *value).unwrap();
+            metrics
+                .record_counter(&TestStat::Gamma, time, *value)
+                .unwrap();
+            metrics
+                .record_gauge_signed(&TestStat::Epsilon, time, -(*value as i64))
+                .unwrap();
+        }
+        // a reading which is not newer is not retained
+        metrics.record_counter(&cpu0, start, 4).unwrap();
+
+        let history = metrics.history("alpha");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].0, Labels::new().with("cpu", 0));
+        let readings: Vec<_> = history[0]
+            .1
+            .iter()
+            .map(|(time, _, value)| (*time, value.clone()))
+            .collect();
+        assert_eq!(
+            readings,
+            vec![
+                (start + Duration::from_secs(1), MetricValue::Unsigned(2)),
+                (start + Duration::from_secs(2), MetricValue::Unsigned(3)),
+            ]
+        );
+        assert_eq!(history[1].0, Labels::new().with("cpu", 1));
+        assert!(history[1].1.is_empty());
+
+        let history = metrics.history("epsilon");
+        assert_eq!(history[0].1[1].2, MetricValue::Signed(-3));
+        assert!(metrics.history("gamma").is_empty());
+    }
+
+    #[test]
+    fn subscribe() {
+        let metrics = Metrics::new();
+        metrics.register(&TestStat::Alpha);
+        metrics.register(&TestStat::Delta);
+        let mut subscription = metrics.subscribe(2, |statistic| statistic.name() == "alpha");
+        assert_eq!(metrics.subscriptions(), 1);
+        let start = Instant::<Nanoseconds<u64>>::now();
+        for i in 0..3 {
+            let time = start + Duration::from_secs(i);
+            metrics.record_counter(&TestStat::Alpha, time, i).unwrap();
+            metrics
+                .record_gauge_float(&TestStat::Delta, time, 1.5)
+                .unwrap();
+        }
+        // a reading which is not newer is not published
+        metrics.record_counter(&TestStat::Alpha, start, 42).unwrap();
+
+        let update = subscription.try_recv().unwrap();
+        assert_eq!(update.name, "alpha");
+        assert_eq!(update.time, start);
+        assert_eq!(update.value, MetricValue::Unsigned(0));
+        let update = subscription.try_recv().unwrap();
+        assert_eq!(update.value, MetricValue::Unsigned(1));
+        assert!(subscription.try_recv().is_none());
+        assert_eq!(subscription.dropped(), 1);
+
+        drop(subscription);
+        assert_eq!(metrics.subscriptions(), 0);
+    }
+
+    #[test]
+    fn try_subscribe() {
+        let metrics = Metrics::new();
+        let first = metrics.try_subscribe(2, 1, |_| true);
+        assert!(first.is_some());
+        let second = metrics.try_subscribe(2, 1, |_| true);
+        assert!(second.is_some());
+        assert!(metrics.try_subscribe(2, 1, |_| true).is_none());
+
+        // a dropped subscription makes room for another
+        drop(first);
+        assert!(metrics.try_subscribe(2, 1, |_| true).is_some());
+        assert_eq!(metrics.subscriptions(), 1);
+    }
 }
@@ -0,0 +1,171 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use rustcommon_time::{Duration, Instant, Nanoseconds};
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Summary statistics for the values recorded into a summary across its
+/// window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Moments {
+    samples: u64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    stddev: f64,
+}
+
+impl Moments {
+    /// The number of values in the window
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// The smallest value in the window
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// The largest value in the window
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// The arithmetic mean of the values in the window
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// The population standard deviation of the values in the window
+    pub fn stddev(&self) -> f64 {
+        self.stddev
+    }
+}
+
+/// Aggregates for all values recorded within a single slice of time
+struct Slice {
+    start: Instant<Nanoseconds<u64>>,
+    count: u64,
+    sum: f64,
+    sum_squares: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Slice {
+    fn new(start: Instant<Nanoseconds<u64>>) -> Self {
+        Self {
+            start,
+            count: 0,
+            sum: 0.0,
+            sum_squares: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    fn increment(&mut self, value: f64, count: u32) {
+        let weighted = value * count as f64;
+        self.count += count as u64;
+        self.sum += weighted;
+        self.sum_squares += weighted * value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+}
+
+/// Internal type which tracks the moments of a summary across the same window
+/// as the summary itself. Values are grouped into slices which are dropped
+/// once they fall out of the window.
+pub(crate) struct MomentsWindow {
+    capacity: usize,
+    span: Option<Duration<Nanoseconds<u64>>>,
+    resolution: Option<Duration<Nanoseconds<u64>>>,
+    slices: Mutex<VecDeque<Slice>>,
+}
+
+impl MomentsWindow {
+    /// A window covering a span of time, with values grouped into slices of
+    /// the provided resolution. Matches the window of a heatmap summary.
+    pub fn timed(span: Duration<Nanoseconds<u64>>, resolution: Duration<Nanoseconds<u64>>) -> Self {
+        let capacity = (span.as_nanos() / resolution.as_nanos().max(1)) as usize + 1;
+        Self {
+            capacity,
+            span: Some(span),
+            resolution: Some(resolution),
+            slices: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// A window covering the most recent values. Matches the window of a
+    /// stream summary.
+    pub fn samples(samples: usize) -> Self {
+        Self {
+            capacity: samples,
+            span: None,
+            resolution: None,
+            slices: Mutex::new(VecDeque::with_capacity(samples)),
+        }
+    }
+
+    pub fn increment(&self, time: Instant<Nanoseconds<u64>>, value: f64, count: u32) {
+        if count == 0 {
+            return;
+        }
+        let mut slices = self.slices.lock().unwrap();
+        let current = match (slices.back(), self.resolution) {
+            (Some(slice), Some(resolution)) => time < slice.start + resolution,
+            _ => false,
+        };
+        if !current {
+            slices.push_back(Slice::new(time));
+        }
+        if let Some(slice) = slices.back_mut() {
+            slice.increment(value, count);
+        }
+        while slices.len() > self.capacity {
+            slices.pop_front();
+        }
+        if let Some(span) = self.span {
+            while slices.front().map(|s| s.start + span <= time) == Some(true) {
+                slices.pop_front();
+            }
+        }
+    }
+
+    /// Calculate the moments across the window. Returns `None` if there are
+    /// no values in the window.
+    pub fn moments(&self) -> Option<Moments> {
+        let now = Instant::<Nanoseconds<u64>>::now();
+        let slices = self.slices.lock().unwrap();
+        let mut total = Slice::new(now);
+        for slice in slices.iter() {
+            if let Some(span) = self.span {
+                if slice.start + span <= now {
+                    continue;
+                }
+            }
+            total.count += slice.count;
+            total.sum += slice.sum;
+            total.sum_squares += slice.sum_squares;
+            total.min = total.min.min(slice.min);
+            total.max = total.max.max(slice.max);
+        }
+        if total.count == 0 {
+            return None;
+        }
+        let samples = total.count as f64;
+        let mean = total.sum / samples;
+        let variance = (total.sum_squares / samples - mean * mean).max(0.0);
+        Some(Moments {
+            samples: total.count,
+            min: total.min,
+            max: total.max,
+            mean,
+            stddev: variance.sqrt(),
+        })
+    }
+}
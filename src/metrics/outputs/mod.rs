@@ -7,6 +7,12 @@
 pub enum ApproxOutput {
     Reading,
     Percentile(u64),
+    Histogram,
+    Min,
+    Max,
+    Mean,
+    Stddev,
+    Samples,
 }
 
 /// Defines an output that should be reported in a snapshot for a statistic
@@ -16,6 +22,18 @@ pub enum Output {
     Reading,
     /// A percentile from a statistic summary
     Percentile(f64),
+    /// Cumulative bucket counts for a distribution
+    Histogram,
+    /// The minimum value across the summary window
+    Min,
+    /// The maximum value across the summary window
+    Max,
+    /// The mean of the values across the summary window
+    Mean,
+    /// The standard deviation of the values across the summary window
+    Stddev,
+    /// The number of samples in the summary window
+    Samples,
 }
 
 impl From<Output> for ApproxOutput {
@@ -25,6 +43,12 @@ impl From<Output> for ApproxOutput {
             Output::Percentile(percentile) => {
                 Self::Percentile((percentile * 1000000.0).ceil() as u64)
             }
+            Output::Histogram => Self::Histogram,
+            Output::Min => Self::Min,
+            Output::Max => Self::Max,
+            Output::Mean => Self::Mean,
+            Output::Stddev => Self::Stddev,
+            Output::Samples => Self::Samples,
         }
     }
 }
@@ -34,6 +58,12 @@ impl From<ApproxOutput> for Output {
         match output {
             ApproxOutput::Reading => Self::Reading,
             ApproxOutput::Percentile(percentile) => Self::Percentile(percentile as f64 / 1000000.0),
+            ApproxOutput::Histogram => Self::Histogram,
+            ApproxOutput::Min => Self::Min,
+            ApproxOutput::Max => Self::Max,
+            ApproxOutput::Mean => Self::Mean,
+            ApproxOutput::Stddev => Self::Stddev,
+            ApproxOutput::Samples => Self::Samples,
         }
     }
 }
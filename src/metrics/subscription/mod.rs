@@ -0,0 +1,105 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::metrics::history::wall_clock;
+use crate::metrics::metrics::Selector;
+use crate::metrics::{Labels, MetricValue, Statistic};
+
+use rustcommon_atomics::{Arithmetic, Atomic, AtomicU64, Ordering};
+use rustcommon_time::{Instant, Nanoseconds};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// A reading of a statistic, published to subscribers as it is recorded
+#[derive(Clone, Debug, PartialEq)]
+pub struct Update {
+    pub name: String,
+    pub labels: Labels,
+    pub time: Instant<Nanoseconds<u64>>,
+    /// The wall-clock time of the reading, as of when it was recorded
+    pub timestamp: SystemTime,
+    pub value: MetricValue,
+}
+
+/// Internal type which holds the sending half of a subscription along with
+/// the statistics it selects.
+pub(crate) struct Subscriber {
+    select: Selector,
+    sender: Sender<Update>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Subscriber {
+    /// Create a subscriber with a buffer of `capacity` updates and the
+    /// corresponding subscription.
+    pub fn new<F>(capacity: usize, select: F) -> (Self, Subscription)
+    where
+        F: Fn(&dyn Statistic) -> bool + Send + Sync + 'static,
+    {
+        let (sender, receiver) = channel(capacity.max(1));
+        let dropped = Arc::new(AtomicU64::new(0));
+        let subscriber = Self {
+            select: Box::new(select),
+            sender,
+            dropped: dropped.clone(),
+        };
+        (subscriber, Subscription { receiver, dropped })
+    }
+
+    /// Send an update if the statistic is selected. This never blocks, so if
+    /// the buffer is full the update is dropped and counted instead.
+    pub fn publish(
+        &self,
+        statistic: &dyn Statistic,
+        time: Instant<Nanoseconds<u64>>,
+        value: &MetricValue,
+    ) {
+        if !(self.select)(statistic) {
+            return;
+        }
+        let update = Update {
+            name: statistic.name().to_string(),
+            labels: statistic.labels().clone(),
+            time,
+            timestamp: wall_clock(time),
+            value: value.clone(),
+        };
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(update) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether the subscription has been dropped
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+/// The receiving half of a subscription to the readings of a set of
+/// statistics. Dropping it ends the subscription.
+pub struct Subscription {
+    receiver: Receiver<Update>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Subscription {
+    /// Wait for the next update
+    pub async fn recv(&mut self) -> Option<Update> {
+        self.receiver.recv().await
+    }
+
+    /// Take the next update if one is buffered
+    pub fn try_recv(&mut self) -> Option<Update> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// The total number of updates which were dropped because the buffer was
+    /// full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
@@ -3,28 +3,48 @@
 // http://www.apache.org/licenses/LICENSE-2.0
 
 use crate::metrics::error::SummaryError;
+use crate::metrics::moments::MomentsWindow;
 use crate::metrics::*;
 
 use rustcommon_heatmap::{AtomicHeatmap, Duration, Instant};
 use rustcommon_streamstats::AtomicStreamstats;
 
 pub(crate) enum SummaryStruct {
-    Heatmap(AtomicHeatmap<u64, AtomicU32>),
-    Stream(AtomicStreamstats<AtomicU64>),
+    Heatmap(AtomicHeatmap<u64, AtomicU32>, MomentsWindow),
+    Stream(AtomicStreamstats<AtomicU64>, MomentsWindow),
 }
 
 impl SummaryStruct {
-    pub fn increment(&self, time: Instant<Nanoseconds<u64>>, value: u64, count: u32) {
+    /// Record a value into the summary. The key is used for percentiles and
+    /// must sort in the same order as the values, while the value itself is
+    /// used for the moments.
+    pub fn increment(&self, time: Instant<Nanoseconds<u64>>, key: u64, value: f64, count: u32) {
         match self {
-            Self::Heatmap(heatmap) => heatmap.increment(time, value, count),
-            Self::Stream(stream) => stream.insert(value),
+            Self::Heatmap(heatmap, moments) => {
+                heatmap.increment(time, key, count);
+                moments.increment(time, value, count);
+            }
+            Self::Stream(stream, moments) => {
+                stream.insert(key);
+                moments.increment(time, value, 1);
+            }
         }
     }
 
     pub fn percentile(&self, percentile: f64) -> Result<u64, SummaryError> {
         match self {
-            Self::Heatmap(heatmap) => heatmap.percentile(percentile).map_err(SummaryError::from),
-            Self::Stream(stream) => stream.percentile(percentile).map_err(SummaryError::from),
+            Self::Heatmap(heatmap, _) => heatmap.percentile(percentile).map_err(SummaryError::from),
+            Self::Stream(stream, _) => stream.percentile(percentile).map_err(SummaryError::from),
+        }
+    }
+
+    /// The min, max, mean, standard deviation, and number of samples across
+    /// the window of the summary.
+    pub fn moments(&self) -> Result<Moments, SummaryError> {
+        match self {
+            Self::Heatmap(_, moments) | Self::Stream(_, moments) => {
+                moments.moments().ok_or(SummaryError::Empty)
+            }
         }
     }
 
@@ -34,11 +54,17 @@ impl SummaryStruct {
         span: Duration<Nanoseconds<u64>>,
         resolution: Duration<Nanoseconds<u64>>,
     ) -> Self {
-        Self::Heatmap(AtomicHeatmap::new(max, precision, span, resolution))
+        Self::Heatmap(
+            AtomicHeatmap::new(max, precision, span, resolution),
+            MomentsWindow::timed(span, resolution),
+        )
     }
 
     pub fn stream(samples: usize) -> Self {
-        Self::Stream(AtomicStreamstats::new(samples))
+        Self::Stream(
+            AtomicStreamstats::new(samples),
+            MomentsWindow::samples(samples),
+        )
     }
 }
 
@@ -74,10 +100,18 @@ impl Summary {
         }
     }
 
-    pub(crate) fn build(&self) -> SummaryStruct {
+    /// Build the summary for a statistic with the given kind of values.
+    /// Heatmaps only apply to unsigned values, so for other kinds a stream
+    /// summary with one sample per resolution across the span is used instead.
+    pub(crate) fn build(&self, kind: Kind) -> SummaryStruct {
         match self.inner {
             SummaryType::Heatmap(max, precision, span, resolution) => {
-                SummaryStruct::heatmap(max, precision, span, resolution)
+                if kind == Kind::Unsigned {
+                    SummaryStruct::heatmap(max, precision, span, resolution)
+                } else {
+                    let samples = span.as_nanos() / resolution.as_nanos().max(1);
+                    SummaryStruct::stream(samples as usize + 1)
+                }
             }
             SummaryType::Stream(samples) => SummaryStruct::stream(samples),
         }
@@ -42,3 +42,21 @@ impl FloatConvert for u8 {
         value as Self
     }
 }
+
+impl FloatConvert for i64 {
+    fn to_float(self) -> f64 {
+        self as f64
+    }
+    fn from_float(value: f64) -> Self {
+        value as Self
+    }
+}
+
+impl FloatConvert for f64 {
+    fn to_float(self) -> f64 {
+        self
+    }
+    fn from_float(value: f64) -> Self {
+        value
+    }
+}
@@ -2,7 +2,7 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
-use crate::metrics::{Source, Summary};
+use crate::metrics::{Kind, Labels, Source, Summary};
 
 use core::hash::{Hash, Hasher};
 
@@ -18,22 +18,51 @@ pub trait Statistic {
     fn name(&self) -> &str;
     /// Indicates which source type the statistic tracks.
     fn source(&self) -> Source;
+    /// Indicates the numeric type of the recorded values. Only gauges may be
+    /// signed or floating point, other sources are always unsigned.
+    fn kind(&self) -> Kind {
+        Kind::Unsigned
+    }
     /// Optionally, specify a summary builder which configures a summary
     /// aggregation for producing additional metrics such as percentiles.
     fn summary(&self) -> Option<Summary> {
         None
     }
+    /// Optionally, specify a set of labels which further identify the
+    /// statistic. Statistics which share a name but have different labels are
+    /// tracked independently.
+    fn labels(&self) -> &Labels {
+        static EMPTY: Labels = Labels::new();
+        &EMPTY
+    }
+    /// Optionally, specify the value at which a counter wraps back to zero,
+    /// eg: `1 << 32` for counters which are 32-bit at their source. A decrease
+    /// in such a counter is treated as a wraparound rather than a reset.
+    fn wraps_at(&self) -> Option<u64> {
+        None
+    }
+    /// Optionally, specify the unit of the recorded values, eg: `nanoseconds`
+    /// or `bytes`. This is included as metadata in the exposition format.
+    fn unit(&self) -> Option<&str> {
+        None
+    }
+    /// Optionally, provide a short human readable description of the
+    /// statistic. This is included as metadata in the exposition format.
+    fn description(&self) -> Option<&str> {
+        None
+    }
 }
 
 impl Hash for dyn Statistic {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.name().to_string().hash(state);
+        self.name().hash(state);
+        self.labels().hash(state);
     }
 }
 
 impl PartialEq for dyn Statistic {
     fn eq(&self, other: &Self) -> bool {
-        self.name() == other.name()
+        self.name() == other.name() && self.labels() == other.labels()
     }
 }
 
@@ -5,7 +5,7 @@
 use serde_derive::Deserialize;
 use strum::IntoEnumIterator;
 
-use crate::config::SamplerConfig;
+use crate::config::{SamplerConfig, SummaryOutput};
 
 use super::stat::*;
 
@@ -19,6 +19,10 @@ pub struct CpuConfig {
     #[serde(default = "crate::common::default_percentiles")]
     percentiles: Vec<f64>,
     #[serde(default)]
+    summaries: Vec<SummaryOutput>,
+    #[serde(default)]
+    per_cpu: bool,
+    #[serde(default)]
     perf_events: bool,
     #[serde(default = "default_statistics")]
     statistics: Vec<CpuStatistic>,
@@ -30,6 +34,8 @@ impl Default for CpuConfig {
             enabled: Default::default(),
             interval: Default::default(),
             percentiles: crate::common::default_percentiles(),
+            summaries: Default::default(),
+            per_cpu: Default::default(),
             perf_events: Default::default(),
             statistics: default_statistics(),
         }
@@ -40,6 +46,13 @@ fn default_statistics() -> Vec<CpuStatistic> {
     CpuStatistic::iter().collect()
 }
 
+impl CpuConfig {
+    /// Whether usage and perf counters should also be reported per-CPU
+    pub fn per_cpu(&self) -> bool {
+        self.per_cpu
+    }
+}
+
 impl SamplerConfig for CpuConfig {
     type Statistic = CpuStatistic;
     fn enabled(&self) -> bool {
@@ -54,6 +67,10 @@ impl SamplerConfig for CpuConfig {
         &self.percentiles
     }
 
+    fn summaries(&self) -> &[SummaryOutput] {
+        &self.summaries
+    }
+
     fn perf_events(&self) -> bool {
         self.perf_events
     }
@@ -31,6 +31,7 @@ pub use stat::*;
 #[allow(dead_code)]
 pub struct Cpu {
     common: Common,
+    cpu_labels: HashMap<u64, Labels>,
     cpus: HashSet<String>,
     cstates: HashMap<String, String>,
     cstate_files: HashMap<String, HashMap<String, File>>,
@@ -50,12 +51,14 @@ pub fn nanos_per_tick() -> u64 {
 #[async_trait]
 impl Sampler for Cpu {
     type Statistic = CpuStatistic;
+    const NAME: &'static str = "cpu";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let statistics = common.config().samplers().cpu().statistics();
         #[allow(unused_mut)]
         let mut sampler = Self {
             common,
+            cpu_labels: HashMap::new(),
             cpus: HashSet::new(),
             cstates: HashMap::new(),
             cstate_files: HashMap::new(),
@@ -68,6 +71,9 @@ impl Sampler for Cpu {
 
         if sampler.sampler_config().enabled() {
             sampler.register();
+            if sampler.common().config().samplers().cpu().per_cpu() {
+                sampler.register_per_cpu();
+            }
         }
 
         // we initialize perf last so we can delay
@@ -93,12 +99,9 @@ impl Sampler for Cpu {
 
     fn spawn(common: Common) {
         if common.config().samplers().cpu().enabled() {
-            if let Ok(mut cpu) = Cpu::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = cpu.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(cpu) = Cpu::new(common.clone()) {
+                common.runtime().spawn(cpu.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize cpu sampler");
             } else {
@@ -152,6 +155,22 @@ impl Sampler for Cpu {
 }
 
 impl Cpu {
+    /// Registers the per-CPU breakdown of statistics, labeled with the CPU id
+    /// and the NUMA node the CPU belongs to.
+    fn register_per_cpu(&mut self) {
+        let cpus = crate::common::hardware_threads().unwrap_or(1);
+        for cpu in 0..cpus {
+            let mut labels = Labels::new().with("cpu", cpu);
+            if let Some(node) = self.common().hardware_info().get_numa(cpu) {
+                labels.insert("node", node);
+            }
+            for statistic in self.statistics.iter().filter(|s| s.per_cpu()) {
+                self.register_statistic(&Labeled::new(*statistic, labels.clone()));
+            }
+            self.cpu_labels.insert(cpu, labels);
+        }
+    }
+
     #[cfg(feature = "bpf")]
     fn initialize_bpf_perf(&mut self) -> Result<(), std::io::Error> {
         let cpus = crate::common::hardware_threads().unwrap();
@@ -170,7 +189,7 @@ impl Cpu {
             include_str!("perf.c").to_string()
         );
         let mut perf_array_attached = false;
-        if let Ok(mut bpf) = bcc::BPF::new(&code) {
+        if let Ok(bpf) = bcc::BPF::new(&code) {
             for statistic in &self.statistics {
                 if let Some(table) = statistic.table() {
                     if let Some(event) = statistic.event() {
@@ -227,9 +246,15 @@ impl Cpu {
 
             let mut reader = BufReader::new(file);
             let mut result = HashMap::new();
+            let mut per_cpu = Vec::new();
             let mut buf = String::new();
             while reader.read_line(&mut buf).await? > 0 {
                 result.extend(parse_proc_stat(&buf));
+                if !self.cpu_labels.is_empty() {
+                    if let Some(usage) = parse_proc_stat_cpu(&buf) {
+                        per_cpu.push(usage);
+                    }
+                }
                 buf.clear();
             }
 
@@ -241,6 +266,19 @@ impl Cpu {
                         .record_counter(&stat, time, value * self.tick_duration);
                 }
             }
+            for (cpu, usage) in per_cpu {
+                if let Some(labels) = self.cpu_labels.get(&cpu) {
+                    for stat in self.statistics.iter().filter(|s| s.per_cpu()) {
+                        if let Some(value) = usage.get(stat) {
+                            let _ = self.metrics().record_counter(
+                                &Labeled::new(*stat, labels.clone()),
+                                time,
+                                value * self.tick_duration,
+                            );
+                        }
+                    }
+                }
+            }
         }
 
         Ok(())
@@ -284,8 +322,15 @@ impl Cpu {
                 if let Ok(table) = &(*bpf).inner.table(stat.table().unwrap()) {
                     let map = crate::common::bpf::perf_table_to_map(table);
                     let mut total = 0;
-                    for (_cpu, count) in map.iter() {
+                    for (cpu, count) in map.iter() {
                         total += count;
+                        if let Some(labels) = self.cpu_labels.get(&(*cpu as u64)) {
+                            let _ = self.metrics().record_counter(
+                                &Labeled::new(*stat, labels.clone()),
+                                time,
+                                *count,
+                            );
+                        }
                     }
                     let _ = self.metrics().record_counter(stat, time, total);
                 }
@@ -392,42 +437,36 @@ impl Cpu {
 }
 
 fn parse_proc_stat(line: &str) -> HashMap<CpuStatistic, u64> {
+    let mut parts = line.split_whitespace();
+    if parts.next() != Some("cpu") {
+        return HashMap::new();
+    }
+    parse_usage(parts)
+}
+
+/// Parses a per-CPU row of `/proc/stat`, returning the CPU id and its usage
+fn parse_proc_stat_cpu(line: &str) -> Option<(u64, HashMap<CpuStatistic, u64>)> {
+    let mut parts = line.split_whitespace();
+    let cpu = parts.next()?.strip_prefix("cpu")?.parse().ok()?;
+    Some((cpu, parse_usage(parts)))
+}
+
+fn parse_usage<'a>(parts: impl Iterator<Item = &'a str>) -> HashMap<CpuStatistic, u64> {
     let mut result = HashMap::new();
-    for (id, part) in line.split_whitespace().enumerate() {
-        match id {
-            0 => {
-                if part != "cpu" {
-                    return result;
-                }
-            }
-            1 => {
-                result.insert(CpuStatistic::UsageUser, part.parse().unwrap_or(0));
-            }
-            2 => {
-                result.insert(CpuStatistic::UsageNice, part.parse().unwrap_or(0));
-            }
-            3 => {
-                result.insert(CpuStatistic::UsageSystem, part.parse().unwrap_or(0));
-            }
-            4 => {
-                result.insert(CpuStatistic::UsageIdle, part.parse().unwrap_or(0));
-            }
-            6 => {
-                result.insert(CpuStatistic::UsageIrq, part.parse().unwrap_or(0));
-            }
-            7 => {
-                result.insert(CpuStatistic::UsageSoftirq, part.parse().unwrap_or(0));
-            }
-            8 => {
-                result.insert(CpuStatistic::UsageSteal, part.parse().unwrap_or(0));
-            }
-            9 => {
-                result.insert(CpuStatistic::UsageGuest, part.parse().unwrap_or(0));
-            }
-            10 => {
-                result.insert(CpuStatistic::UsageGuestNice, part.parse().unwrap_or(0));
-            }
-            _ => {}
+    for (id, part) in parts.enumerate() {
+        if let Some(statistic) = match id {
+            0 => Some(CpuStatistic::UsageUser),
+            1 => Some(CpuStatistic::UsageNice),
+            2 => Some(CpuStatistic::UsageSystem),
+            3 => Some(CpuStatistic::UsageIdle),
+            5 => Some(CpuStatistic::UsageIrq),
+            6 => Some(CpuStatistic::UsageSoftirq),
+            7 => Some(CpuStatistic::UsageSteal),
+            8 => Some(CpuStatistic::UsageGuest),
+            9 => Some(CpuStatistic::UsageGuestNice),
+            _ => None,
+        } {
+            result.insert(statistic, part.parse().unwrap_or(0));
         }
     }
     result
@@ -455,6 +494,18 @@ mod test {
         assert_eq!(result.get(&CpuStatistic::UsageSystem), Some(&53564));
     }
 
+    #[test]
+    fn test_parse_proc_stat_cpu() {
+        let line = "cpu3 5025 0 2043 204137 1011 0 1092 0 0 0";
+        assert!(parse_proc_stat(line).is_empty());
+        let (cpu, result) = parse_proc_stat_cpu(line).expect("failed to parse");
+        assert_eq!(cpu, 3);
+        assert_eq!(result.get(&CpuStatistic::UsageUser), Some(&5025));
+        assert_eq!(result.get(&CpuStatistic::UsageSoftirq), Some(&1092));
+        assert!(parse_proc_stat_cpu("cpu  131586 0 53564 8246483").is_none());
+        assert!(parse_proc_stat_cpu("intr 114930548 113199788 3").is_none());
+    }
+
     #[test]
     fn test_parse_frequency() {
         let result = parse_frequency("cpu MHz         : 1979.685");
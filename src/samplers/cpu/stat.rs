@@ -9,7 +9,8 @@ use bcc::perf_event::*;
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[derive(
     Clone,
@@ -17,6 +18,7 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -26,63 +28,129 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum CpuStatistic {
-    #[strum(serialize = "cpu/usage/user")]
+    #[strum(
+        serialize = "cpu/usage/user",
+        message = "nanoseconds spent in user-space"
+    )]
     UsageUser,
-    #[strum(serialize = "cpu/usage/nice")]
+    #[strum(
+        serialize = "cpu/usage/nice",
+        message = "nanoseconds spent on lower-priority tasks"
+    )]
     UsageNice,
-    #[strum(serialize = "cpu/usage/system")]
+    #[strum(
+        serialize = "cpu/usage/system",
+        message = "nanoseconds spent in kernel-space"
+    )]
     UsageSystem,
-    #[strum(serialize = "cpu/usage/idle")]
+    #[strum(serialize = "cpu/usage/idle", message = "nanoseconds spent idle")]
     UsageIdle,
-    #[strum(serialize = "cpu/usage/irq")]
+    #[strum(
+        serialize = "cpu/usage/irq",
+        message = "nanoseconds spent handling interrupts"
+    )]
     UsageIrq,
-    #[strum(serialize = "cpu/usage/softirq")]
+    #[strum(
+        serialize = "cpu/usage/softirq",
+        message = "nanoseconds spent handling soft interrupts"
+    )]
     UsageSoftirq,
-    #[strum(serialize = "cpu/usage/steal")]
+    #[strum(
+        serialize = "cpu/usage/steal",
+        message = "nanoseconds stolen by the hypervisor"
+    )]
     UsageSteal,
-    #[strum(serialize = "cpu/usage/guest")]
+    #[strum(
+        serialize = "cpu/usage/guest",
+        message = "nanoseconds spent running a guest VM"
+    )]
     UsageGuest,
-    #[strum(serialize = "cpu/usage/guestnice")]
+    #[strum(
+        serialize = "cpu/usage/guestnice",
+        message = "nanoseconds spent running a low-priority guest VM"
+    )]
     UsageGuestNice,
-    #[strum(serialize = "cpu/cache/miss")]
+    #[strum(
+        serialize = "cpu/cache/miss",
+        message = "cache accesses resulting in miss"
+    )]
     CacheMiss,
-    #[strum(serialize = "cpu/cache/access")]
+    #[strum(serialize = "cpu/cache/access", message = "total cache accesses")]
     CacheAccess,
-    #[strum(serialize = "cpu/bpu/branch")]
+    #[strum(serialize = "cpu/bpu/branch", message = "total branch instructions")]
     BpuBranches,
-    #[strum(serialize = "cpu/bpu/miss")]
+    #[strum(
+        serialize = "cpu/bpu/miss",
+        message = "branch predictions resulting in miss"
+    )]
     BpuMiss,
-    #[strum(serialize = "cpu/cycles")]
+    #[strum(serialize = "cpu/cycles", message = "cpu cycles elapsed")]
     Cycles,
-    #[strum(serialize = "cpu/dtlb/load/miss")]
+    #[strum(
+        serialize = "cpu/dtlb/load/miss",
+        message = "dtlb loads resulting in miss"
+    )]
     DtlbLoadMiss,
-    #[strum(serialize = "cpu/dtlb/load/access")]
+    #[strum(serialize = "cpu/dtlb/load/access", message = "total dtlb loads")]
     DtlbLoadAccess,
-    #[strum(serialize = "cpu/dtlb/store/access")]
+    #[strum(serialize = "cpu/dtlb/store/access", message = "total dtlb stores")]
     DtlbStoreAccess,
-    #[strum(serialize = "cpu/dtlb/store/miss")]
+    #[strum(
+        serialize = "cpu/dtlb/store/miss",
+        message = "dtlb stores resulting in miss"
+    )]
     DtlbStoreMiss,
-    #[strum(serialize = "cpu/instructions")]
+    #[strum(serialize = "cpu/instructions", message = "instructions retired")]
     Instructions,
-    #[strum(serialize = "cpu/reference_cycles")]
+    #[strum(
+        serialize = "cpu/reference_cycles",
+        message = "reference number of cpu cycles elapsed"
+    )]
     ReferenceCycles,
-    #[strum(serialize = "cpu/cstate/c0/time")]
+    #[strum(
+        serialize = "cpu/cstate/c0/time",
+        message = "nanoseconds spent in c0 state, active mode"
+    )]
     CstateC0Time,
-    #[strum(serialize = "cpu/cstate/c1/time")]
+    #[strum(
+        serialize = "cpu/cstate/c1/time",
+        message = "nanoseconds spent in c1 state, auto halt"
+    )]
     CstateC1Time,
-    #[strum(serialize = "cpu/cstate/c1e/time")]
+    #[strum(
+        serialize = "cpu/cstate/c1e/time",
+        message = "nanoseconds spent in c1e state, auto halt with low frequency and voltage"
+    )]
     CstateC1ETime,
-    #[strum(serialize = "cpu/cstate/c2/time")]
+    #[strum(
+        serialize = "cpu/cstate/c2/time",
+        message = "nanoseconds spent in c2 state"
+    )]
     CstateC2Time,
-    #[strum(serialize = "cpu/cstate/c3/time")]
+    #[strum(
+        serialize = "cpu/cstate/c3/time",
+        message = "nanoseconds spent in c3 state, L1/L2 flush and clocks off"
+    )]
     CstateC3Time,
-    #[strum(serialize = "cpu/cstate/c6/time")]
+    #[strum(
+        serialize = "cpu/cstate/c6/time",
+        message = "nanoseconds spent in c6 state, core state saved and PLL off"
+    )]
     CstateC6Time,
-    #[strum(serialize = "cpu/cstate/c7/time")]
+    #[strum(
+        serialize = "cpu/cstate/c7/time",
+        message = "nanoseconds spent in c7 state, c6 and LLC may flush"
+    )]
     CstateC7Time,
-    #[strum(serialize = "cpu/cstate/c8/time")]
+    #[strum(
+        serialize = "cpu/cstate/c8/time",
+        message = "nanoseconds spent in c8 state, c7 and LLC must flush"
+    )]
     CstateC8Time,
-    #[strum(serialize = "cpu/frequency")]
+    #[strum(
+        serialize = "cpu/frequency",
+        message = "instantaneous cpu frequency in Hz"
+    )]
     Frequency,
 }
 
@@ -97,9 +165,53 @@ impl Statistic for CpuStatistic {
             _ => Source::Counter,
         }
     }
+
+    fn unit(&self) -> Option<&str> {
+        match self {
+            Self::UsageUser
+            | Self::UsageNice
+            | Self::UsageSystem
+            | Self::UsageIdle
+            | Self::UsageIrq
+            | Self::UsageSoftirq
+            | Self::UsageSteal
+            | Self::UsageGuest
+            | Self::UsageGuestNice
+            | Self::CstateC0Time
+            | Self::CstateC1Time
+            | Self::CstateC1ETime
+            | Self::CstateC2Time
+            | Self::CstateC3Time
+            | Self::CstateC6Time
+            | Self::CstateC7Time
+            | Self::CstateC8Time => Some("nanoseconds"),
+            Self::Frequency => Some("hertz"),
+            _ => None,
+        }
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
 
 impl CpuStatistic {
+    /// Indicates whether the statistic can be broken down per-CPU
+    pub fn per_cpu(self) -> bool {
+        matches!(
+            self,
+            Self::UsageUser
+                | Self::UsageNice
+                | Self::UsageSystem
+                | Self::UsageIdle
+                | Self::UsageIrq
+                | Self::UsageSoftirq
+                | Self::UsageSteal
+                | Self::UsageGuest
+                | Self::UsageGuestNice
+        ) || self.table().is_some()
+    }
+
     #[cfg(feature = "bpf")]
     pub fn event(self) -> Option<Event> {
         match self {
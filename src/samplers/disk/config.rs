@@ -5,7 +5,7 @@
 use serde_derive::Deserialize;
 use strum::IntoEnumIterator;
 
-use crate::config::SamplerConfig;
+use crate::config::{SamplerConfig, SummaryOutput};
 
 use super::stat::*;
 
@@ -16,10 +16,18 @@ pub struct DiskConfig {
     bpf: bool,
     #[serde(default)]
     enabled: bool,
+    #[serde(default = "default_include")]
+    include: String,
+    #[serde(default)]
+    exclude: Option<String>,
     #[serde(default)]
     interval: Option<usize>,
+    #[serde(default)]
+    per_device: bool,
     #[serde(default = "crate::common::default_percentiles")]
     percentiles: Vec<f64>,
+    #[serde(default)]
+    summaries: Vec<SummaryOutput>,
     #[serde(default = "default_statistics")]
     statistics: Vec<DiskStatistic>,
 }
@@ -29,8 +37,12 @@ impl Default for DiskConfig {
         Self {
             bpf: Default::default(),
             enabled: Default::default(),
+            include: default_include(),
+            exclude: Default::default(),
             interval: Default::default(),
+            per_device: Default::default(),
             percentiles: crate::common::default_percentiles(),
+            summaries: Default::default(),
             statistics: default_statistics(),
         }
     }
@@ -40,6 +52,29 @@ fn default_statistics() -> Vec<DiskStatistic> {
     DiskStatistic::iter().collect()
 }
 
+// matches whole disks, but not their partitions or stacked devices
+fn default_include() -> String {
+    r"^((sd[a-z]+)|(hd[a-z]+)|(vd[a-z]+)|(xvd[a-z]+)|(nvme\d+n\d+))$".to_string()
+}
+
+impl DiskConfig {
+    /// Regular expression for the device names which should be sampled
+    pub fn include(&self) -> &str {
+        &self.include
+    }
+
+    /// Optional regular expression for device names which should be excluded
+    /// even if they match the include pattern
+    pub fn exclude(&self) -> Option<&str> {
+        self.exclude.as_deref()
+    }
+
+    /// Whether statistics should also be reported for each device
+    pub fn per_device(&self) -> bool {
+        self.per_device
+    }
+}
+
 impl SamplerConfig for DiskConfig {
     type Statistic = DiskStatistic;
 
@@ -59,6 +94,10 @@ impl SamplerConfig for DiskConfig {
         &self.percentiles
     }
 
+    fn summaries(&self) -> &[SummaryOutput] {
+        &self.summaries
+    }
+
     fn statistics(&self) -> Vec<<Self as SamplerConfig>::Statistic> {
         let mut enabled = Vec::new();
         for statistic in self.statistics.iter() {
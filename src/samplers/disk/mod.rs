@@ -2,9 +2,7 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
-use std::collections::HashMap;
-#[cfg(feature = "bpf")]
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::SeekFrom;
 use std::sync::{Arc, Mutex};
 
@@ -29,26 +27,40 @@ pub struct Disk {
     bpf: Option<Arc<Mutex<BPF>>>,
     bpf_last: Arc<Mutex<Instant>>,
     common: Common,
+    devices: HashSet<String>,
+    exclude: Option<Regex>,
+    include: Regex,
     proc_diskstats: Option<File>,
-    disk_regex: Option<Regex>,
     statistics: Vec<DiskStatistic>,
 }
 
 #[async_trait]
 impl Sampler for Disk {
     type Statistic = DiskStatistic;
+    const NAME: &'static str = "disk";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let fault_tolerant = common.config.general().fault_tolerant();
         let statistics = common.config().samplers().disk().statistics();
+        let include = Regex::new(common.config().samplers().disk().include())
+            .map_err(|e| format_err!("invalid disk include pattern: {}", e))?;
+        let exclude = match common.config().samplers().disk().exclude() {
+            Some(exclude) => Some(
+                Regex::new(exclude)
+                    .map_err(|e| format_err!("invalid disk exclude pattern: {}", e))?,
+            ),
+            None => None,
+        };
 
         #[allow(unused_mut)]
         let mut sampler = Self {
             bpf: None,
             bpf_last: Arc::new(Mutex::new(Instant::now())),
             common,
+            devices: HashSet::new(),
+            exclude,
+            include,
             proc_diskstats: None,
-            disk_regex: None,
             statistics,
         };
 
@@ -68,12 +80,9 @@ impl Sampler for Disk {
 
     fn spawn(common: Common) {
         if common.config().samplers().disk().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize disk sampler");
             } else {
@@ -156,9 +165,10 @@ impl Disk {
                             if let Ok(results) = bpf.get_kprobe_functions("blk_start_request") {
                                 if !results.is_empty() {
                                     if self.common.config.fault_tolerant() {
-                                        let _ = probe.try_attach_to_bpf(&mut bpf);
+                                        let _ =
+                                            self.common.attach_probe(Self::NAME, &probe, &mut bpf);
                                     } else {
-                                        probe.try_attach_to_bpf(&mut bpf)?;
+                                        self.common.attach_probe(Self::NAME, &probe, &mut bpf)?;
                                     }
                                 }
                             }
@@ -171,11 +181,13 @@ impl Disk {
                             {
                                 if !results.is_empty() {
                                     if self.common.config.fault_tolerant() {
-                                        if let Err(e) = probe.try_attach_to_bpf(&mut bpf) {
+                                        if let Err(e) =
+                                            self.common.attach_probe(Self::NAME, &probe, &mut bpf)
+                                        {
                                             warn!("skipping {} with error: {}", probe.name, e);
                                         }
                                     } else {
-                                        probe.try_attach_to_bpf(&mut bpf)?;
+                                        self.common.attach_probe(Self::NAME, &probe, &mut bpf)?;
                                     }
                                 }
                             }
@@ -188,9 +200,10 @@ impl Disk {
                             {
                                 if results.is_empty() {
                                     if self.common.config.fault_tolerant() {
-                                        let _ = probe.try_attach_to_bpf(&mut bpf);
+                                        let _ =
+                                            self.common.attach_probe(Self::NAME, &probe, &mut bpf);
                                     } else {
-                                        probe.try_attach_to_bpf(&mut bpf)?;
+                                        self.common.attach_probe(Self::NAME, &probe, &mut bpf)?;
                                     }
                                 }
                             }
@@ -198,9 +211,9 @@ impl Disk {
                         _ => {
                             // load + attach the kernel probes that are required to the bpf instance.
                             if self.common.config.fault_tolerant() {
-                                let _ = probe.try_attach_to_bpf(&mut bpf);
+                                let _ = self.common.attach_probe(Self::NAME, &probe, &mut bpf);
                             } else {
-                                probe.try_attach_to_bpf(&mut bpf)?;
+                                self.common.attach_probe(Self::NAME, &probe, &mut bpf)?;
                             }
                         }
                     }
@@ -219,51 +232,71 @@ impl Disk {
             self.proc_diskstats = Some(file);
         }
 
-        if self.disk_regex.is_none() {
-            let re = Regex::new(r"^((sd[a-z]+)|(hd[a-z]+)|(nvme\d+n\d+))$")
-                .expect("failed to compile regex");
-            self.disk_regex = Some(re);
-        }
-
         if let Some(file) = &mut self.proc_diskstats {
             file.seek(SeekFrom::Start(0)).await?;
-            if let Some(re) = &mut self.disk_regex {
-                let mut reader = BufReader::new(file);
-                let mut line = String::new();
-                let mut result = HashMap::<DiskStatistic, u64>::new();
-                while reader.read_line(&mut line).await? > 0 {
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    if re.is_match(parts.get(2).unwrap_or(&"unknown")) {
-                        for (id, part) in parts.iter().enumerate() {
-                            if let Some(statistic) = match id {
-                                3 => Some(DiskStatistic::OperationsRead),
-                                5 => Some(DiskStatistic::BandwidthRead),
-                                7 => Some(DiskStatistic::OperationsWrite),
-                                9 => Some(DiskStatistic::BandwidthWrite),
-                                14 => Some(DiskStatistic::OperationsDiscard),
-                                16 => Some(DiskStatistic::BandwidthDiscard),
-                                _ => None,
-                            } {
-                                result.entry(statistic).or_insert(0);
-                                let current = result.get_mut(&statistic).unwrap();
-                                *current += part.parse().unwrap_or(0);
-                            }
+            let mut reader = BufReader::new(file);
+            let mut line = String::new();
+            let mut result = HashMap::<DiskStatistic, u64>::new();
+            let mut per_device = Vec::new();
+            while reader.read_line(&mut line).await? > 0 {
+                if let Some((device, stats)) = parse_diskstats(&line) {
+                    let excluded = self
+                        .exclude
+                        .as_ref()
+                        .map(|re| re.is_match(device))
+                        .unwrap_or(false);
+                    if self.include.is_match(device) && !excluded {
+                        for (statistic, value) in &stats {
+                            *result.entry(*statistic).or_insert(0) += value;
                         }
+                        per_device.push((device.to_string(), stats));
                     }
-                    line.clear();
                 }
-                let time = Instant::now();
-                for stat in &self.statistics {
-                    if let Some(value) = result.get(stat) {
-                        let value = match stat {
-                            DiskStatistic::BandwidthWrite
-                            | DiskStatistic::BandwidthRead
-                            | DiskStatistic::BandwidthDiscard => value * 512,
-                            _ => *value,
-                        };
-                        let _ = self.metrics().record_counter(stat, time, value);
+                line.clear();
+            }
+
+            let time = Instant::now();
+            for stat in &self.statistics {
+                if let Some(value) = result.get(stat) {
+                    self.record(stat, time, *value);
+                }
+            }
+
+            if self.common.config().samplers().disk().per_device() {
+                for (device, stats) in &per_device {
+                    let labels = Labels::new().with("device", device);
+                    if !self.devices.contains(device) {
+                        for stat in self.statistics.iter().filter(|s| s.bpf_table().is_none()) {
+                            self.register_statistic(&Labeled::new(*stat, labels.clone()));
+                        }
+                        self.devices.insert(device.clone());
+                    }
+                    for stat in &self.statistics {
+                        if let Some(value) = stats.get(stat) {
+                            self.record(&Labeled::new(*stat, labels.clone()), time, *value);
+                        }
                     }
                 }
+
+                // stop tracking devices which have been removed
+                let present: HashSet<&str> = per_device
+                    .iter()
+                    .map(|(device, _)| device.as_str())
+                    .collect();
+                let removed: Vec<String> = self
+                    .devices
+                    .iter()
+                    .filter(|device| !present.contains(device.as_str()))
+                    .cloned()
+                    .collect();
+                for device in removed {
+                    let labels = Labels::new().with("device", &device);
+                    for stat in self.statistics.iter().filter(|s| s.bpf_table().is_none()) {
+                        self.metrics()
+                            .deregister(&Labeled::new(*stat, labels.clone()));
+                    }
+                    self.devices.remove(&device);
+                }
             }
         }
 
@@ -279,7 +312,7 @@ impl Disk {
             if let Some(ref bpf) = self.bpf {
                 let bpf = bpf.lock().unwrap();
                 for statistic in self.statistics.iter().filter(|s| s.bpf_table().is_some()) {
-                    if let Ok(mut table) = (*bpf).inner.table(statistic.bpf_table().unwrap()) {
+                    if let Ok(table) = (*bpf).inner.table(statistic.bpf_table().unwrap()) {
                         for (&value, &count) in &map_from_table(&mut table) {
                             if count > 0 {
                                 let _ = self.metrics().record_bucket(
@@ -298,3 +331,73 @@ impl Disk {
         Ok(())
     }
 }
+
+/// Parses a line of `/proc/diskstats` returning the device name and the values
+/// for each statistic, converted to bytes and nanoseconds where applicable
+fn parse_diskstats(line: &str) -> Option<(&str, HashMap<DiskStatistic, u64>)> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let device = parts.get(2)?;
+    let mut result = HashMap::new();
+    for (id, part) in parts.iter().enumerate() {
+        if let Some(statistic) = match id {
+            3 => Some(DiskStatistic::OperationsRead),
+            4 => Some(DiskStatistic::MergesRead),
+            5 => Some(DiskStatistic::BandwidthRead),
+            7 => Some(DiskStatistic::OperationsWrite),
+            8 => Some(DiskStatistic::MergesWrite),
+            9 => Some(DiskStatistic::BandwidthWrite),
+            11 => Some(DiskStatistic::InFlight),
+            12 => Some(DiskStatistic::IoTime),
+            13 => Some(DiskStatistic::IoWeightedTime),
+            14 => Some(DiskStatistic::OperationsDiscard),
+            15 => Some(DiskStatistic::MergesDiscard),
+            16 => Some(DiskStatistic::BandwidthDiscard),
+            _ => None,
+        } {
+            let value: u64 = part.parse().unwrap_or(0);
+            let value = match statistic {
+                DiskStatistic::BandwidthWrite
+                | DiskStatistic::BandwidthRead
+                | DiskStatistic::BandwidthDiscard => value * 512,
+                DiskStatistic::IoTime | DiskStatistic::IoWeightedTime => value * crate::MILLISECOND,
+                _ => value,
+            };
+            result.insert(statistic, value);
+        }
+    }
+    Some((device, result))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_diskstats() {
+        let line = " 259       0 nvme0n1 2398 1028 271914 411 7313 3829 281554 3452 2 3984 4279 0 0 0 0 450 414";
+        let (device, result) = parse_diskstats(line).expect("failed to parse");
+        assert_eq!(device, "nvme0n1");
+        assert_eq!(result.get(&DiskStatistic::OperationsRead), Some(&2398));
+        assert_eq!(result.get(&DiskStatistic::MergesWrite), Some(&3829));
+        assert_eq!(
+            result.get(&DiskStatistic::BandwidthRead),
+            Some(&(271914 * 512))
+        );
+        assert_eq!(result.get(&DiskStatistic::InFlight), Some(&2));
+        assert_eq!(
+            result.get(&DiskStatistic::IoTime),
+            Some(&(3984 * crate::MILLISECOND))
+        );
+    }
+
+    #[test]
+    fn test_default_include() {
+        let re = Regex::new(DiskConfig::default().include()).unwrap();
+        for device in &["sda", "hdb", "vda", "xvdf", "nvme0n1"] {
+            assert!(re.is_match(device));
+        }
+        for device in &["sda1", "nvme0n1p1", "dm-0", "md0", "loop0"] {
+            assert!(!re.is_match(device));
+        }
+    }
+}
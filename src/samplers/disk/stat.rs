@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[cfg(feature = "bpf")]
 use crate::common::bpf::*;
@@ -15,6 +16,7 @@ use crate::common::bpf::*;
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -24,33 +26,105 @@ use crate::common::bpf::*;
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum DiskStatistic {
-    #[strum(serialize = "disk/read/bytes")]
+    #[strum(
+        serialize = "disk/read/bytes",
+        message = "bytes read from disk devices"
+    )]
     BandwidthRead,
-    #[strum(serialize = "disk/write/bytes")]
+    #[strum(
+        serialize = "disk/write/bytes",
+        message = "bytes written to disk devices"
+    )]
     BandwidthWrite,
-    #[strum(serialize = "disk/discard/bytes")]
+    #[strum(
+        serialize = "disk/discard/bytes",
+        message = "bytes marked as unused on SSD devices"
+    )]
     BandwidthDiscard,
-    #[strum(serialize = "disk/read/operations")]
+    #[strum(
+        serialize = "disk/read/operations",
+        message = "total number of reads completed"
+    )]
     OperationsRead,
-    #[strum(serialize = "disk/write/operations")]
+    #[strum(
+        serialize = "disk/write/operations",
+        message = "total number of writes completed"
+    )]
     OperationsWrite,
-    #[strum(serialize = "disk/discard/operations")]
+    #[strum(
+        serialize = "disk/discard/operations",
+        message = "total number of discards completed"
+    )]
     OperationsDiscard,
-    #[strum(serialize = "disk/read/latency")]
+    #[strum(
+        serialize = "disk/read/merged",
+        message = "reads merged with an adjacent read"
+    )]
+    MergesRead,
+    #[strum(
+        serialize = "disk/write/merged",
+        message = "writes merged with an adjacent write"
+    )]
+    MergesWrite,
+    #[strum(
+        serialize = "disk/discard/merged",
+        message = "discards merged with an adjacent discard"
+    )]
+    MergesDiscard,
+    #[strum(
+        serialize = "disk/in_flight",
+        message = "number of IOs currently in flight"
+    )]
+    InFlight,
+    #[strum(
+        serialize = "disk/io/time",
+        message = "nanoseconds the device has spent with IOs in flight"
+    )]
+    IoTime,
+    #[strum(
+        serialize = "disk/io/weighted_time",
+        message = "nanoseconds spent doing IOs weighted by the number of IOs in flight"
+    )]
+    IoWeightedTime,
+    #[strum(
+        serialize = "disk/read/latency",
+        message = "end-to-end latency distribution for read operations"
+    )]
     LatencyRead,
-    #[strum(serialize = "disk/write/latency")]
+    #[strum(
+        serialize = "disk/write/latency",
+        message = "end-to-end latency distribution for write operations"
+    )]
     LatencyWrite,
-    #[strum(serialize = "disk/read/device_latency")]
+    #[strum(
+        serialize = "disk/read/device_latency",
+        message = "latency distribution waiting for disk to complete a read operation"
+    )]
     DeviceLatencyRead,
-    #[strum(serialize = "disk/write/device_latency")]
+    #[strum(
+        serialize = "disk/write/device_latency",
+        message = "latency distribution waiting for disk to complete a write operation"
+    )]
     DeviceLatencyWrite,
-    #[strum(serialize = "disk/read/queue_latency")]
+    #[strum(
+        serialize = "disk/read/queue_latency",
+        message = "latency distribution where read was waiting on the device queue"
+    )]
     QueueLatencyRead,
-    #[strum(serialize = "disk/write/queue_latency")]
+    #[strum(
+        serialize = "disk/write/queue_latency",
+        message = "latency distribution where write was waiting on the device queue"
+    )]
     QueueLatencyWrite,
-    #[strum(serialize = "disk/read/io_size")]
+    #[strum(
+        serialize = "disk/read/io_size",
+        message = "size distribution for read operations"
+    )]
     IoSizeRead,
-    #[strum(serialize = "disk/write/io_size")]
+    #[strum(
+        serialize = "disk/write/io_size",
+        message = "size distribution for write operations"
+    )]
     IoSizeWrite,
 }
 
@@ -142,8 +216,33 @@ impl Statistic for DiskStatistic {
     fn source(&self) -> Source {
         if self.bpf_table().is_some() {
             Source::Distribution
+        } else if *self == Self::InFlight {
+            Source::Gauge
         } else {
             Source::Counter
         }
     }
+
+    fn unit(&self) -> Option<&str> {
+        match self {
+            Self::BandwidthRead
+            | Self::BandwidthWrite
+            | Self::BandwidthDiscard
+            | Self::IoSizeRead
+            | Self::IoSizeWrite => Some("bytes"),
+            Self::IoTime
+            | Self::IoWeightedTime
+            | Self::LatencyRead
+            | Self::LatencyWrite
+            | Self::DeviceLatencyRead
+            | Self::DeviceLatencyWrite
+            | Self::QueueLatencyRead
+            | Self::QueueLatencyWrite => Some("nanoseconds"),
+            _ => None,
+        }
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
@@ -30,6 +30,7 @@ pub struct Ext4 {
 #[async_trait]
 impl Sampler for Ext4 {
     type Statistic = Ext4Statistic;
+    const NAME: &'static str = "ext4";
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let fault_tolerant = common.config.general().fault_tolerant();
         let statistics = common.config().samplers().ext4().statistics();
@@ -58,12 +59,9 @@ impl Sampler for Ext4 {
 
     fn spawn(common: Common) {
         if common.config().samplers().ext4().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize ext4 sampler");
             } else {
@@ -144,11 +142,11 @@ impl Ext4 {
                 // load + attach the kernel probes that are required to the bpf instance.
                 for probe in probes {
                     if self.common.config.fault_tolerant() {
-                        if let Err(e) = probe.try_attach_to_bpf(&mut bpf) {
+                        if let Err(e) = self.common.attach_probe(Self::NAME, &probe, &mut bpf) {
                             warn!("skipping {} with error: {}", probe.name, e);
                         }
                     } else {
-                        probe.try_attach_to_bpf(&mut bpf)?;
+                        self.common.attach_probe(Self::NAME, &probe, &mut bpf)?;
                     }
                 }
 
@@ -168,7 +166,7 @@ impl Ext4 {
                 let bpf = bpf.lock().unwrap();
                 let time = Instant::now();
                 for statistic in self.statistics.iter().filter(|s| s.bpf_table().is_some()) {
-                    if let Ok(mut table) = (*bpf).inner.table(statistic.bpf_table().unwrap()) {
+                    if let Ok(table) = (*bpf).inner.table(statistic.bpf_table().unwrap()) {
                         for (&value, &count) in &map_from_table(&mut table) {
                             if count > 0 {
                                 let _ = self.metrics().record_bucket(
@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[cfg(feature = "bpf")]
 use crate::common::bpf::*;
@@ -15,6 +16,7 @@ use crate::common::bpf::*;
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -25,13 +27,25 @@ use crate::common::bpf::*;
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 #[allow(clippy::enum_variant_names)]
 pub enum Ext4Statistic {
-    #[strum(serialize = "ext4/read/latency")]
+    #[strum(
+        serialize = "ext4/read/latency",
+        message = "latency distribution for read() on ext4 filesystems"
+    )]
     ReadLatency,
-    #[strum(serialize = "ext4/write/latency")]
+    #[strum(
+        serialize = "ext4/write/latency",
+        message = "latency distribution for write() on ext4 filesystems"
+    )]
     WriteLatency,
-    #[strum(serialize = "ext4/open/latency")]
+    #[strum(
+        serialize = "ext4/open/latency",
+        message = "latency distribution for open() on ext4 filesystems"
+    )]
     OpenLatency,
-    #[strum(serialize = "ext4/fsync/latency")]
+    #[strum(
+        serialize = "ext4/fsync/latency",
+        message = "latency distribution for fsync() on ext4 filesystems"
+    )]
     FsyncLatency,
 }
 
@@ -132,4 +146,12 @@ impl Statistic for Ext4Statistic {
     fn source(&self) -> Source {
         Source::Distribution
     }
+
+    fn unit(&self) -> Option<&str> {
+        Some("nanoseconds")
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
@@ -4,7 +4,7 @@
 
 use serde_derive::Deserialize;
 
-use crate::config::SamplerConfig;
+use crate::config::{SamplerConfig, SummaryOutput};
 
 use super::stat::*;
 
@@ -25,6 +25,8 @@ pub struct HttpConfig {
     passthrough: bool,
     #[serde(default = "crate::common::default_percentiles")]
     percentiles: Vec<f64>,
+    #[serde(default)]
+    summaries: Vec<SummaryOutput>,
     url: Option<String>,
     // http request timeout in milliseconds
     #[serde(default = "default_timeout")]
@@ -40,6 +42,7 @@ impl Default for HttpConfig {
             interval: Default::default(),
             passthrough: Default::default(),
             percentiles: crate::common::default_percentiles(),
+            summaries: Default::default(),
             url: None,
             timeout: default_timeout(),
         }
@@ -91,6 +94,10 @@ impl SamplerConfig for HttpConfig {
         &self.percentiles
     }
 
+    fn summaries(&self) -> &[SummaryOutput] {
+        &self.summaries
+    }
+
     fn statistics(&self) -> Vec<<Self as SamplerConfig>::Statistic> {
         // we don't know the statistics yet, register at runtime instead
         Vec::new()
@@ -27,6 +27,7 @@ pub struct Http {
 #[async_trait]
 impl Sampler for Http {
     type Statistic = HttpStatistic;
+    const NAME: &'static str = "http";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let url = common.config.samplers().http().url();
@@ -50,12 +51,9 @@ impl Sampler for Http {
 
     fn spawn(common: Common) {
         if common.config().samplers().http().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize http sampler");
             } else {
@@ -106,42 +104,54 @@ impl Sampler for Http {
                     for gauge in self.common.config().samplers().http().gauges() {
                         statistics.insert(
                             gauge.to_string(),
-                            HttpStatistic::new(gauge.to_string(), Source::Counter),
+                            HttpStatistic::new(gauge.to_string(), Source::Gauge),
                         );
                     }
                     for (key, value) in json.entries() {
-                        if let Some(value) = value.as_u64() {
-                            if let Some(statistic) = statistics.get(key) {
-                                self.common().metrics().register(statistic);
+                        if !value.is_number() {
+                            continue;
+                        }
+                        if let Some(statistic) = statistics.get(key) {
+                            self.common().metrics().register(statistic);
+                            self.common()
+                                .metrics()
+                                .add_summary(statistic, Summary::stream(self.samples()));
+                            if self.passthrough {
                                 self.common()
                                     .metrics()
-                                    .add_summary(statistic, Summary::stream(self.samples()));
-                                if self.passthrough {
-                                    self.common()
-                                        .metrics()
-                                        .add_output(statistic, Output::Reading);
-                                }
-                                for percentile in self.sampler_config().percentiles() {
-                                    self.common()
-                                        .metrics()
-                                        .add_output(statistic, Output::Percentile(*percentile));
-                                }
-                                match statistic.source() {
-                                    Source::Counter => {
+                                    .add_output(statistic, Output::Reading);
+                            }
+                            for percentile in self.sampler_config().percentiles() {
+                                self.common()
+                                    .metrics()
+                                    .add_output(statistic, Output::Percentile(*percentile));
+                            }
+                            for summary in self.sampler_config().summaries() {
+                                self.common()
+                                    .metrics()
+                                    .add_output(statistic, Output::from(*summary));
+                            }
+                            match statistic.source() {
+                                Source::Counter => {
+                                    if let Some(value) = value.as_u64() {
                                         let _ = self
                                             .common()
                                             .metrics()
                                             .record_counter(statistic, time, value);
                                     }
-                                    Source::Gauge => {
+                                }
+                                Source::Gauge => {
+                                    if let Some(value) = value.as_f64() {
                                         let _ = self
                                             .common()
                                             .metrics()
-                                            .record_gauge(statistic, time, value);
+                                            .record_gauge_float(statistic, time, value);
                                     }
-                                    _ => unimplemented!(),
                                 }
-                            } else if self.passthrough {
+                                _ => unimplemented!(),
+                            }
+                        } else if self.passthrough {
+                            if let Some(value) = value.as_f64() {
                                 let statistic = HttpStatistic::new(key.to_string(), Source::Gauge);
                                 self.common().metrics().register(&statistic);
                                 self.common()
@@ -150,7 +160,7 @@ impl Sampler for Http {
                                 let _ = self
                                     .common()
                                     .metrics()
-                                    .record_gauge(&statistic, time, value);
+                                    .record_gauge_float(&statistic, time, value);
                             }
                         }
                     }
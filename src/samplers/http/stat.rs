@@ -25,4 +25,14 @@ impl Statistic for HttpStatistic {
     fn source(&self) -> Source {
         self.source
     }
+
+    // JSON numbers are not typed, so gauges are tracked as floating point to
+    // accept fractional and negative values
+    fn kind(&self) -> Kind {
+        if self.source == Source::Gauge {
+            Kind::Float
+        } else {
+            Kind::Unsigned
+        }
+    }
 }
@@ -5,7 +5,7 @@
 use serde_derive::Deserialize;
 use strum::IntoEnumIterator;
 
-use crate::config::SamplerConfig;
+use crate::config::{SamplerConfig, SummaryOutput};
 
 use super::stat::*;
 
@@ -20,6 +20,8 @@ pub struct InterruptConfig {
     interval: Option<usize>,
     #[serde(default = "crate::common::default_percentiles")]
     percentiles: Vec<f64>,
+    #[serde(default)]
+    summaries: Vec<SummaryOutput>,
     #[serde(default = "default_statistics")]
     statistics: Vec<InterruptStatistic>,
 }
@@ -31,6 +33,7 @@ impl Default for InterruptConfig {
             enabled: Default::default(),
             interval: Default::default(),
             percentiles: crate::common::default_percentiles(),
+            summaries: Default::default(),
             statistics: default_statistics(),
         }
     }
@@ -59,6 +62,10 @@ impl SamplerConfig for InterruptConfig {
         &self.percentiles
     }
 
+    fn summaries(&self) -> &[SummaryOutput] {
+        &self.summaries
+    }
+
     fn statistics(&self) -> Vec<<Self as SamplerConfig>::Statistic> {
         let mut enabled = Vec::new();
         for statistic in self.statistics.iter() {
@@ -34,6 +34,7 @@ pub struct Interrupt {
 #[async_trait]
 impl Sampler for Interrupt {
     type Statistic = InterruptStatistic;
+    const NAME: &'static str = "interrupt";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let fault_tolerant = common.config.general().fault_tolerant();
@@ -64,12 +65,9 @@ impl Sampler for Interrupt {
 
     fn spawn(common: Common) {
         if common.config().samplers().interrupt().enabled() {
-            if let Ok(mut interrupt) = Interrupt::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = interrupt.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(interrupt) = Interrupt::new(common.clone()) {
+                common.runtime().spawn(interrupt.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize interrupt sampler");
             } else {
@@ -147,11 +145,11 @@ impl Interrupt {
                 // load + attach the kernel probes that are required to the bpf instance.
                 for probe in probes {
                     if self.common.config.fault_tolerant() {
-                        if let Err(e) = probe.try_attach_to_bpf(&mut bpf) {
+                        if let Err(e) = self.common.attach_probe(Self::NAME, &probe, &mut bpf) {
                             warn!("skipping {} with error: {}", probe.name, e);
                         }
                     } else {
-                        probe.try_attach_to_bpf(&mut bpf)?;
+                        self.common.attach_probe(Self::NAME, &probe, &mut bpf)?;
                     }
                 }
 
@@ -316,7 +314,7 @@ impl Interrupt {
                 let bpf = bpf.lock().unwrap();
                 let time = Instant::now();
                 for statistic in self.statistics.iter().filter(|s| s.bpf_table().is_some()) {
-                    if let Ok(mut table) = (*bpf).inner.table(statistic.bpf_table().unwrap()) {
+                    if let Ok(table) = (*bpf).inner.table(statistic.bpf_table().unwrap()) {
                         for (&value, &count) in &map_from_table(&mut table) {
                             if count > 0 {
                                 let _ = self.metrics().record_bucket(
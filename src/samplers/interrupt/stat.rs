@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[cfg(feature = "bpf")]
 use crate::common::bpf::*;
@@ -15,6 +16,7 @@ use crate::common::bpf::*;
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -24,69 +26,159 @@ use crate::common::bpf::*;
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum InterruptStatistic {
-    #[strum(serialize = "interrupt/total")]
+    #[strum(serialize = "interrupt/total", message = "total interrupts")]
     Total,
-    #[strum(serialize = "interrupt/timer")]
+    #[strum(
+        serialize = "interrupt/timer",
+        message = "interrupts related to the system timer (PIT/HPET)"
+    )]
     Timer,
-    #[strum(serialize = "interrupt/nmi")]
+    #[strum(serialize = "interrupt/nmi", message = "non-maskable interrupts")]
     NonMaskable,
-    #[strum(serialize = "interrupt/nvme")]
+    #[strum(
+        serialize = "interrupt/nvme",
+        message = "interrupts for servicing NVMe queues"
+    )]
     Nvme,
-    #[strum(serialize = "interrupt/network")]
+    #[strum(
+        serialize = "interrupt/network",
+        message = "interrupts for servicing network devices (NIC queues)"
+    )]
     Network,
-    #[strum(serialize = "interrupt/local_timer")]
+    #[strum(
+        serialize = "interrupt/local_timer",
+        message = "APIC interrupts which fire on a specific CPU as a result of a local timer"
+    )]
     LocalTimer,
-    #[strum(serialize = "interrupt/spurious")]
+    #[strum(
+        serialize = "interrupt/spurious",
+        message = "interrupts which were marked spurious and not handled"
+    )]
     Spurious,
-    #[strum(serialize = "interrupt/performance_monitoring")]
+    #[strum(
+        serialize = "interrupt/performance_monitoring",
+        message = "interrupts generated when a performance counter overflows or PEBS interrupt threshold is reached"
+    )]
     PerformanceMonitoring,
-    #[strum(serialize = "interrupt/rescheduling")]
+    #[strum(
+        serialize = "interrupt/rescheduling",
+        message = "interrupts used to notify a core to schedule a thread"
+    )]
     Rescheduling,
-    #[strum(serialize = "interrupt/function_call")]
+    #[strum(
+        serialize = "interrupt/function_call",
+        message = "interrupts used to run a function on another CPU"
+    )]
     FunctionCall,
-    #[strum(serialize = "interrupt/tlb_shootdowns")]
+    #[strum(
+        serialize = "interrupt/tlb_shootdowns",
+        message = "interrupts caused to trigger TLB shootdowns"
+    )]
     TlbShootdowns,
-    #[strum(serialize = "interrupt/thermal_event")]
+    #[strum(
+        serialize = "interrupt/thermal_event",
+        message = "interrupts caused by thermal events, like throttling"
+    )]
     ThermalEvent,
-    #[strum(serialize = "interrupt/machine_check_exception")]
+    #[strum(
+        serialize = "interrupt/machine_check_exception",
+        message = "interrupts caused by machine check exceptions"
+    )]
     MachineCheckException,
-    #[strum(serialize = "interrupt/rtc")]
+    #[strum(
+        serialize = "interrupt/rtc",
+        message = "interrupts caused by the realtime clock"
+    )]
     RealTimeClock,
-    #[strum(serialize = "interrupt/node0/total")]
+    #[strum(
+        serialize = "interrupt/node0/total",
+        message = "total interrupts which were handled on NUMA node 0"
+    )]
     Node0Total,
-    #[strum(serialize = "interrupt/node1/total")]
+    #[strum(
+        serialize = "interrupt/node1/total",
+        message = "total interrupts which were handled on NUMA node 1"
+    )]
     Node1Total,
-    #[strum(serialize = "interrupt/node0/network")]
+    #[strum(
+        serialize = "interrupt/node0/network",
+        message = "interrupts for servicing network devices which were handled on NUMA node 0"
+    )]
     Node0Network,
-    #[strum(serialize = "interrupt/node1/network")]
+    #[strum(
+        serialize = "interrupt/node1/network",
+        message = "interrupts for servicing network devices which were handled on NUMA node 1"
+    )]
     Node1Network,
-    #[strum(serialize = "interrupt/node0/nvme")]
+    #[strum(
+        serialize = "interrupt/node0/nvme",
+        message = "interrupts for servicing NVMe devices which were handled on NUMA node 0"
+    )]
     Node0Nvme,
-    #[strum(serialize = "interrupt/node1/nvme")]
+    #[strum(
+        serialize = "interrupt/node1/nvme",
+        message = "interrupts for servicing NVMe devices which were handled on NUMA node 1"
+    )]
     Node1Nvme,
-    #[strum(serialize = "interrupt/softirq/hi")]
+    #[strum(
+        serialize = "interrupt/softirq/hi",
+        message = "latency distribution for high priority tasklet softirqs"
+    )]
     SoftIrqHI,
-    #[strum(serialize = "interrupt/softirq/timer")]
+    #[strum(
+        serialize = "interrupt/softirq/timer",
+        message = "latency distribution for timer softirqs"
+    )]
     SoftIrqTimer,
-    #[strum(serialize = "interrupt/softirq/net_rx")]
+    #[strum(
+        serialize = "interrupt/softirq/net_rx",
+        message = "latency distribution for network receive softirqs"
+    )]
     SoftIrqNetRx,
-    #[strum(serialize = "interrupt/softirq/net_tx")]
+    #[strum(
+        serialize = "interrupt/softirq/net_tx",
+        message = "latency distribution for network transmit softirqs"
+    )]
     SoftIrqNetTx,
-    #[strum(serialize = "interrupt/softirq/block")]
+    #[strum(
+        serialize = "interrupt/softirq/block",
+        message = "latency distribution for block device softirqs"
+    )]
     SoftIrqBlock,
-    #[strum(serialize = "interrupt/softirq/irq_poll")]
+    #[strum(
+        serialize = "interrupt/softirq/irq_poll",
+        message = "latency distribution for IRQ poll softirqs"
+    )]
     SoftIrqPoll,
-    #[strum(serialize = "interrupt/softirq/tasklet")]
+    #[strum(
+        serialize = "interrupt/softirq/tasklet",
+        message = "latency distribution for tasklet softirqs"
+    )]
     SoftIrqTasklet,
-    #[strum(serialize = "interrupt/softirq/sched")]
+    #[strum(
+        serialize = "interrupt/softirq/sched",
+        message = "latency distribution for scheduler softirqs"
+    )]
     SoftIrqSched,
-    #[strum(serialize = "interrupt/softirq/hr_timer")]
+    #[strum(
+        serialize = "interrupt/softirq/hr_timer",
+        message = "latency distribution for high resolution timer softirqs"
+    )]
     SoftIrqHRTimer,
-    #[strum(serialize = "interrupt/softirq/rcu")]
+    #[strum(
+        serialize = "interrupt/softirq/rcu",
+        message = "latency distribution for RCU softirqs"
+    )]
     SoftIrqRCU,
-    #[strum(serialize = "interrupt/softirq/unknown")]
+    #[strum(
+        serialize = "interrupt/softirq/unknown",
+        message = "latency distribution for unknown softirqs"
+    )]
     SoftIrqUnknown,
-    #[strum(serialize = "interrupt/hardirq")]
+    #[strum(
+        serialize = "interrupt/hardirq",
+        message = "latency distribution for hardirq handlers"
+    )]
     HardIrq,
 }
 
@@ -176,4 +268,16 @@ impl Statistic for InterruptStatistic {
             Source::Counter
         }
     }
+
+    fn unit(&self) -> Option<&str> {
+        if self.bpf_table().is_some() {
+            Some("nanoseconds")
+        } else {
+            None
+        }
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
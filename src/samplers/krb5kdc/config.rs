@@ -5,7 +5,7 @@
 use serde_derive::Deserialize;
 use strum::IntoEnumIterator;
 
-use crate::config::SamplerConfig;
+use crate::config::{SamplerConfig, SummaryOutput};
 
 use super::stat::*;
 
@@ -20,6 +20,8 @@ pub struct Krb5kdcConfig {
     interval: Option<usize>,
     #[serde(default)]
     percentiles: Vec<f64>,
+    #[serde(default)]
+    summaries: Vec<SummaryOutput>,
     #[serde(default = "default_statistics")]
     statistics: Vec<Krb5kdcStatistic>,
     #[serde(default)]
@@ -33,6 +35,7 @@ impl Default for Krb5kdcConfig {
             enabled: Default::default(),
             interval: Default::default(),
             percentiles: crate::common::default_percentiles(),
+            summaries: Default::default(),
             statistics: default_statistics(),
             path: Default::default(),
         }
@@ -68,6 +71,10 @@ impl SamplerConfig for Krb5kdcConfig {
         &self.percentiles
     }
 
+    fn summaries(&self) -> &[SummaryOutput] {
+        &self.summaries
+    }
+
     fn statistics(&self) -> Vec<<Self as SamplerConfig>::Statistic> {
         let mut enabled = Vec::new();
         for statistic in self.statistics.iter() {
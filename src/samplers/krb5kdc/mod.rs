@@ -50,7 +50,7 @@ impl Krb5kdc {
 
             // load + attach the kernel probes that are required to the bpf instance.
             for probe in probes {
-                if let Err(err) = probe.try_attach_to_bpf(&mut bpf) {
+                if let Err(err) = self.common.attach_probe(Self::NAME, &probe, &mut bpf) {
                     if self.common.config().fault_tolerant() {
                         warn!("krb5kdc unable to attach probe to function {}", &probe.name);
                     } else {
@@ -68,6 +68,7 @@ impl Krb5kdc {
 #[async_trait]
 impl Sampler for Krb5kdc {
     type Statistic = Krb5kdcStatistic;
+    const NAME: &'static str = "krb5kdc";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let fault_tolerant = common.config.general().fault_tolerant();
@@ -97,13 +98,10 @@ impl Sampler for Krb5kdc {
 
     fn spawn(common: Common) {
         if common.config().samplers().krb5kdc().enabled() {
+            common.status().register(Self::NAME);
             match Self::new(common.clone()) {
-                Ok(mut sampler) => {
-                    common.runtime().spawn(async move {
-                        loop {
-                            let _ = sampler.sample().await;
-                        }
-                    });
+                Ok(sampler) => {
+                    common.runtime().spawn(sampler.run());
                 }
                 Err(e) => {
                     if !common.config.fault_tolerant() {
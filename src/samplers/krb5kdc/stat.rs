@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[cfg(feature = "bpf")]
 use crate::common::bpf::*;
@@ -15,6 +16,7 @@ use crate::common::bpf::*;
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -24,283 +26,562 @@ use crate::common::bpf::*;
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum Krb5kdcStatistic {
-    #[strum(serialize = "krb5kdc/finish_process_as_req/unknown")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/unknown",
+        message = "count of finish_process_as_req calls resulting in an unlisted error code"
+    )]
     FinishProcessAsReqUnknown,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/none")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/none",
+        message = "count of finish_process_as_req calls resulting in no error"
+    )]
     FinishProcessAsReqNone,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/name_exp")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/name_exp",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_NAME_EXP"
+    )]
     FinishProcessAsReqNameExp,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/service_exp")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/service_exp",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_SERVICE_EXP"
+    )]
     FinishProcessAsReqServiceExp,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/bad_pvno")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/bad_pvno",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_BAD_PVNO"
+    )]
     FinishProcessAsReqBadPvno,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/c_old_mast_kvno")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/c_old_mast_kvno",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_C_OLD_MAST_KVNO"
+    )]
     FinishProcessAsReqCOldMastKvno,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/s_old_mast_kvno")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/s_old_mast_kvno",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_S_OLD_MAST_KVNO"
+    )]
     FinishProcessAsReqSOldMastKvno,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/c_principal_unknown")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/c_principal_unknown",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_C_PRINCIPAL_UNKNOWN"
+    )]
     FinishProcessAsReqCPrincipalUnknown,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/s_principal_unknown")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/s_principal_unknown",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_S_PRINCIPAL_UNKNOWN"
+    )]
     FinishProcessAsReqSPrincipalUnknown,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/principal_not_unique")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/principal_not_unique",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_PRINCIPAL_NOT_UNIQUE"
+    )]
     FinishProcessAsReqPrincipalNotUnique,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/null_key")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/null_key",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_NULL_KEY"
+    )]
     FinishProcessAsReqNullKey,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/cannot_postdate")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/cannot_postdate",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_CANNOT_POSTDATE"
+    )]
     FinishProcessAsReqCannotPostdate,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/never_valid")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/never_valid",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_NEVER_VALID"
+    )]
     FinishProcessAsReqNeverValid,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/policy")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/policy",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_POLICY"
+    )]
     FinishProcessAsReqPolicy,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/badoption")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/badoption",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_BADOPTION"
+    )]
     FinishProcessAsReqBadoption,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/etype_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/etype_nosupp",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_ETYPE_NOSUPP"
+    )]
     FinishProcessAsReqEtypeNosupp,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/sumtype_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/sumtype_nosupp",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_SUMTYPE_NOSUPP"
+    )]
     FinishProcessAsReqSumtypeNosupp,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/padata_type_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/padata_type_nosupp",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_PADATA_TYPE_NOSUPP"
+    )]
     FinishProcessAsReqPadataTypeNosupp,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/trtype_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/trtype_nosupp",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_TRTYPE_NOSUPP"
+    )]
     FinishProcessAsReqTrtypeNosupp,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/client_revoked")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/client_revoked",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_CLIENT_REVOKED"
+    )]
     FinishProcessAsReqClientRevoked,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/service_revoked")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/service_revoked",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_SERVICE_REVOKED"
+    )]
     FinishProcessAsReqServiceRevoked,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/tgt_revoked")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/tgt_revoked",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_TGT_REVOKED"
+    )]
     FinishProcessAsReqTgtRevoked,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/client_notyet")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/client_notyet",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_CLIENT_NOTYET"
+    )]
     FinishProcessAsReqClientNotyet,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/service_notyet")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/service_notyet",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_SERVICE_NOTYET"
+    )]
     FinishProcessAsReqServiceNotyet,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/key_exp")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/key_exp",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_KEY_EXP"
+    )]
     FinishProcessAsReqKeyExp,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/preauth_failed")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/preauth_failed",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_PREAUTH_FAILED"
+    )]
     FinishProcessAsReqPreauthFailed,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/preauth_required")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/preauth_required",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_PREAUTH_REQUIRED"
+    )]
     FinishProcessAsReqPreauthRequired,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/server_nomatch")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/server_nomatch",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_SERVER_NOMATCH"
+    )]
     FinishProcessAsReqServerNomatch,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/must_use_user2user")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/must_use_user2user",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_MUST_USE_USER2USER"
+    )]
     FinishProcessAsReqMustUseUser2user,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/path_not_accepted")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/path_not_accepted",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_PATH_NOT_ACCEPTED"
+    )]
     FinishProcessAsReqPathNotAccepted,
 
-    #[strum(serialize = "krb5kdc/finish_process_as_req/svc_unavailable")]
+    #[strum(
+        serialize = "krb5kdc/finish_process_as_req/svc_unavailable",
+        message = "count of finish_process_as_req calls resulting in KRB5KDC_ERR_SVC_UNAVAILABLE"
+    )]
     FinishProcessAsReqSvcUnavailable,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/unknown")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/unknown",
+        message = "count of finish_dispatch_cache calls resulting in an unlisted error code"
+    )]
     FinishDispatchCacheUnknown,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/none")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/none",
+        message = "count of finish_dispatch_cache calls resulting in no error"
+    )]
     FinishDispatchCacheNone,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/name_exp")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/name_exp",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_NAME_EXP"
+    )]
     FinishDispatchCacheNameExp,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/service_exp")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/service_exp",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_SERVICE_EXP"
+    )]
     FinishDispatchCacheServiceExp,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/bad_pvno")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/bad_pvno",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_BAD_PVNO"
+    )]
     FinishDispatchCacheBadPvno,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/c_old_mast_kvno")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/c_old_mast_kvno",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_C_OLD_MAST_KVNO"
+    )]
     FinishDispatchCacheCOldMastKvno,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/s_old_mast_kvno")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/s_old_mast_kvno",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_S_OLD_MAST_KVNO"
+    )]
     FinishDispatchCacheSOldMastKvno,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/c_principal_unknown")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/c_principal_unknown",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_C_PRINCIPAL_UNKNOWN"
+    )]
     FinishDispatchCacheCPrincipalUnknown,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/s_principal_unknown")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/s_principal_unknown",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_S_PRINCIPAL_UNKNOWN"
+    )]
     FinishDispatchCacheSPrincipalUnknown,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/principal_not_unique")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/principal_not_unique",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_PRINCIPAL_NOT_UNIQUE"
+    )]
     FinishDispatchCachePrincipalNotUnique,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/null_key")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/null_key",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_NULL_KEY"
+    )]
     FinishDispatchCacheNullKey,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/cannot_postdate")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/cannot_postdate",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_CANNOT_POSTDATE"
+    )]
     FinishDispatchCacheCannotPostdate,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/never_valid")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/never_valid",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_NEVER_VALID"
+    )]
     FinishDispatchCacheNeverValid,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/policy")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/policy",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_POLICY"
+    )]
     FinishDispatchCachePolicy,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/badoption")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/badoption",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_BADOPTION"
+    )]
     FinishDispatchCacheBadoption,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/etype_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/etype_nosupp",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_ETYPE_NOSUPP"
+    )]
     FinishDispatchCacheEtypeNosupp,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/sumtype_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/sumtype_nosupp",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_SUMTYPE_NOSUPP"
+    )]
     FinishDispatchCacheSumtypeNosupp,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/padata_type_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/padata_type_nosupp",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_PADATA_TYPE_NOSUPP"
+    )]
     FinishDispatchCachePadataTypeNosupp,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/trtype_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/trtype_nosupp",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_TRTYPE_NOSUPP"
+    )]
     FinishDispatchCacheTrtypeNosupp,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/client_revoked")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/client_revoked",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_CLIENT_REVOKED"
+    )]
     FinishDispatchCacheClientRevoked,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/service_revoked")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/service_revoked",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_SERVICE_REVOKED"
+    )]
     FinishDispatchCacheServiceRevoked,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/tgt_revoked")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/tgt_revoked",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_TGT_REVOKED"
+    )]
     FinishDispatchCacheTgtRevoked,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/client_notyet")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/client_notyet",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_CLIENT_NOTYET"
+    )]
     FinishDispatchCacheClientNotyet,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/service_notyet")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/service_notyet",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_SERVICE_NOTYET"
+    )]
     FinishDispatchCacheServiceNotyet,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/key_exp")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/key_exp",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_KEY_EXP"
+    )]
     FinishDispatchCacheKeyExp,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/preauth_failed")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/preauth_failed",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_PREAUTH_FAILED"
+    )]
     FinishDispatchCachePreauthFailed,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/preauth_required")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/preauth_required",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_PREAUTH_REQUIRED"
+    )]
     FinishDispatchCachePreauthRequired,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/server_nomatch")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/server_nomatch",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_SERVER_NOMATCH"
+    )]
     FinishDispatchCacheServerNomatch,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/must_use_user2user")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/must_use_user2user",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_MUST_USE_USER2USER"
+    )]
     FinishDispatchCacheMustUseUser2user,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/path_not_accepted")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/path_not_accepted",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_PATH_NOT_ACCEPTED"
+    )]
     FinishDispatchCachePathNotAccepted,
 
-    #[strum(serialize = "krb5kdc/finish_dispatch_cache/svc_unavailable")]
+    #[strum(
+        serialize = "krb5kdc/finish_dispatch_cache/svc_unavailable",
+        message = "count of finish_dispatch_cache calls resulting in KRB5KDC_ERR_SVC_UNAVAILABLE"
+    )]
     FinishDispatchCacheSvcUnavailable,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/unknown")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/unknown",
+        message = "count of process_tgs_req calls resulting in an unlisted error code"
+    )]
     ProcessTgsReqUnknown,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/none")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/none",
+        message = "count of process_tgs_req calls resulting in no error"
+    )]
     ProcessTgsReqNone,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/name_exp")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/name_exp",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_NAME_EXP"
+    )]
     ProcessTgsReqNameExp,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/service_exp")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/service_exp",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_SERVICE_EXP"
+    )]
     ProcessTgsReqServiceExp,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/bad_pvno")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/bad_pvno",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_BAD_PVNO"
+    )]
     ProcessTgsReqBadPvno,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/c_old_mast_kvno")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/c_old_mast_kvno",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_C_OLD_MAST_KVNO"
+    )]
     ProcessTgsReqCOldMastKvno,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/s_old_mast_kvno")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/s_old_mast_kvno",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_S_OLD_MAST_KVNO"
+    )]
     ProcessTgsReqSOldMastKvno,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/c_principal_unknown")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/c_principal_unknown",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_C_PRINCIPAL_UNKNOWN"
+    )]
     ProcessTgsReqCPrincipalUnknown,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/s_principal_unknown")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/s_principal_unknown",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_S_PRINCIPAL_UNKNOWN"
+    )]
     ProcessTgsReqSPrincipalUnknown,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/principal_not_unique")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/principal_not_unique",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_PRINCIPAL_NOT_UNIQUE"
+    )]
     ProcessTgsReqPrincipalNotUnique,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/null_key")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/null_key",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_NULL_KEY"
+    )]
     ProcessTgsReqNullKey,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/cannot_postdate")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/cannot_postdate",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_CANNOT_POSTDATE"
+    )]
     ProcessTgsReqCannotPostdate,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/never_valid")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/never_valid",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_NEVER_VALID"
+    )]
     ProcessTgsReqNeverValid,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/policy")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/policy",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_POLICY"
+    )]
     ProcessTgsReqPolicy,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/badoption")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/badoption",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_BADOPTION"
+    )]
     ProcessTgsReqBadoption,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/etype_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/etype_nosupp",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_ETYPE_NOSUPP"
+    )]
     ProcessTgsReqEtypeNosupp,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/sumtype_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/sumtype_nosupp",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_SUMTYPE_NOSUPP"
+    )]
     ProcessTgsReqSumtypeNosupp,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/padata_type_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/padata_type_nosupp",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_PADATA_TYPE_NOSUPP"
+    )]
     ProcessTgsReqPadataTypeNosupp,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/trtype_nosupp")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/trtype_nosupp",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_TRTYPE_NOSUPP"
+    )]
     ProcessTgsReqTrtypeNosupp,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/client_revoked")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/client_revoked",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_CLIENT_REVOKED"
+    )]
     ProcessTgsReqClientRevoked,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/service_revoked")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/service_revoked",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_SERVICE_REVOKED"
+    )]
     ProcessTgsReqServiceRevoked,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/tgt_revoked")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/tgt_revoked",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_TGT_REVOKED"
+    )]
     ProcessTgsReqTgtRevoked,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/client_notyet")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/client_notyet",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_CLIENT_NOTYET"
+    )]
     ProcessTgsReqClientNotyet,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/service_notyet")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/service_notyet",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_SERVICE_NOTYET"
+    )]
     ProcessTgsReqServiceNotyet,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/key_exp")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/key_exp",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_KEY_EXP"
+    )]
     ProcessTgsReqKeyExp,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/preauth_failed")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/preauth_failed",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_PREAUTH_FAILED"
+    )]
     ProcessTgsReqPreauthFailed,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/preauth_required")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/preauth_required",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_PREAUTH_REQUIRED"
+    )]
     ProcessTgsReqPreauthRequired,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/server_nomatch")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/server_nomatch",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_SERVER_NOMATCH"
+    )]
     ProcessTgsReqServerNomatch,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/must_use_user2user")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/must_use_user2user",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_MUST_USE_USER2USER"
+    )]
     ProcessTgsReqMustUseUser2user,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/path_not_accepted")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/path_not_accepted",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_PATH_NOT_ACCEPTED"
+    )]
     ProcessTgsReqPathNotAccepted,
 
-    #[strum(serialize = "krb5kdc/process_tgs_req/svc_unavailable")]
+    #[strum(
+        serialize = "krb5kdc/process_tgs_req/svc_unavailable",
+        message = "count of process_tgs_req calls resulting in KRB5KDC_ERR_SVC_UNAVAILABLE"
+    )]
     ProcessTgsReqSvcUnavailable,
 }
 
@@ -640,4 +921,8 @@ impl Statistic for Krb5kdcStatistic {
     fn source(&self) -> Source {
         Source::Counter
     }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
@@ -4,7 +4,7 @@
 
 use serde_derive::Deserialize;
 
-use crate::config::SamplerConfig;
+use crate::config::{SamplerConfig, SummaryOutput};
 
 use super::stat::*;
 
@@ -17,6 +17,8 @@ pub struct MemcacheConfig {
     interval: Option<usize>,
     #[serde(default = "crate::common::default_percentiles")]
     percentiles: Vec<f64>,
+    #[serde(default)]
+    summaries: Vec<SummaryOutput>,
     endpoint: Option<String>,
 }
 
@@ -26,6 +28,7 @@ impl Default for MemcacheConfig {
             enabled: Default::default(),
             interval: Default::default(),
             percentiles: crate::common::default_percentiles(),
+            summaries: Default::default(),
             endpoint: None,
         }
     }
@@ -52,6 +55,10 @@ impl SamplerConfig for MemcacheConfig {
         &self.percentiles
     }
 
+    fn summaries(&self) -> &[SummaryOutput] {
+        &self.summaries
+    }
+
     fn statistics(&self) -> Vec<<Self as SamplerConfig>::Statistic> {
         Vec::new()
     }
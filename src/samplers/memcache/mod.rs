@@ -26,6 +26,7 @@ pub struct Memcache {
 #[async_trait]
 impl Sampler for Memcache {
     type Statistic = MemcacheStatistic;
+    const NAME: &'static str = "memcache";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         if !common.config.samplers().memcache().enabled() {
@@ -58,12 +59,9 @@ impl Sampler for Memcache {
 
     fn spawn(common: Common) {
         if common.config().samplers().memcache().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize memcache sampler");
             } else {
@@ -118,10 +116,7 @@ impl Sampler for Memcache {
                 for line in lines {
                     let parts: Vec<&str> = line.split_whitespace().collect();
                     if let Some(name) = parts.get(1) {
-                        if let Some(Ok(value)) = parts
-                            .get(2)
-                            .map(|v| v.parse::<f64>().map(|v| v.floor() as u64))
-                        {
+                        if let Some(Ok(value)) = parts.get(2).map(|v| v.parse::<f64>()) {
                             let statistic = MemcacheStatistic::new((*name).to_string());
 
                             // all statistics will be registered and have the
@@ -141,22 +136,28 @@ impl Sampler for Memcache {
                                         .metrics()
                                         .add_output(&statistic, Output::Percentile(*percentile));
                                 }
+                                for summary in self.sampler_config().summaries() {
+                                    self.common()
+                                        .metrics()
+                                        .add_output(&statistic, Output::from(*summary));
+                                }
                             }
 
                             // all statistics should have their current value
                             // recorded
                             match statistic.source() {
                                 Source::Counter => {
-                                    let _ = self
-                                        .common()
-                                        .metrics()
-                                        .record_counter(&statistic, time, value);
+                                    let _ = self.common().metrics().record_counter(
+                                        &statistic,
+                                        time,
+                                        value.floor() as u64,
+                                    );
                                 }
                                 Source::Gauge => {
                                     let _ = self
                                         .common()
                                         .metrics()
-                                        .record_gauge(&statistic, time, value);
+                                        .record_gauge_float(&statistic, time, value);
                                 }
                                 _ => {}
                             }
@@ -36,4 +36,13 @@ impl Statistic for MemcacheStatistic {
     fn source(&self) -> Source {
         self.summary_type().unwrap_or(Source::Gauge)
     }
+
+    // some gauges, such as `rusage_user`, are fractional
+    fn kind(&self) -> Kind {
+        if self.source() == Source::Gauge {
+            Kind::Float
+        } else {
+            Kind::Unsigned
+        }
+    }
 }
@@ -33,6 +33,7 @@ pub struct Memory {
 #[async_trait]
 impl Sampler for Memory {
     type Statistic = MemoryStatistic;
+    const NAME: &'static str = "memory";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let statistics = common.config().samplers().memory().statistics();
@@ -50,12 +51,9 @@ impl Sampler for Memory {
 
     fn spawn(common: Common) {
         if common.config().samplers().memory().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize memory sampler");
             } else {
@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[derive(
     Clone,
@@ -12,6 +13,7 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -21,146 +23,344 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum MemoryStatistic {
-    #[strum(serialize = "memory/total")]
+    #[strum(
+        serialize = "memory/total",
+        message = "total usable RAM, which is physical RAM minus reserved bits and the kernel binary code"
+    )]
     Total,
-    #[strum(serialize = "memory/free")]
+    #[strum(
+        serialize = "memory/free",
+        message = "physical RAM left unused by the system"
+    )]
     Free,
-    #[strum(serialize = "memory/available")]
+    #[strum(
+        serialize = "memory/available",
+        message = "estimate of the memory available to allocate without swapping"
+    )]
     Available,
-    #[strum(serialize = "memory/buffers")]
+    #[strum(
+        serialize = "memory/buffers",
+        message = "temporary storage for raw disk blocks"
+    )]
     Buffers,
-    #[strum(serialize = "memory/cached")]
+    #[strum(
+        serialize = "memory/cached",
+        message = "physical RAM used as cache memory"
+    )]
     Cached,
-    #[strum(serialize = "memory/swap/cached")]
+    #[strum(
+        serialize = "memory/swap/cached",
+        message = "memory which was moved into swap and back, but remains in the swapfile"
+    )]
     SwapCached,
-    #[strum(serialize = "memory/active/total")]
+    #[strum(
+        serialize = "memory/active/total",
+        message = "memory which has been used more recently and is usually not reclaimed"
+    )]
     Active,
-    #[strum(serialize = "memory/inactive/total")]
+    #[strum(
+        serialize = "memory/inactive/total",
+        message = "memory which has been used less recently and is more eligible to be reclaimed"
+    )]
     Inactive,
-    #[strum(serialize = "memory/active/anon")]
+    #[strum(
+        serialize = "memory/active/anon",
+        message = "anonymous and tmpfs/shmem memory which is in active use"
+    )]
     ActiveAnon,
-    #[strum(serialize = "memory/inactive/anon")]
+    #[strum(
+        serialize = "memory/inactive/anon",
+        message = "anonymous and tmpfs/shmem memory which is a candidate for eviction"
+    )]
     InactiveAnon,
-    #[strum(serialize = "memory/active/file")]
+    #[strum(
+        serialize = "memory/active/file",
+        message = "file cache memory which is in active use"
+    )]
     ActiveFile,
-    #[strum(serialize = "memory/inactive/file")]
+    #[strum(
+        serialize = "memory/inactive/file",
+        message = "file cache memory which is a candidate for reclaiming"
+    )]
     InactiveFile,
-    #[strum(serialize = "memory/unevictable")]
+    #[strum(
+        serialize = "memory/unevictable",
+        message = "memory which is not evictable because it is locked into memory"
+    )]
     Unevictable,
-    #[strum(serialize = "memory/mlocked")]
+    #[strum(
+        serialize = "memory/mlocked",
+        message = "memory which is locked into memory by user programs"
+    )]
     Mlocked,
-    #[strum(serialize = "memory/swap/total")]
+    #[strum(serialize = "memory/swap/total", message = "total swap available")]
     SwapTotal,
-    #[strum(serialize = "memory/swap/free")]
+    #[strum(serialize = "memory/swap/free", message = "total swap free")]
     SwapFree,
-    #[strum(serialize = "memory/dirty")]
+    #[strum(
+        serialize = "memory/dirty",
+        message = "memory waiting to be written back to the disk"
+    )]
     Dirty,
-    #[strum(serialize = "memory/writeback")]
+    #[strum(
+        serialize = "memory/writeback",
+        message = "memory actively being written back to the disk"
+    )]
     Writeback,
-    #[strum(serialize = "memory/anon_pages")]
+    #[strum(
+        serialize = "memory/anon_pages",
+        message = "memory used by pages not backed by files and mapped into userspace page tables"
+    )]
     AnonPages,
-    #[strum(serialize = "memory/mapped")]
+    #[strum(
+        serialize = "memory/mapped",
+        message = "memory used for files which have been mmapped, such as libraries"
+    )]
     Mapped,
-    #[strum(serialize = "memory/shmem")]
+    #[strum(
+        serialize = "memory/shmem",
+        message = "memory used by shared memory (shmem) and tmpfs"
+    )]
     Shmem,
-    #[strum(serialize = "memory/slab/total")]
+    #[strum(
+        serialize = "memory/slab/total",
+        message = "memory used by the kernel to cache data structures for its own use"
+    )]
     SlabTotal,
-    #[strum(serialize = "memory/slab/reclaimable")]
+    #[strum(
+        serialize = "memory/slab/reclaimable",
+        message = "slab memory which can be reclaimed, such as caches"
+    )]
     SlabReclaimable,
-    #[strum(serialize = "memory/slab/unreclaimable")]
+    #[strum(
+        serialize = "memory/slab/unreclaimable",
+        message = "slab memory which cannot be reclaimed even when lacking memory"
+    )]
     SlabUnreclaimable,
-    #[strum(serialize = "memory/kernel_stack")]
+    #[strum(
+        serialize = "memory/kernel_stack",
+        message = "memory used by kernel stack allocations for each task"
+    )]
     KernelStack,
-    #[strum(serialize = "memory/page_tables")]
+    #[strum(
+        serialize = "memory/page_tables",
+        message = "memory dedicated to the lowest page table level"
+    )]
     PageTables,
-    #[strum(serialize = "memory/nfs_unstable")]
+    #[strum(
+        serialize = "memory/nfs_unstable",
+        message = "NFS pages sent to the server but not yet committed to stable storage"
+    )]
     NFSUnstable,
-    #[strum(serialize = "memory/bounce")]
+    #[strum(
+        serialize = "memory/bounce",
+        message = "memory used for block device bounce buffers"
+    )]
     Bounce,
-    #[strum(serialize = "memory/writeback_temp")]
+    #[strum(
+        serialize = "memory/writeback_temp",
+        message = "memory used by FUSE for temporary writeback buffers"
+    )]
     WritebackTmp,
-    #[strum(serialize = "memory/commit/limit")]
+    #[strum(
+        serialize = "memory/commit/limit",
+        message = "memory currently available to be allocated based on the overcommit ratio"
+    )]
     CommitLimit,
-    #[strum(serialize = "memory/commit/committed")]
+    #[strum(
+        serialize = "memory/commit/committed",
+        message = "memory estimated to complete the workload, including swap"
+    )]
     CommittedAS,
-    #[strum(serialize = "memory/vmalloc/total")]
+    #[strum(
+        serialize = "memory/vmalloc/total",
+        message = "total allocated virtual address space"
+    )]
     VmallocTotal,
-    #[strum(serialize = "memory/vmalloc/used")]
+    #[strum(
+        serialize = "memory/vmalloc/used",
+        message = "used virtual address space"
+    )]
     VmallocUsed,
-    #[strum(serialize = "memory/vmalloc/chunk")]
+    #[strum(
+        serialize = "memory/vmalloc/chunk",
+        message = "largest contiguous block of available virtual address space"
+    )]
     VmallocChunk,
-    #[strum(serialize = "memory/hardware_corrupted")]
+    #[strum(
+        serialize = "memory/hardware_corrupted",
+        message = "memory with physical corruption problems which has been set aside by the kernel"
+    )]
     HardwareCorrupted,
-    #[strum(serialize = "memory/anon_hugepages")]
+    #[strum(
+        serialize = "memory/anon_hugepages",
+        message = "memory used by huge pages not backed by files and mapped into userspace page tables"
+    )]
     AnonHugePages,
-    #[strum(serialize = "memory/shmem_hugepages")]
+    #[strum(
+        serialize = "memory/shmem_hugepages",
+        message = "shared memory allocated as transparent hugepages"
+    )]
     ShmemHugePages,
-    #[strum(serialize = "memory/shmem_pmd_mapped")]
+    #[strum(
+        serialize = "memory/shmem_pmd_mapped",
+        message = "shared memory mapped into userspace with hugepages"
+    )]
     ShmemPmdMapped,
-    #[strum(serialize = "memory/hugepages/total")]
+    #[strum(
+        serialize = "memory/hugepages/total",
+        message = "total number of hugepages for the system"
+    )]
     HugePagesTotal,
-    #[strum(serialize = "memory/hugepages/free")]
+    #[strum(
+        serialize = "memory/hugepages/free",
+        message = "number of hugepages available for the system"
+    )]
     HugePagesFree,
-    #[strum(serialize = "memory/hugepages/reserved")]
+    #[strum(
+        serialize = "memory/hugepages/reserved",
+        message = "number of unused hugepages reserved for hugetlbfs"
+    )]
     HugePagesRsvd,
-    #[strum(serialize = "memory/hugepages/surplus")]
+    #[strum(
+        serialize = "memory/hugepages/surplus",
+        message = "number of surplus hugepages"
+    )]
     HugePagesSurp,
-    #[strum(serialize = "memory/hugepage_size")]
+    #[strum(serialize = "memory/hugepage_size", message = "size of each hugepage")]
     Hugepagesize,
-    #[strum(serialize = "memory/hugetlb")]
+    #[strum(
+        serialize = "memory/hugetlb",
+        message = "total memory consumed by hugepages of all sizes"
+    )]
     Hugetlb,
-    #[strum(serialize = "memory/directmap/4k")]
+    #[strum(
+        serialize = "memory/directmap/4k",
+        message = "memory mapped into kernel address space with 4 kB page mappings"
+    )]
     DirectMap4k,
-    #[strum(serialize = "memory/directmap/2M")]
+    #[strum(
+        serialize = "memory/directmap/2M",
+        message = "memory mapped into kernel address space with 2 MB page mappings"
+    )]
     DirectMap2M,
-    #[strum(serialize = "memory/directmap/1G")]
+    #[strum(
+        serialize = "memory/directmap/1G",
+        message = "memory mapped into kernel address space with 1 GB page mappings"
+    )]
     DirectMap1G,
     // NUMA
-    #[strum(serialize = "memory/numa/hit")]
+    #[strum(
+        serialize = "memory/numa/hit",
+        message = "pages successfully allocated on the intended node"
+    )]
     NumaHit,
-    #[strum(serialize = "memory/numa/miss")]
+    #[strum(
+        serialize = "memory/numa/miss",
+        message = "pages which could not be allocated on the intended node"
+    )]
     NumaMiss,
-    #[strum(serialize = "memory/numa/foreign")]
+    #[strum(
+        serialize = "memory/numa/foreign",
+        message = "pages allocated on a remote node even though the allocation should have been local"
+    )]
     NumaForeign,
-    #[strum(serialize = "memory/numa/interleave")]
+    #[strum(
+        serialize = "memory/numa/interleave",
+        message = "pages allocated on the remote node as intended by interleave policy"
+    )]
     NumaInterleave,
-    #[strum(serialize = "memory/numa/local")]
+    #[strum(
+        serialize = "memory/numa/local",
+        message = "pages allocated on the node where the process was running"
+    )]
     NumaLocal,
-    #[strum(serialize = "memory/numa/other")]
+    #[strum(
+        serialize = "memory/numa/other",
+        message = "pages allocated on a node where the process was not running"
+    )]
     NumaOther,
     // THP
-    #[strum(serialize = "memory/thp/fault_alloc")]
+    #[strum(
+        serialize = "memory/thp/fault_alloc",
+        message = "number of times a huge page was allocated to satisfy a page fault"
+    )]
     ThpFaultAlloc,
-    #[strum(serialize = "memory/thp/fault_fallback")]
+    #[strum(
+        serialize = "memory/thp/fault_fallback",
+        message = "number of times a page fault fell back to a base page after failing to allocate a huge page"
+    )]
     ThpFaultFallback,
-    #[strum(serialize = "memory/thp/collapse_alloc")]
+    #[strum(
+        serialize = "memory/thp/collapse_alloc",
+        message = "number of times a hugepage was allocated to collapse multiple pages"
+    )]
     ThpCollapseAlloc,
-    #[strum(serialize = "memory/thp/collapse_alloc_failed")]
+    #[strum(
+        serialize = "memory/thp/collapse_alloc_failed",
+        message = "number of times allocating a hugepage to collapse multiple pages failed"
+    )]
     ThpCollapseAllocFailed,
-    #[strum(serialize = "memory/thp/split_page")]
+    #[strum(
+        serialize = "memory/thp/split_page",
+        message = "number of huge pages which have been split into base pages"
+    )]
     ThpSplitPage,
-    #[strum(serialize = "memory/thp/split_page_failed")]
+    #[strum(
+        serialize = "memory/thp/split_page_failed",
+        message = "number of times a huge page split failed"
+    )]
     ThpSplitPageFailed,
-    #[strum(serialize = "memory/thp/deferred_split_page")]
+    #[strum(
+        serialize = "memory/thp/deferred_split_page",
+        message = "number of times a page split was deferred by placing it on the split queue"
+    )]
     ThpDeferredSplitPage,
     // Compaction
-    #[strum(serialize = "memory/compact/stall")]
+    #[strum(
+        serialize = "memory/compact/stall",
+        message = "number of times processes stall to run compaction"
+    )]
     CompactStall,
-    #[strum(serialize = "memory/compact/fail")]
+    #[strum(
+        serialize = "memory/compact/fail",
+        message = "number of compactions which fail to free a hugepage"
+    )]
     CompactFail,
-    #[strum(serialize = "memory/compact/success")]
+    #[strum(
+        serialize = "memory/compact/success",
+        message = "number of compactions resulting in successfully freeing a hugepage"
+    )]
     CompactSuccess,
-    #[strum(serialize = "memory/compact/migrate_scanned")]
+    #[strum(
+        serialize = "memory/compact/migrate_scanned",
+        message = "number of pages scanned to potentially migrate"
+    )]
     CompactMigrateScanned,
-    #[strum(serialize = "memory/compact/free_scanned")]
+    #[strum(
+        serialize = "memory/compact/free_scanned",
+        message = "number of pages scanned to potentially free"
+    )]
     CompactFreeScanned,
-    #[strum(serialize = "memory/compact/isolated")]
+    #[strum(
+        serialize = "memory/compact/isolated",
+        message = "number of pages isolated by compaction"
+    )]
     CompactIsolated,
-    #[strum(serialize = "memory/compact/daemon/wake")]
+    #[strum(
+        serialize = "memory/compact/daemon/wake",
+        message = "number of times kcompactd has woken"
+    )]
     CompactDaemonWake,
-    #[strum(serialize = "memory/compact/daemon/migrate_scanned")]
+    #[strum(
+        serialize = "memory/compact/daemon/migrate_scanned",
+        message = "number of pages kcompactd has scanned to potentially migrate"
+    )]
     CompactDaemonMigrateScanned,
-    #[strum(serialize = "memory/compact/daemon/free_scanned")]
+    #[strum(
+        serialize = "memory/compact/daemon/free_scanned",
+        message = "number of pages kcompactd has scanned to potentially free"
+    )]
     CompactDaemonFreeScanned,
 }
 
@@ -219,4 +419,41 @@ impl Statistic for MemoryStatistic {
             _ => Source::Gauge,
         }
     }
+
+    fn unit(&self) -> Option<&str> {
+        match self {
+            Self::NumaHit
+            | Self::NumaMiss
+            | Self::NumaForeign
+            | Self::NumaInterleave
+            | Self::NumaLocal
+            | Self::NumaOther => Some("pages"),
+            Self::HugePagesTotal
+            | Self::HugePagesFree
+            | Self::HugePagesRsvd
+            | Self::HugePagesSurp
+            | Self::ThpFaultAlloc
+            | Self::ThpFaultFallback
+            | Self::ThpCollapseAlloc
+            | Self::ThpCollapseAllocFailed
+            | Self::ThpSplitPage
+            | Self::ThpSplitPageFailed
+            | Self::ThpDeferredSplitPage
+            | Self::CompactStall
+            | Self::CompactFail
+            | Self::CompactSuccess
+            | Self::CompactMigrateScanned
+            | Self::CompactFreeScanned
+            | Self::CompactIsolated
+            | Self::CompactDaemonWake
+            | Self::CompactDaemonMigrateScanned
+            | Self::CompactDaemonFreeScanned => None,
+            // values from /proc/meminfo are reported in kibibytes
+            _ => Some("kibibytes"),
+        }
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
@@ -9,6 +9,8 @@ use async_trait::async_trait;
 use tokio::runtime::Runtime;
 use tokio::time::{interval, Interval};
 
+use crate::common::shutdown::Shutdown;
+use crate::common::status::Status;
 use crate::config::General as GeneralConfig;
 use crate::config::{Config, SamplerConfig};
 use crate::*;
@@ -56,9 +58,12 @@ pub use usercall::Usercall;
 pub use xfs::Xfs;
 
 #[async_trait]
-pub trait Sampler: Sized + Send {
+pub trait Sampler: Sized + Send + 'static {
     type Statistic: Statistic;
 
+    /// The name of the sampler, matching its section in the config
+    const NAME: &'static str;
+
     /// Create a new instance of the sampler
     fn new(common: Common) -> Result<Self, anyhow::Error>;
 
@@ -72,6 +77,30 @@ pub trait Sampler: Sized + Send {
     /// wait until next sample interval
     async fn sample(&mut self) -> Result<(), std::io::Error>;
 
+    /// Sample until shutdown, recording progress so that readiness can be
+    /// reported
+    async fn run(mut self) {
+        let interval = std::time::Duration::from_millis(self.interval() as u64);
+        self.common().status().initialized(Self::NAME, interval);
+        let shutdown = self.common().shutdown().clone();
+        let guard = shutdown.register();
+        loop {
+            tokio::select! {
+                result = self.sample() => {
+                    if result.is_ok() {
+                        self.common().status().sampled(Self::NAME);
+                    }
+                }
+                _ = shutdown.wait() => break,
+            }
+        }
+        // dropping the sampler detaches any BPF probes, which must happen
+        // before the guard is released
+        drop(self);
+        drop(guard);
+        debug!("stopped {} sampler", Self::NAME);
+    }
+
     fn interval(&self) -> usize {
         self.sampler_config()
             .interval()
@@ -103,40 +132,58 @@ pub trait Sampler: Sized + Send {
     /// Register all the statistics
     fn register(&self) {
         for statistic in self.sampler_config().statistics() {
-            self.common()
-                .metrics()
-                .add_output(&statistic, Output::Reading);
-            let percentiles = self.sampler_config().percentiles();
-            if !percentiles.is_empty() {
-                if statistic.source() == Source::Distribution {
-                    self.common().metrics().add_summary(
-                        &statistic,
-                        Summary::heatmap(
-                            1_000_000_000,
-                            2,
-                            Duration::from_secs(
-                                self.common()
-                                    .config()
-                                    .general()
-                                    .window()
-                                    .try_into()
-                                    .unwrap(),
-                            ),
-                            Duration::from_secs(1),
+            self.register_statistic(&statistic);
+        }
+    }
+
+    /// Register a single statistic with a reading output and a summary for
+    /// the configured percentiles. This may be used directly for statistics
+    /// which are discovered at runtime, such as labeled per-entity statistics.
+    fn register_statistic(&self, statistic: &dyn Statistic) {
+        self.common()
+            .metrics()
+            .add_output(statistic, Output::Reading);
+        let percentiles = self.sampler_config().percentiles();
+        let summaries = self.sampler_config().summaries();
+        if !percentiles.is_empty() || !summaries.is_empty() {
+            if statistic.source() == Source::Distribution {
+                self.common().metrics().add_summary(
+                    statistic,
+                    Summary::heatmap(
+                        1_000_000_000,
+                        2,
+                        Duration::from_secs(
+                            self.common()
+                                .config()
+                                .general()
+                                .window()
+                                .try_into()
+                                .unwrap(),
                         ),
-                    );
-                } else {
-                    self.common()
-                        .metrics()
-                        .add_summary(&statistic, Summary::stream(self.samples()));
-                }
-            }
-            for percentile in percentiles {
+                        Duration::from_secs(1),
+                    ),
+                );
+            } else {
                 self.common()
                     .metrics()
-                    .add_output(&statistic, Output::Percentile(*percentile));
+                    .add_summary(statistic, Summary::stream(self.samples()));
             }
         }
+        for percentile in percentiles {
+            self.common()
+                .metrics()
+                .add_output(statistic, Output::Percentile(*percentile));
+        }
+        for summary in summaries {
+            self.common()
+                .metrics()
+                .add_output(statistic, Output::from(*summary));
+        }
+        if statistic.source() == Source::Distribution && self.general_config().histograms() {
+            self.common()
+                .metrics()
+                .add_output(statistic, Output::Histogram);
+        }
     }
 
     fn samples(&self) -> usize {
@@ -147,6 +194,19 @@ pub trait Sampler: Sized + Send {
         self.common().metrics()
     }
 
+    /// Record a value for a counter or gauge statistic according to its source
+    fn record(&self, statistic: &dyn Statistic, time: Instant, value: u64) {
+        match statistic.source() {
+            Source::Counter => {
+                let _ = self.metrics().record_counter(statistic, time, value);
+            }
+            Source::Gauge => {
+                let _ = self.metrics().record_gauge(statistic, time, value);
+            }
+            _ => {}
+        }
+    }
+
     /// Used to map errors according to fault tolerance
     /// WouldBlock is returned as-is so that async/await behaves as expected
     /// All other errors are handled per fault tolerance setting
@@ -171,6 +231,8 @@ pub struct Common {
     hardware_info: Arc<HardwareInfo>,
     interval: Option<Interval>,
     metrics: Arc<Metrics>,
+    shutdown: Arc<Shutdown>,
+    status: Arc<Status>,
 }
 
 impl Clone for Common {
@@ -181,18 +243,28 @@ impl Clone for Common {
             hardware_info: self.hardware_info.clone(),
             interval: None,
             metrics: self.metrics.clone(),
+            shutdown: self.shutdown.clone(),
+            status: self.status.clone(),
         }
     }
 }
 
 impl Common {
-    pub fn new(config: Arc<Config>, metrics: Arc<Metrics>, runtime: Arc<Runtime>) -> Self {
+    pub fn new(
+        config: Arc<Config>,
+        metrics: Arc<Metrics>,
+        runtime: Arc<Runtime>,
+        shutdown: Arc<Shutdown>,
+        status: Arc<Status>,
+    ) -> Self {
         Self {
             config,
             hardware_info: Arc::new(HardwareInfo::new()),
             interval: None,
             metrics,
             runtime,
+            shutdown,
+            status,
         }
     }
 
@@ -219,4 +291,27 @@ impl Common {
     pub fn metrics(&self) -> &Metrics {
         &self.metrics
     }
+
+    /// Signals the samplers to stop
+    pub fn shutdown(&self) -> &Arc<Shutdown> {
+        &self.shutdown
+    }
+
+    pub fn status(&self) -> &Status {
+        &self.status
+    }
+
+    /// Attach a probe to the bpf instance, recording it as attached for the
+    /// sampler on success
+    #[cfg(feature = "bpf")]
+    pub fn attach_probe(
+        &self,
+        sampler: &'static str,
+        probe: &crate::common::bpf::Probe,
+        bpf: &mut bcc::BPF,
+    ) -> Result<(), anyhow::Error> {
+        probe.try_attach_to_bpf(bpf)?;
+        self.status.attached(sampler, &probe.name);
+        Ok(())
+    }
 }
@@ -5,7 +5,7 @@
 use serde_derive::Deserialize;
 use strum::IntoEnumIterator;
 
-use crate::config::SamplerConfig;
+use crate::config::{SamplerConfig, SummaryOutput};
 
 use super::stat::*;
 
@@ -16,10 +16,18 @@ pub struct NetworkConfig {
     bpf: bool,
     #[serde(default)]
     enabled: bool,
+    #[serde(default = "default_include")]
+    include: String,
+    #[serde(default)]
+    exclude: Option<String>,
     #[serde(default)]
     interval: Option<usize>,
+    #[serde(default)]
+    per_interface: bool,
     #[serde(default = "crate::common::default_percentiles")]
     percentiles: Vec<f64>,
+    #[serde(default)]
+    summaries: Vec<SummaryOutput>,
     #[serde(default = "default_statistics")]
     statistics: Vec<NetworkStatistic>,
 }
@@ -29,8 +37,12 @@ impl Default for NetworkConfig {
         Self {
             bpf: Default::default(),
             enabled: Default::default(),
+            include: default_include(),
+            exclude: Default::default(),
             interval: Default::default(),
+            per_interface: Default::default(),
             percentiles: crate::common::default_percentiles(),
+            summaries: Default::default(),
             statistics: default_statistics(),
         }
     }
@@ -40,6 +52,28 @@ fn default_statistics() -> Vec<NetworkStatistic> {
     NetworkStatistic::iter().collect()
 }
 
+fn default_include() -> String {
+    ".*".to_string()
+}
+
+impl NetworkConfig {
+    /// Regular expression for the interface names which should be sampled
+    pub fn include(&self) -> &str {
+        &self.include
+    }
+
+    /// Optional regular expression for interface names which should be
+    /// excluded even if they match the include pattern
+    pub fn exclude(&self) -> Option<&str> {
+        self.exclude.as_deref()
+    }
+
+    /// Whether statistics should also be reported for each interface
+    pub fn per_interface(&self) -> bool {
+        self.per_interface
+    }
+}
+
 impl SamplerConfig for NetworkConfig {
     type Statistic = NetworkStatistic;
 
@@ -59,6 +93,10 @@ impl SamplerConfig for NetworkConfig {
         &self.percentiles
     }
 
+    fn summaries(&self) -> &[SummaryOutput] {
+        &self.summaries
+    }
+
     fn statistics(&self) -> Vec<<Self as SamplerConfig>::Statistic> {
         let mut enabled = Vec::new();
         for statistic in self.statistics.iter() {
@@ -2,13 +2,13 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
-use std::collections::HashMap;
-#[cfg(feature = "bpf")]
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use tokio::io::SeekFrom;
 
 use async_trait::async_trait;
+use regex::Regex;
+use strum::IntoEnumIterator;
 use tokio::fs::File;
 use tokio::io::{AsyncBufReadExt, AsyncSeekExt, BufReader};
 
@@ -28,6 +28,9 @@ pub struct Network {
     bpf: Option<Arc<Mutex<BPF>>>,
     bpf_last: Arc<Mutex<Instant>>,
     common: Common,
+    exclude: Option<Regex>,
+    include: Regex,
+    interfaces: HashSet<String>,
     proc_net_dev: Option<File>,
     statistics: Vec<NetworkStatistic>,
 }
@@ -35,16 +38,29 @@ pub struct Network {
 #[async_trait]
 impl Sampler for Network {
     type Statistic = NetworkStatistic;
+    const NAME: &'static str = "network";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let fault_tolerant = common.config.general().fault_tolerant();
         let statistics = common.config().samplers().network().statistics();
+        let include = Regex::new(common.config().samplers().network().include())
+            .map_err(|e| format_err!("invalid network include pattern: {}", e))?;
+        let exclude = match common.config().samplers().network().exclude() {
+            Some(exclude) => Some(
+                Regex::new(exclude)
+                    .map_err(|e| format_err!("invalid network exclude pattern: {}", e))?,
+            ),
+            None => None,
+        };
 
         #[allow(unused_mut)]
         let mut sampler = Self {
             bpf: None,
             bpf_last: Arc::new(Mutex::new(Instant::now())),
             common,
+            exclude,
+            include,
+            interfaces: HashSet::new(),
             proc_net_dev: None,
             statistics,
         };
@@ -57,7 +73,14 @@ impl Sampler for Network {
         }
 
         if sampler.sampler_config().enabled() {
-            sampler.register();
+            // link statistics are only meaningful per-interface
+            for statistic in sampler
+                .statistics
+                .iter()
+                .filter(|s| s.sysfs_file().is_none())
+            {
+                sampler.register_statistic(statistic);
+            }
         }
 
         Ok(sampler)
@@ -65,12 +88,9 @@ impl Sampler for Network {
 
     fn spawn(common: Common) {
         if common.config().samplers().network().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize network sampler");
             } else {
@@ -150,11 +170,11 @@ impl Network {
                 // load + attach the kernel probes that are required to the bpf instance.
                 for probe in probes {
                     if self.common.config.fault_tolerant() {
-                        if let Err(e) = probe.try_attach_to_bpf(&mut bpf) {
+                        if let Err(e) = self.common.attach_probe(Self::NAME, &probe, &mut bpf) {
                             warn!("skipping {} with error: {}", probe.name, e);
                         }
                     } else {
-                        probe.try_attach_to_bpf(&mut bpf)?;
+                        self.common.attach_probe(Self::NAME, &probe, &mut bpf)?;
                     }
                 }
 
@@ -173,6 +193,7 @@ impl Network {
         }
 
         let mut result = HashMap::new();
+        let mut per_interface = Vec::new();
 
         if let Some(file) = &mut self.proc_net_dev {
             file.seek(SeekFrom::Start(0)).await?;
@@ -180,19 +201,17 @@ impl Network {
             let mut line = String::new();
 
             while reader.read_line(&mut line).await? > 0 {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if !parts.is_empty() && parts[1].parse::<u64>().is_ok() {
-                    for statistic in &self.statistics {
-                        if let Some(field) = statistic.field_number() {
-                            if !result.contains_key(statistic) {
-                                result.insert(statistic, 0);
-                            }
-                            let current = result.get_mut(statistic).unwrap();
-                            *current += parts
-                                .get(field)
-                                .map(|v| v.parse().unwrap_or(0))
-                                .unwrap_or(0);
+                if let Some((interface, stats)) = parse_proc_net_dev(&line) {
+                    let excluded = self
+                        .exclude
+                        .as_ref()
+                        .map(|re| re.is_match(interface))
+                        .unwrap_or(false);
+                    if self.include.is_match(interface) && !excluded {
+                        for (statistic, value) in &stats {
+                            *result.entry(*statistic).or_insert(0) += value;
                         }
+                        per_interface.push((interface.to_string(), stats));
                     }
                 }
                 line.clear();
@@ -205,9 +224,68 @@ impl Network {
                 let _ = self.metrics().record_counter(statistic, time, *value);
             }
         }
+
+        if self.common.config().samplers().network().per_interface() {
+            for (interface, stats) in &mut per_interface {
+                self.sample_sysfs(interface, stats).await;
+                let labels = Labels::new().with("interface", interface.as_str());
+                if !self.interfaces.contains(interface) {
+                    for statistic in self.statistics.iter().filter(|s| s.bpf_table().is_none()) {
+                        self.register_statistic(&Labeled::new(*statistic, labels.clone()));
+                    }
+                    self.interfaces.insert(interface.clone());
+                }
+                for statistic in &self.statistics {
+                    if let Some(value) = stats.get(statistic) {
+                        self.record(&Labeled::new(*statistic, labels.clone()), time, *value);
+                    }
+                }
+            }
+
+            // stop tracking interfaces which have been removed
+            let present: HashSet<&str> = per_interface
+                .iter()
+                .map(|(interface, _)| interface.as_str())
+                .collect();
+            let removed: Vec<String> = self
+                .interfaces
+                .iter()
+                .filter(|interface| !present.contains(interface.as_str()))
+                .cloned()
+                .collect();
+            for interface in removed {
+                let labels = Labels::new().with("interface", &interface);
+                for statistic in self.statistics.iter().filter(|s| s.bpf_table().is_none()) {
+                    self.metrics()
+                        .deregister(&Labeled::new(*statistic, labels.clone()));
+                }
+                self.interfaces.remove(&interface);
+            }
+        }
         Ok(())
     }
 
+    /// Reads the link statistics for an interface from sysfs. Some of these
+    /// files cannot be read while the link is down, so any which fail to read
+    /// or parse are skipped.
+    async fn sample_sysfs(&self, interface: &str, stats: &mut HashMap<NetworkStatistic, u64>) {
+        for statistic in &self.statistics {
+            if let Some(file) = statistic.sysfs_file() {
+                let path = format!("/sys/class/net/{}/{}", interface, file);
+                if let Ok(content) = tokio::fs::read_to_string(path).await {
+                    if let Ok(value) = content.trim().parse::<u64>() {
+                        let value = match statistic {
+                            // speed is reported in megabits per second
+                            NetworkStatistic::LinkSpeed => value * 1_000_000,
+                            _ => value,
+                        };
+                        stats.insert(*statistic, value);
+                    }
+                }
+            }
+        }
+    }
+
     #[cfg(feature = "bpf")]
     fn sample_bpf(&self) -> Result<(), std::io::Error> {
         if self.bpf_last.lock().unwrap().elapsed()
@@ -217,7 +295,7 @@ impl Network {
             if let Some(ref bpf) = self.bpf {
                 let bpf = bpf.lock().unwrap();
                 for statistic in self.statistics.iter().filter(|s| s.bpf_table().is_some()) {
-                    if let Ok(mut table) = (*bpf).inner.table(statistic.bpf_table().unwrap()) {
+                    if let Ok(table) = (*bpf).inner.table(statistic.bpf_table().unwrap()) {
                         for (&value, &count) in &map_from_table(&mut table) {
                             if count > 0 {
                                 let _ = self.metrics().record_bucket(statistic, time, value, count);
@@ -231,3 +309,50 @@ impl Network {
         Ok(())
     }
 }
+
+/// Parses a line of `/proc/net/dev` returning the interface name and the
+/// counters for each statistic. Header lines are skipped.
+fn parse_proc_net_dev(line: &str) -> Option<(&str, HashMap<NetworkStatistic, u64>)> {
+    let (interface, counters) = line.split_once(':')?;
+    let parts: Vec<&str> = counters.split_whitespace().collect();
+    if parts.is_empty() || parts[0].parse::<u64>().is_err() {
+        return None;
+    }
+    let mut result = HashMap::new();
+    for statistic in NetworkStatistic::iter() {
+        if let Some(field) = statistic.field_number() {
+            let value = parts
+                .get(field - 1)
+                .map(|v| v.parse().unwrap_or(0))
+                .unwrap_or(0);
+            result.insert(statistic, value);
+        }
+    }
+    Some((interface.trim(), result))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_net_dev() {
+        assert!(parse_proc_net_dev(
+            "Inter-|   Receive                                                |  Transmit"
+        )
+        .is_none());
+        assert!(parse_proc_net_dev(
+            " face |bytes    packets errs drop fifo frame compressed multicast|bytes"
+        )
+        .is_none());
+        let line = "  eth0: 1234    10    1    2    0     0          0         3     5678      20    0    0    0     0       4          0";
+        let (interface, result) = parse_proc_net_dev(line).expect("failed to parse");
+        assert_eq!(interface, "eth0");
+        assert_eq!(result.get(&NetworkStatistic::ReceiveBytes), Some(&1234));
+        assert_eq!(result.get(&NetworkStatistic::ReceiveDrops), Some(&2));
+        assert_eq!(result.get(&NetworkStatistic::ReceiveMulticast), Some(&3));
+        assert_eq!(result.get(&NetworkStatistic::TransmitBytes), Some(&5678));
+        assert_eq!(result.get(&NetworkStatistic::TransmitCarrier), Some(&4));
+        assert_eq!(result.get(&NetworkStatistic::ReceiveSize), None);
+    }
+}
@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[cfg(feature = "bpf")]
 use crate::common::bpf::*;
@@ -15,6 +16,7 @@ use crate::common::bpf::*;
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -24,42 +26,110 @@ use crate::common::bpf::*;
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum NetworkStatistic {
-    #[strum(serialize = "network/receive/bytes")]
+    #[strum(
+        serialize = "network/receive/bytes",
+        message = "number of bytes received"
+    )]
     ReceiveBytes,
-    #[strum(serialize = "network/receive/packets")]
+    #[strum(
+        serialize = "network/receive/packets",
+        message = "total number of packets received"
+    )]
     ReceivePackets,
-    #[strum(serialize = "network/receive/errors")]
+    #[strum(
+        serialize = "network/receive/errors",
+        message = "number of receive errors detected by the device driver"
+    )]
     ReceiveErrors,
-    #[strum(serialize = "network/receive/drops")]
+    #[strum(
+        serialize = "network/receive/drops",
+        message = "number of received packets which were dropped by the device driver"
+    )]
     ReceiveDrops,
-    #[strum(serialize = "network/receive/fifo")]
+    #[strum(
+        serialize = "network/receive/fifo",
+        message = "number of FIFO buffer errors on receive"
+    )]
     ReceiveFifo,
-    #[strum(serialize = "network/receive/frame")]
+    #[strum(
+        serialize = "network/receive/frame",
+        message = "number of packets received with framing errors"
+    )]
     ReceiveFrame,
-    #[strum(serialize = "network/receive/compressed")]
+    #[strum(
+        serialize = "network/receive/compressed",
+        message = "number of compressed packets received"
+    )]
     ReceiveCompressed,
-    #[strum(serialize = "network/receive/multicast")]
+    #[strum(
+        serialize = "network/receive/multicast",
+        message = "number of multicast packets received"
+    )]
     ReceiveMulticast,
-    #[strum(serialize = "network/transmit/bytes")]
+    #[strum(
+        serialize = "network/transmit/bytes",
+        message = "number of bytes transmitted"
+    )]
     TransmitBytes,
-    #[strum(serialize = "network/transmit/packets")]
+    #[strum(
+        serialize = "network/transmit/packets",
+        message = "total number of packets transmitted"
+    )]
     TransmitPackets,
-    #[strum(serialize = "network/transmit/errors")]
+    #[strum(
+        serialize = "network/transmit/errors",
+        message = "total number of errors when transmitting packets"
+    )]
     TransmitErrors,
-    #[strum(serialize = "network/transmit/drops")]
+    #[strum(
+        serialize = "network/transmit/drops",
+        message = "number of packets to transmit which were dropped by the device driver"
+    )]
     TransmitDrops,
-    #[strum(serialize = "network/transmit/fifo")]
+    #[strum(
+        serialize = "network/transmit/fifo",
+        message = "number of FIFO buffer errors on transmit"
+    )]
     TransmitFifo,
-    #[strum(serialize = "network/transmit/collisions")]
+    #[strum(
+        serialize = "network/transmit/collisions",
+        message = "number of collisions detected"
+    )]
     TransmitCollisions,
-    #[strum(serialize = "network/transmit/carrier")]
+    #[strum(
+        serialize = "network/transmit/carrier",
+        message = "number of carrier losses detected by the device driver"
+    )]
     TransmitCarrier,
-    #[strum(serialize = "network/transmit/compressed")]
+    #[strum(
+        serialize = "network/transmit/compressed",
+        message = "number of compressed packets transmitted"
+    )]
     TransmitCompressed,
-    #[strum(serialize = "network/receive/size")]
+    #[strum(
+        serialize = "network/receive/size",
+        message = "size distribution of received packets"
+    )]
     ReceiveSize,
-    #[strum(serialize = "network/transmit/size")]
+    #[strum(
+        serialize = "network/transmit/size",
+        message = "size distribution of transmitted packets"
+    )]
     TransmitSize,
+    #[strum(serialize = "network/link/speed", message = "negotiated link speed")]
+    LinkSpeed,
+    #[strum(serialize = "network/link/mtu", message = "maximum transmission unit")]
+    LinkMtu,
+    #[strum(
+        serialize = "network/link/carrier",
+        message = "1 if the interface has carrier, 0 if not"
+    )]
+    LinkCarrier,
+    #[strum(
+        serialize = "network/link/carrier_changes",
+        message = "number of times the carrier state changed"
+    )]
+    LinkCarrierChanges,
 }
 
 impl NetworkStatistic {
@@ -85,6 +155,18 @@ impl NetworkStatistic {
         }
     }
 
+    /// The file in `/sys/class/net/<interface>/` which provides the value
+    /// for link statistics. These are only reported per-interface.
+    pub fn sysfs_file(self) -> Option<&'static str> {
+        match self {
+            Self::LinkSpeed => Some("speed"),
+            Self::LinkMtu => Some("mtu"),
+            Self::LinkCarrier => Some("carrier"),
+            Self::LinkCarrierChanges => Some("carrier_changes"),
+            _ => None,
+        }
+    }
+
     pub fn bpf_table(self) -> Option<&'static str> {
         match self {
             Self::ReceiveSize => Some("rx_size"),
@@ -127,10 +209,41 @@ impl Statistic for NetworkStatistic {
     }
 
     fn source(&self) -> Source {
-        if self.bpf_table().is_some() {
-            Source::Distribution
+        match self {
+            Self::LinkSpeed | Self::LinkMtu | Self::LinkCarrier => Source::Gauge,
+            _ => {
+                if self.bpf_table().is_some() {
+                    Source::Distribution
+                } else {
+                    Source::Counter
+                }
+            }
+        }
+    }
+
+    /// Many drivers keep the counters in `/proc/net/dev` as an `unsigned
+    /// long`, so they wrap at 32 bits on 32-bit platforms.
+    fn wraps_at(&self) -> Option<u64> {
+        if cfg!(target_pointer_width = "32") && self.field_number().is_some() {
+            Some(1 << 32)
         } else {
-            Source::Counter
+            None
+        }
+    }
+
+    fn unit(&self) -> Option<&str> {
+        match self {
+            Self::ReceiveBytes
+            | Self::TransmitBytes
+            | Self::ReceiveSize
+            | Self::TransmitSize
+            | Self::LinkMtu => Some("bytes"),
+            Self::LinkSpeed => Some("bits_per_second"),
+            _ => None,
         }
     }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
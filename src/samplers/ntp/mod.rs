@@ -24,6 +24,7 @@ pub struct Ntp {
 #[async_trait]
 impl Sampler for Ntp {
     type Statistic = NtpStatistic;
+    const NAME: &'static str = "ntp";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let statistics = common.config().samplers().ntp().statistics();
@@ -40,13 +41,10 @@ impl Sampler for Ntp {
     fn spawn(common: Common) {
         debug!("spawning");
         if common.config().samplers().ntp().enabled() {
+            common.status().register(Self::NAME);
             debug!("sampler is enabled");
-            if let Ok(mut ntp) = Ntp::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = ntp.sample().await;
-                    }
-                });
+            if let Ok(ntp) = Ntp::new(common.clone()) {
+                common.runtime().spawn(ntp.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize ntp sampler");
             } else {
@@ -105,6 +103,24 @@ impl Ntp {
                 timeval.esterror as u64 * MICROSECOND,
             );
         }
+
+        // the offset is only available through the kernel clock state, which
+        // is in nanoseconds or microseconds depending on the clock status
+        #[cfg(target_os = "linux")]
+        {
+            let mut timex: libc::timex = unsafe { std::mem::zeroed() };
+            let time = Instant::now();
+            if unsafe { libc::ntp_adjtime(&mut timex) } >= 0 {
+                let offset = if timex.status & libc::STA_NANO != 0 {
+                    timex.offset as i64
+                } else {
+                    timex.offset as i64 * MICROSECOND as i64
+                };
+                let _ = self
+                    .metrics()
+                    .record_gauge_signed(&NtpStatistic::Offset, time, offset);
+            }
+        }
         Ok(())
     }
 
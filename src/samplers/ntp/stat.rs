@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[derive(
     Clone,
@@ -12,6 +13,7 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -21,10 +23,21 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum NtpStatistic {
-    #[strum(serialize = "ntp/estimated_error")]
+    #[strum(
+        serialize = "ntp/estimated_error",
+        message = "the current estimated error of the local clock"
+    )]
     EstimatedError,
-    #[strum(serialize = "ntp/maximum_error")]
+    #[strum(
+        serialize = "ntp/maximum_error",
+        message = "the maximum error of the local clock"
+    )]
     MaximumError,
+    #[strum(
+        serialize = "ntp/offset",
+        message = "the offset of the local clock from the reference clock"
+    )]
+    Offset,
 }
 
 impl Statistic for NtpStatistic {
@@ -35,4 +48,19 @@ impl Statistic for NtpStatistic {
     fn source(&self) -> Source {
         Source::Gauge
     }
+
+    fn kind(&self) -> Kind {
+        match self {
+            Self::Offset => Kind::Signed,
+            _ => Kind::Unsigned,
+        }
+    }
+
+    fn unit(&self) -> Option<&str> {
+        Some("nanoseconds")
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
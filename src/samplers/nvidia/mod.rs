@@ -28,6 +28,7 @@ pub struct Nvidia {
 #[async_trait]
 impl Sampler for Nvidia {
     type Statistic = NvidiaStatistic;
+    const NAME: &'static str = "nvidia";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let statistics = common.config().samplers().nvidia().statistics();
@@ -53,13 +54,10 @@ impl Sampler for Nvidia {
     fn spawn(common: Common) {
         debug!("spawning");
         if common.config().samplers().nvidia().enabled() {
+            common.status().register(Self::NAME);
             debug!("sampler is enabled");
-            if let Ok(mut sampler) = Nvidia::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            if let Ok(sampler) = Nvidia::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize nvidia sampler");
             } else {
@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[derive(
     Clone,
@@ -12,6 +13,7 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -21,51 +23,96 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum NvidiaConfigStatistic {
-    #[strum(serialize = "gpu/temperature")]
+    #[strum(serialize = "gpu/temperature", message = "current GPU temperature")]
     GpuTemperature,
-    #[strum(serialize = "memory/ecc/sbe")]
+    #[strum(
+        serialize = "memory/ecc/sbe",
+        message = "count of single-bit errors (correctable)"
+    )]
     MemoryEccSbe,
-    #[strum(serialize = "memory/ecc/dbe")]
+    #[strum(
+        serialize = "memory/ecc/dbe",
+        message = "count of double-bit errors (uncorrectable)"
+    )]
     MemoryEccDbe,
-    #[strum(serialize = "memory/ecc/enabled")]
+    #[strum(
+        serialize = "memory/ecc/enabled",
+        message = "1 if ECC is enabled, 0 if not"
+    )]
     MemoryEccEnabled,
-    #[strum(serialize = "power/usage")]
+    #[strum(serialize = "power/usage", message = "current power usage")]
     PowerUsage,
-    #[strum(serialize = "power/limit")]
+    #[strum(serialize = "power/limit", message = "enforced power limit")]
     PowerLimit,
-    #[strum(serialize = "energy/consumption")]
+    #[strum(
+        serialize = "energy/consumption",
+        message = "total energy consumption since boot"
+    )]
     EnergyConsumption,
-    #[strum(serialize = "clock/sm/current")]
+    #[strum(
+        serialize = "clock/sm/current",
+        message = "current streaming multiprocessor clock speed"
+    )]
     ClockSMCurrent,
-    #[strum(serialize = "clock/memory/current")]
+    #[strum(
+        serialize = "clock/memory/current",
+        message = "current memory clock speed"
+    )]
     ClockMemoryCurrent,
-    #[strum(serialize = "pcie/replay")]
+    #[strum(
+        serialize = "pcie/replay",
+        message = "count of PCIe replays, which may indicate link issues"
+    )]
     PcieReplay,
-    #[strum(serialize = "pcie/rx/throughput")]
+    #[strum(serialize = "pcie/rx/throughput", message = "PCIe receive throughput")]
     PcieRxThroughput,
-    #[strum(serialize = "pcie/tx/throughput")]
+    #[strum(serialize = "pcie/tx/throughput", message = "PCIe transmit throughput")]
     PcieTxThroughput,
-    #[strum(serialize = "gpu/utilization")]
+    #[strum(
+        serialize = "gpu/utilization",
+        message = "GPU utilization as a percentage"
+    )]
     GpuUtilization,
-    #[strum(serialize = "memory/utilization")]
+    #[strum(
+        serialize = "memory/utilization",
+        message = "memory copy utilization as a percentage"
+    )]
     MemoryUtilization,
-    #[strum(serialize = "decoder/utilization")]
+    #[strum(
+        serialize = "decoder/utilization",
+        message = "video decoder utilization as a percentage"
+    )]
     DecoderUtilization,
-    #[strum(serialize = "encoder/utilization")]
+    #[strum(
+        serialize = "encoder/utilization",
+        message = "video encoder utilization as a percentage"
+    )]
     EncoderUtilization,
-    #[strum(serialize = "memory/fb/free")]
+    #[strum(serialize = "memory/fb/free", message = "framebuffer memory free")]
     MemoryFbFree,
-    #[strum(serialize = "memory/fb/total")]
+    #[strum(serialize = "memory/fb/total", message = "total framebuffer memory")]
     MemoryFbTotal,
-    #[strum(serialize = "memory/fb/used")]
+    #[strum(serialize = "memory/fb/used", message = "framebuffer memory used")]
     MemoryFbUsed,
-    #[strum(serialize = "memory/retired/sbe")]
+    #[strum(
+        serialize = "memory/retired/sbe",
+        message = "memory pages retired due to multiple single-bit errors"
+    )]
     MemoryRetiredSbe,
-    #[strum(serialize = "memory/retired/dbe")]
+    #[strum(
+        serialize = "memory/retired/dbe",
+        message = "memory pages retired due to double-bit error"
+    )]
     MemoryRetiredDbe,
-    #[strum(serialize = "memory/retired/pending")]
+    #[strum(
+        serialize = "memory/retired/pending",
+        message = "1 if memory pages are pending retirement, 0 if not"
+    )]
     MemoryRetiredPending,
-    #[strum(serialize = "processes/compute")]
+    #[strum(
+        serialize = "processes/compute",
+        message = "number of processes running in compute context"
+    )]
     ProcessesCompute,
 }
 
@@ -229,4 +276,61 @@ impl Statistic for NvidiaStatistic {
             _ => Source::Gauge,
         }
     }
+
+    fn unit(&self) -> Option<&str> {
+        NvidiaConfigStatistic::from(*self).unit()
+    }
+
+    fn description(&self) -> Option<&str> {
+        NvidiaConfigStatistic::from(*self).get_message()
+    }
+}
+
+impl NvidiaConfigStatistic {
+    /// The unit of the values reported for the statistic
+    pub fn unit(self) -> Option<&'static str> {
+        match self {
+            Self::GpuTemperature => Some("celsius"),
+            Self::PowerUsage | Self::PowerLimit => Some("watts"),
+            Self::EnergyConsumption => Some("joules"),
+            Self::ClockSMCurrent | Self::ClockMemoryCurrent => Some("megahertz"),
+            Self::PcieRxThroughput | Self::PcieTxThroughput => Some("kilobytes_per_second"),
+            Self::GpuUtilization
+            | Self::MemoryUtilization
+            | Self::DecoderUtilization
+            | Self::EncoderUtilization => Some("percent"),
+            Self::MemoryFbFree | Self::MemoryFbTotal | Self::MemoryFbUsed => Some("bytes"),
+            _ => None,
+        }
+    }
+}
+
+impl From<NvidiaStatistic> for NvidiaConfigStatistic {
+    fn from(statistic: NvidiaStatistic) -> Self {
+        match statistic {
+            NvidiaStatistic::GpuTemperature(_) => Self::GpuTemperature,
+            NvidiaStatistic::MemoryEccSbe(_) => Self::MemoryEccSbe,
+            NvidiaStatistic::MemoryEccDbe(_) => Self::MemoryEccDbe,
+            NvidiaStatistic::MemoryEccEnabled(_) => Self::MemoryEccEnabled,
+            NvidiaStatistic::PowerUsage(_) => Self::PowerUsage,
+            NvidiaStatistic::PowerLimit(_) => Self::PowerLimit,
+            NvidiaStatistic::EnergyConsumption(_) => Self::EnergyConsumption,
+            NvidiaStatistic::ClockSMCurrent(_) => Self::ClockSMCurrent,
+            NvidiaStatistic::ClockMemoryCurrent(_) => Self::ClockMemoryCurrent,
+            NvidiaStatistic::PcieReplay(_) => Self::PcieReplay,
+            NvidiaStatistic::PcieRxThroughput(_) => Self::PcieRxThroughput,
+            NvidiaStatistic::PcieTxThroughput(_) => Self::PcieTxThroughput,
+            NvidiaStatistic::GpuUtilization(_) => Self::GpuUtilization,
+            NvidiaStatistic::MemoryUtilization(_) => Self::MemoryUtilization,
+            NvidiaStatistic::DecoderUtilization(_) => Self::DecoderUtilization,
+            NvidiaStatistic::EncoderUtilization(_) => Self::EncoderUtilization,
+            NvidiaStatistic::MemoryFbFree(_) => Self::MemoryFbFree,
+            NvidiaStatistic::MemoryFbTotal(_) => Self::MemoryFbTotal,
+            NvidiaStatistic::MemoryFbUsed(_) => Self::MemoryFbUsed,
+            NvidiaStatistic::MemoryRetiredSbe(_) => Self::MemoryRetiredSbe,
+            NvidiaStatistic::MemoryRetiredDbe(_) => Self::MemoryRetiredDbe,
+            NvidiaStatistic::MemoryRetiredPending(_) => Self::MemoryRetiredPending,
+            NvidiaStatistic::ProcessesCompute(_) => Self::ProcessesCompute,
+        }
+    }
 }
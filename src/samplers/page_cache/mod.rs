@@ -31,6 +31,7 @@ pub struct PageCache {
 #[async_trait]
 impl Sampler for PageCache {
     type Statistic = PageCacheStatistic;
+    const NAME: &'static str = "page_cache";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let fault_tolerant = common.config.general().fault_tolerant();
@@ -60,12 +61,9 @@ impl Sampler for PageCache {
 
     fn spawn(common: Common) {
         if common.config().samplers().page_cache().enabled() {
-            if let Ok(mut interrupt) = PageCache::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = interrupt.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(interrupt) = PageCache::new(common.clone()) {
+                common.runtime().spawn(interrupt.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize page_cache sampler");
             } else {
@@ -140,11 +138,11 @@ impl PageCache {
                 // load + attach the kernel probes that are required to the bpf instance.
                 for probe in probes {
                     if self.common.config.fault_tolerant() {
-                        if let Err(e) = probe.try_attach_to_bpf(&mut bpf) {
+                        if let Err(e) = self.common.attach_probe(Self::NAME, &probe, &mut bpf) {
                             warn!("skipping {} with error: {}", probe.name, e);
                         }
                     } else {
-                        probe.try_attach_to_bpf(&mut bpf)?;
+                        self.common.attach_probe(Self::NAME, &probe, &mut bpf)?;
                     }
                 }
 
@@ -167,20 +165,20 @@ impl PageCache {
 
             // to make things simple for wraparound behavior, clear each BPF
             // counter after reading it.
-            if let Ok(mut table) = (*bpf).inner.table("page_accessed") {
+            if let Ok(table) = (*bpf).inner.table("page_accessed") {
                 page_accessed = crate::common::bpf::parse_u64(table.iter().next().unwrap().value);
                 let _ = table.set(&mut [0, 0, 0, 0], &mut [0, 0, 0, 0, 0, 0, 0, 0]);
             }
-            if let Ok(mut table) = (*bpf).inner.table("buffer_dirty") {
+            if let Ok(table) = (*bpf).inner.table("buffer_dirty") {
                 buffer_dirty = crate::common::bpf::parse_u64(table.iter().next().unwrap().value);
                 let _ = table.set(&mut [0, 0, 0, 0], &mut [0, 0, 0, 0, 0, 0, 0, 0]);
             }
-            if let Ok(mut table) = (*bpf).inner.table("add_to_page_cache_lru") {
+            if let Ok(table) = (*bpf).inner.table("add_to_page_cache_lru") {
                 add_to_page_cache_lru =
                     crate::common::bpf::parse_u64(table.iter().next().unwrap().value);
                 let _ = table.set(&mut [0, 0, 0, 0], &mut [0, 0, 0, 0, 0, 0, 0, 0]);
             }
-            if let Ok(mut table) = (*bpf).inner.table("page_dirtied") {
+            if let Ok(table) = (*bpf).inner.table("page_dirtied") {
                 page_dirtied = crate::common::bpf::parse_u64(table.iter().next().unwrap().value);
                 let _ = table.set(&mut [0, 0, 0, 0], &mut [0, 0, 0, 0, 0, 0, 0, 0]);
             }
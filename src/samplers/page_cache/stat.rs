@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[cfg(feature = "bpf")]
 use crate::common::bpf::*;
@@ -15,6 +16,7 @@ use crate::common::bpf::*;
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -24,9 +26,15 @@ use crate::common::bpf::*;
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum PageCacheStatistic {
-    #[strum(serialize = "page_cache/hit")]
+    #[strum(
+        serialize = "page_cache/hit",
+        message = "number of times a read request was served from the page cache"
+    )]
     Hit,
-    #[strum(serialize = "page_cache/miss")]
+    #[strum(
+        serialize = "page_cache/miss",
+        message = "number of times a read request resulted in a page cache miss"
+    )]
     Miss,
 }
 
@@ -90,4 +98,8 @@ impl Statistic for PageCacheStatistic {
     fn source(&self) -> Source {
         Source::Counter
     }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
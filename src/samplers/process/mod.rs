@@ -39,6 +39,7 @@ pub struct Process {
 #[async_trait]
 impl Sampler for Process {
     type Statistic = ProcessStatistic;
+    const NAME: &'static str = "process";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let statistics = common.config().samplers().process().statistics();
@@ -59,12 +60,9 @@ impl Sampler for Process {
 
     fn spawn(common: Common) {
         if common.config().samplers().process().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize process sampler");
             } else {
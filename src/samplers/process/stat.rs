@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[derive(
     Clone,
@@ -12,6 +13,7 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -21,13 +23,25 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum ProcessStatistic {
-    #[strum(serialize = "process/cpu/user")]
+    #[strum(
+        serialize = "process/cpu/user",
+        message = "nanoseconds spent in user mode running the monitored process"
+    )]
     CpuUser,
-    #[strum(serialize = "process/cpu/system")]
+    #[strum(
+        serialize = "process/cpu/system",
+        message = "nanoseconds spent in system mode running the monitored process"
+    )]
     CpuSystem,
-    #[strum(serialize = "process/memory/virtual")]
+    #[strum(
+        serialize = "process/memory/virtual",
+        message = "total virtual memory allocated to the monitored process"
+    )]
     MemoryVirtual,
-    #[strum(serialize = "process/memory/resident")]
+    #[strum(
+        serialize = "process/memory/resident",
+        message = "amount of memory actually used by the monitored process"
+    )]
     MemoryResident,
 }
 
@@ -42,4 +56,15 @@ impl Statistic for ProcessStatistic {
             _ => Source::Counter,
         }
     }
+
+    fn unit(&self) -> Option<&str> {
+        match self {
+            Self::CpuUser | Self::CpuSystem => Some("nanoseconds"),
+            Self::MemoryVirtual | Self::MemoryResident => Some("bytes"),
+        }
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
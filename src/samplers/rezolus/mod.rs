@@ -37,6 +37,7 @@ pub struct Rezolus {
 #[async_trait]
 impl Sampler for Rezolus {
     type Statistic = RezolusStatistic;
+    const NAME: &'static str = "rezolus";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let statistics = common.config().samplers().rezolus().statistics();
@@ -55,12 +56,9 @@ impl Sampler for Rezolus {
 
     fn spawn(common: Common) {
         if common.config().samplers().rezolus().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize rezolus sampler");
             } else {
@@ -97,11 +95,23 @@ impl Sampler for Rezolus {
         let r = self.sample_cpu().await;
         self.map_result(r)?;
 
+        self.sample_counter_resets();
+
         Ok(())
     }
 }
 
 impl Rezolus {
+    fn sample_counter_resets(&self) {
+        let statistic = RezolusStatistic::CounterResets;
+        if self.statistics.contains(&statistic) {
+            let value = self.metrics().counter_resets();
+            let _ = self
+                .metrics()
+                .record_counter(&statistic, Instant::now(), value);
+        }
+    }
+
     async fn sample_cpu(&mut self) -> Result<(), std::io::Error> {
         if self.proc_stat.is_none() {
             let pid: u32 = std::process::id();
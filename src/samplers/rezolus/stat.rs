@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[derive(
     Clone,
@@ -12,6 +13,7 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -21,14 +23,41 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum RezolusStatistic {
-    #[strum(serialize = "rezolus/cpu/user")]
+    #[strum(
+        serialize = "rezolus/cpu/user",
+        message = "nanoseconds spent in user mode running Rezolus"
+    )]
     CpuUser,
-    #[strum(serialize = "rezolus/cpu/system")]
+    #[strum(
+        serialize = "rezolus/cpu/system",
+        message = "nanoseconds spent in system mode running Rezolus"
+    )]
     CpuSystem,
-    #[strum(serialize = "rezolus/memory/virtual")]
+    #[strum(
+        serialize = "rezolus/memory/virtual",
+        message = "total virtual memory allocated to Rezolus"
+    )]
     MemoryVirtual,
-    #[strum(serialize = "rezolus/memory/resident")]
+    #[strum(
+        serialize = "rezolus/memory/resident",
+        message = "amount of memory actually used by Rezolus"
+    )]
     MemoryResident,
+    #[strum(
+        serialize = "rezolus/counter_resets",
+        message = "number of times a sampled counter decreased and was treated as a reset"
+    )]
+    CounterResets,
+    #[strum(
+        serialize = "rezolus/exposition/kafka/errors",
+        message = "number of failed attempts to connect or send to kafka"
+    )]
+    KafkaErrors,
+    #[strum(
+        serialize = "rezolus/exposition/kafka/dropped",
+        message = "number of kafka messages dropped because the buffer was full"
+    )]
+    KafkaDropped,
 }
 
 impl Statistic for RezolusStatistic {
@@ -42,4 +71,16 @@ impl Statistic for RezolusStatistic {
             _ => Source::Counter,
         }
     }
+
+    fn unit(&self) -> Option<&str> {
+        match self {
+            Self::CpuUser | Self::CpuSystem => Some("nanoseconds"),
+            Self::MemoryVirtual | Self::MemoryResident => Some("bytes"),
+            _ => None,
+        }
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
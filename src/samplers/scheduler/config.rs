@@ -5,7 +5,7 @@
 use serde_derive::Deserialize;
 use strum::IntoEnumIterator;
 
-use crate::config::SamplerConfig;
+use crate::config::{SamplerConfig, SummaryOutput};
 
 use super::stat::*;
 
@@ -21,6 +21,8 @@ pub struct SchedulerConfig {
     #[serde(default = "crate::common::default_percentiles")]
     percentiles: Vec<f64>,
     #[serde(default)]
+    summaries: Vec<SummaryOutput>,
+    #[serde(default)]
     perf_events: bool,
     #[serde(default = "default_statistics")]
     statistics: Vec<SchedulerStatistic>,
@@ -33,6 +35,7 @@ impl Default for SchedulerConfig {
             enabled: Default::default(),
             interval: Default::default(),
             percentiles: crate::common::default_percentiles(),
+            summaries: Default::default(),
             perf_events: Default::default(),
             statistics: default_statistics(),
         }
@@ -62,6 +65,10 @@ impl SamplerConfig for SchedulerConfig {
         &self.percentiles
     }
 
+    fn summaries(&self) -> &[SummaryOutput] {
+        &self.summaries
+    }
+
     fn perf_events(&self) -> bool {
         self.perf_events
     }
@@ -41,6 +41,7 @@ pub struct Scheduler {
 #[async_trait]
 impl Sampler for Scheduler {
     type Statistic = SchedulerStatistic;
+    const NAME: &'static str = "scheduler";
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let fault_tolerant = common.config.general().fault_tolerant();
         let statistics = common.config().samplers().scheduler().statistics();
@@ -90,12 +91,9 @@ impl Sampler for Scheduler {
 
     fn spawn(common: Common) {
         if common.config().samplers().scheduler().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize scheduler sampler");
             } else {
@@ -161,7 +159,7 @@ impl Scheduler {
         );
 
         let mut perf_array_attached = false;
-        if let Ok(mut bpf) = bcc::BPF::new(&code) {
+        if let Ok(bpf) = bcc::BPF::new(&code) {
             for statistic in &self.statistics {
                 if let Some(table) = statistic.perf_table() {
                     if let Some(event) = statistic.event() {
@@ -263,7 +261,7 @@ impl Scheduler {
                     let bpf = bpf.lock().unwrap();
                     let time = Instant::now();
                     for statistic in self.statistics.iter().filter(|s| s.bpf_table().is_some()) {
-                        if let Ok(mut table) = (*bpf).inner.table(statistic.bpf_table().unwrap()) {
+                        if let Ok(table) = (*bpf).inner.table(statistic.bpf_table().unwrap()) {
                             for (&value, &count) in &map_from_table(&mut table) {
                                 if count > 0 {
                                     let _ = self.metrics().record_bucket(
@@ -355,11 +353,11 @@ impl Scheduler {
                 // load + attach the kernel probes that are required to the bpf instance.
                 for probe in probes {
                     if self.common.config.fault_tolerant() {
-                        if let Err(e) = probe.try_attach_to_bpf(&mut bpf) {
+                        if let Err(e) = self.common.attach_probe(Self::NAME, &probe, &mut bpf) {
                             warn!("skipping {} with error: {}", probe.name, e);
                         }
                     } else {
-                        probe.try_attach_to_bpf(&mut bpf)?;
+                        self.common.attach_probe(Self::NAME, &probe, &mut bpf)?;
                     }
                 }
 
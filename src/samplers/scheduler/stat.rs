@@ -7,7 +7,8 @@ use crate::metrics::*;
 #[cfg(feature = "bpf")]
 use bcc::perf_event::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[cfg(feature = "bpf")]
 use crate::common::bpf::*;
@@ -18,6 +19,7 @@ use crate::common::bpf::*;
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -27,17 +29,35 @@ use crate::common::bpf::*;
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum SchedulerStatistic {
-    #[strum(serialize = "scheduler/cpu_migrations")]
+    #[strum(
+        serialize = "scheduler/cpu_migrations",
+        message = "number of times processes have been migrated across CPUs"
+    )]
     CpuMigrations,
-    #[strum(serialize = "scheduler/runqueue/latency")]
+    #[strum(
+        serialize = "scheduler/runqueue/latency",
+        message = "distribution of time that runnable tasks were waiting on the runqueue"
+    )]
     RunqueueLatency,
-    #[strum(serialize = "scheduler/context_switches")]
+    #[strum(
+        serialize = "scheduler/context_switches",
+        message = "number of context switches"
+    )]
     ContextSwitches,
-    #[strum(serialize = "scheduler/processes/created")]
+    #[strum(
+        serialize = "scheduler/processes/created",
+        message = "number of processes created"
+    )]
     ProcessesCreated,
-    #[strum(serialize = "scheduler/processes/running")]
+    #[strum(
+        serialize = "scheduler/processes/running",
+        message = "number of processes currently running"
+    )]
     ProcessesRunning,
-    #[strum(serialize = "scheduler/processes/blocked")]
+    #[strum(
+        serialize = "scheduler/processes/blocked",
+        message = "number of processes currently blocked"
+    )]
     ProcessesBlocked,
 }
 
@@ -127,4 +147,15 @@ impl Statistic for SchedulerStatistic {
             _ => Source::Counter,
         }
     }
+
+    fn unit(&self) -> Option<&str> {
+        match self {
+            Self::RunqueueLatency => Some("nanoseconds"),
+            _ => None,
+        }
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
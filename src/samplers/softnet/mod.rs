@@ -29,6 +29,7 @@ pub struct Softnet {
 #[async_trait]
 impl Sampler for Softnet {
     type Statistic = SoftnetStatistic;
+    const NAME: &'static str = "softnet";
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let statistics = common.config().samplers().softnet().statistics();
         let sampler = Self {
@@ -44,12 +45,9 @@ impl Sampler for Softnet {
 
     fn spawn(common: Common) {
         if common.config().samplers().softnet().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize softnet sampler");
             } else {
@@ -6,7 +6,8 @@ use crate::*;
 
 use num_derive::FromPrimitive;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[derive(
     Clone,
@@ -14,6 +15,7 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     FromPrimitive,
@@ -24,17 +26,32 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum SoftnetStatistic {
-    #[strum(serialize = "softnet/processed")]
+    #[strum(
+        serialize = "softnet/processed",
+        message = "number of packets processed in the softnet layer"
+    )]
     Processed = 0,
-    #[strum(serialize = "softnet/dropped")]
+    #[strum(serialize = "softnet/dropped", message = "number of packets dropped")]
     Dropped = 1,
-    #[strum(serialize = "softnet/time_squeezed")]
+    #[strum(
+        serialize = "softnet/time_squeezed",
+        message = "number of times packet processing did not complete within the budget"
+    )]
     TimeSqueezed = 2,
-    #[strum(serialize = "softnet/cpu_collision")]
+    #[strum(
+        serialize = "softnet/cpu_collision",
+        message = "collisions occurring obtaining device lock while transmitting"
+    )]
     CpuCollision = 3,
-    #[strum(serialize = "softnet/received_rps")]
+    #[strum(
+        serialize = "softnet/received_rps",
+        message = "number of times cpus woken up for received rps"
+    )]
     ReceivedRps = 4,
-    #[strum(serialize = "softnet/flow_limit_count")]
+    #[strum(
+        serialize = "softnet/flow_limit_count",
+        message = "number of times the flow limit count was reached"
+    )]
     FlowLimitCount = 5,
 }
 
@@ -46,4 +63,8 @@ impl Statistic for SoftnetStatistic {
     fn source(&self) -> Source {
         Source::Counter
     }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
@@ -34,6 +34,7 @@ pub struct Tcp {
 #[async_trait]
 impl Sampler for Tcp {
     type Statistic = TcpStatistic;
+    const NAME: &'static str = "tcp";
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let fault_tolerant = common.config.general().fault_tolerant();
         let statistics = common.config().samplers().tcp().statistics();
@@ -64,12 +65,9 @@ impl Sampler for Tcp {
 
     fn spawn(common: Common) {
         if common.config().samplers().tcp().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize tcp sampler");
             } else {
@@ -153,11 +151,11 @@ impl Tcp {
                 // load + attach the kernel probes that are required to the bpf instance.
                 for probe in probes {
                     if self.common.config.fault_tolerant() {
-                        if let Err(e) = probe.try_attach_to_bpf(&mut bpf) {
+                        if let Err(e) = self.common.attach_probe(Self::NAME, &probe, &mut bpf) {
                             warn!("skipping {} with error: {}", probe.name, e);
                         }
                     } else {
-                        probe.try_attach_to_bpf(&mut bpf)?;
+                        self.common.attach_probe(Self::NAME, &probe, &mut bpf)?;
                     }
                 }
 
@@ -232,9 +230,7 @@ impl Tcp {
                         }
                         // if it's distribution
                         Source::Distribution => {
-                            if let Ok(mut table) =
-                                (*bpf).inner.table(statistic.bpf_table().unwrap())
-                            {
+                            if let Ok(table) = (*bpf).inner.table(statistic.bpf_table().unwrap()) {
                                 for (&value, &count) in &map_from_table(&mut table) {
                                     if count > 0 {
                                         let _ = self.metrics().record_bucket(
@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[cfg(feature = "bpf")]
 use crate::common::bpf::*;
@@ -15,6 +16,7 @@ use crate::common::bpf::*;
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -24,69 +26,159 @@ use crate::common::bpf::*;
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum TcpStatistic {
-    #[strum(serialize = "tcp/connect/latency")]
+    #[strum(
+        serialize = "tcp/connect/latency",
+        message = "end-to-end latency from an active outbound connect() until the socket is established"
+    )]
     ConnectLatency,
-    #[strum(serialize = "tcp/receive/segment")]
+    #[strum(
+        serialize = "tcp/receive/segment",
+        message = "total number of segments received"
+    )]
     ReceiveSegments,
-    #[strum(serialize = "tcp/transmit/segment")]
+    #[strum(
+        serialize = "tcp/transmit/segment",
+        message = "number of segments transmitted"
+    )]
     TransmitSegments,
-    #[strum(serialize = "tcp/receive/prune_called")]
+    #[strum(
+        serialize = "tcp/receive/prune_called",
+        message = "number of packets pruned from the receive queue because of socket buffer overrun"
+    )]
     ReceivePruneCalled,
-    #[strum(serialize = "tcp/receive/collapsed")]
+    #[strum(
+        serialize = "tcp/receive/collapsed",
+        message = "segments collapsed in the receive queue"
+    )]
     ReceiveCollapsed,
-    #[strum(serialize = "tcp/transmit/retransmit")]
+    #[strum(
+        serialize = "tcp/transmit/retransmit",
+        message = "number of segments retransmitted"
+    )]
     Retransmits,
-    #[strum(serialize = "tcp/receive/checksum_error")]
+    #[strum(
+        serialize = "tcp/receive/checksum_error",
+        message = "segments received with invalid checksum"
+    )]
     ReceiveChecksumErrors,
-    #[strum(serialize = "tcp/transmit/reset")]
+    #[strum(serialize = "tcp/transmit/reset", message = "number of RSTs sent")]
     TransmitResets,
-    #[strum(serialize = "tcp/receive/error")]
+    #[strum(
+        serialize = "tcp/receive/error",
+        message = "total number of errors on receive"
+    )]
     ReceiveErrors,
-    #[strum(serialize = "tcp/syncookies/sent")]
+    #[strum(
+        serialize = "tcp/syncookies/sent",
+        message = "number of SYN cookies sent"
+    )]
     SyncookiesSent,
-    #[strum(serialize = "tcp/syncookies/received")]
+    #[strum(
+        serialize = "tcp/syncookies/received",
+        message = "number of SYN cookies received"
+    )]
     SyncookiesRecieved,
-    #[strum(serialize = "tcp/syncookies/failed")]
+    #[strum(
+        serialize = "tcp/syncookies/failed",
+        message = "number of invalid SYN cookies received"
+    )]
     SyncookiesFailed,
-    #[strum(serialize = "tcp/receive/pruned")]
+    #[strum(
+        serialize = "tcp/receive/pruned",
+        message = "packets pruned from the receive queue"
+    )]
     ReceivePruned,
-    #[strum(serialize = "tcp/receive/ofo_pruned")]
+    #[strum(
+        serialize = "tcp/receive/ofo_pruned",
+        message = "number of packets pruned from the out-of-order queue due to socket buffer overrun"
+    )]
     ReceiveOfoPruned,
-    #[strum(serialize = "tcp/transmit/delayed_ack")]
+    #[strum(
+        serialize = "tcp/transmit/delayed_ack",
+        message = "number of delayed ACKs sent"
+    )]
     TransmitDelayedAcks,
-    #[strum(serialize = "tcp/receive/listen_overflows")]
+    #[strum(
+        serialize = "tcp/receive/listen_overflows",
+        message = "times the listen queue of a socket overflowed"
+    )]
     ReceiveListenOverflows,
-    #[strum(serialize = "tcp/receive/listen_drops")]
+    #[strum(
+        serialize = "tcp/receive/listen_drops",
+        message = "number of SYNs to LISTEN sockets ignored"
+    )]
     ReceiveListenDrops,
-    #[strum(serialize = "tcp/abort/failed")]
+    #[strum(
+        serialize = "tcp/abort/failed",
+        message = "failed to send RST on abort due to memory pressure"
+    )]
     AbortFailed,
-    #[strum(serialize = "tcp/abort/on_close")]
+    #[strum(
+        serialize = "tcp/abort/on_close",
+        message = "connections reset due to early user close"
+    )]
     AbortOnClose,
-    #[strum(serialize = "tcp/abort/on_data")]
+    #[strum(
+        serialize = "tcp/abort/on_data",
+        message = "connections reset due to unexpected data"
+    )]
     AbortOnData,
-    #[strum(serialize = "tcp/abort/on_linger")]
+    #[strum(
+        serialize = "tcp/abort/on_linger",
+        message = "connections reset after user close while in linger timeout"
+    )]
     AbortOnLinger,
-    #[strum(serialize = "tcp/abort/on_memory")]
+    #[strum(
+        serialize = "tcp/abort/on_memory",
+        message = "connections reset due to memory pressure or too many orphaned sockets"
+    )]
     AbortOnMemory,
-    #[strum(serialize = "tcp/abort/on_timeout")]
+    #[strum(
+        serialize = "tcp/abort/on_timeout",
+        message = "connections reset due to timeout"
+    )]
     AbortOnTimeout,
-    #[strum(serialize = "tcp/srtt")]
+    #[strum(
+        serialize = "tcp/srtt",
+        message = "distribution of the smoothed round trip time"
+    )]
     SmoothedRoundTripTime,
-    #[strum(serialize = "tcp/jitter")]
+    #[strum(
+        serialize = "tcp/jitter",
+        message = "distribution of the median deviation of the smoothed round trip time"
+    )]
     Jitter,
-    #[strum(serialize = "tcp/connection/accepted")]
+    #[strum(
+        serialize = "tcp/connection/accepted",
+        message = "number of connections accepted passively"
+    )]
     ConnectionAccepted,
-    #[strum(serialize = "tcp/connection/initiated")]
+    #[strum(
+        serialize = "tcp/connection/initiated",
+        message = "number of connections initiated actively"
+    )]
     ConnectionInitiated,
-    #[strum(serialize = "tcp/drop")]
+    #[strum(
+        serialize = "tcp/drop",
+        message = "number of packets dropped in the kernel TCP stack"
+    )]
     Drop,
-    #[strum(serialize = "tcp/tlp")]
+    #[strum(serialize = "tcp/tlp", message = "number of tail loss probes sent")]
     TailLossProbe,
-    #[strum(serialize = "tcp/transmit/retransmit_timeout")]
+    #[strum(
+        serialize = "tcp/transmit/retransmit_timeout",
+        message = "number of retransmit timeouts"
+    )]
     RetransmitTimeout,
-    #[strum(serialize = "tcp/receive/duplicate")]
+    #[strum(
+        serialize = "tcp/receive/duplicate",
+        message = "number of duplicate segments received"
+    )]
     Duplicate,
-    #[strum(serialize = "tcp/receive/out_of_order")]
+    #[strum(
+        serialize = "tcp/receive/out_of_order",
+        message = "number of out of order segments received"
+    )]
     OutOfOrder,
 }
 
@@ -281,4 +373,17 @@ impl Statistic for TcpStatistic {
             _ => Source::Counter,
         }
     }
+
+    fn unit(&self) -> Option<&str> {
+        match self {
+            Self::ConnectLatency | Self::SmoothedRoundTripTime | Self::Jitter => {
+                Some("nanoseconds")
+            }
+            _ => None,
+        }
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
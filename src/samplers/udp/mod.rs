@@ -26,6 +26,7 @@ pub struct Udp {
 #[async_trait]
 impl Sampler for Udp {
     type Statistic = UdpStatistic;
+    const NAME: &'static str = "udp";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let statistics = common.config().samplers().udp().statistics();
@@ -44,12 +45,9 @@ impl Sampler for Udp {
 
     fn spawn(common: Common) {
         if common.config().samplers().udp().enabled() {
-            if let Ok(mut sampler) = Self::new(common.clone()) {
-                common.runtime().spawn(async move {
-                    loop {
-                        let _ = sampler.sample().await;
-                    }
-                });
+            common.status().register(Self::NAME);
+            if let Ok(sampler) = Self::new(common.clone()) {
+                common.runtime().spawn(sampler.run());
             } else if !common.config.fault_tolerant() {
                 fatal!("failed to initialize udp sampler");
             } else {
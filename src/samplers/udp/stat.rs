@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[derive(
     Clone,
@@ -12,6 +13,7 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -21,11 +23,20 @@ use strum_macros::{EnumIter, EnumString, IntoStaticStr};
 )]
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 pub enum UdpStatistic {
-    #[strum(serialize = "udp/receive/datagrams")]
+    #[strum(
+        serialize = "udp/receive/datagrams",
+        message = "number of datagrams received"
+    )]
     InDatagrams,
-    #[strum(serialize = "udp/receive/errors")]
+    #[strum(
+        serialize = "udp/receive/errors",
+        message = "number of errors on receive"
+    )]
     InErrors,
-    #[strum(serialize = "udp/transmit/datagrams")]
+    #[strum(
+        serialize = "udp/transmit/datagrams",
+        message = "number of datagrams transmitted"
+    )]
     OutDatagrams,
 }
 
@@ -47,4 +58,8 @@ impl Statistic for UdpStatistic {
     fn source(&self) -> Source {
         Source::Counter
     }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }
@@ -5,7 +5,7 @@
 use serde_derive::Deserialize;
 use std::collections::BTreeMap;
 
-use crate::config::SamplerConfig;
+use crate::config::{SamplerConfig, SummaryOutput};
 
 use super::stat::UsercallStatistic;
 
@@ -30,6 +30,8 @@ pub struct UsercallConfig {
     #[serde(default)]
     percentiles: Vec<f64>,
     #[serde(default)]
+    summaries: Vec<SummaryOutput>,
+    #[serde(default)]
     libraries: Vec<LibraryProbeConfig>,
 }
 
@@ -93,6 +95,10 @@ impl SamplerConfig for UsercallConfig {
         &self.percentiles
     }
 
+    fn summaries(&self) -> &[SummaryOutput] {
+        &self.summaries
+    }
+
     fn statistics(&self) -> Vec<<Self as SamplerConfig>::Statistic> {
         let mut stats = Vec::new();
         for lib_conf in self.libraries().iter() {
@@ -210,7 +210,11 @@ impl Usercall {
                     } else {
                         Err(err)?
                     }
-                };
+                } else {
+                    self.common
+                        .status()
+                        .attached(Self::NAME, &format!("{}:{}", lib, func));
+                }
             }
 
             self.bpf = Some(Arc::new(Mutex::new(BPF { inner: bpf })));
@@ -223,6 +227,7 @@ impl Usercall {
 #[async_trait]
 impl Sampler for Usercall {
     type Statistic = UsercallStatistic;
+    const NAME: &'static str = "usercall";
 
     fn new(common: Common) -> Result<Self, anyhow::Error> {
         let statistics = common.config().samplers().usercall().statistics();
@@ -244,13 +249,10 @@ impl Sampler for Usercall {
 
     fn spawn(common: Common) {
         if common.config().samplers().usercall().enabled() {
+            common.status().register(Self::NAME);
             match Self::new(common.clone()) {
-                Ok(mut sampler) => {
-                    common.runtime().spawn(async move {
-                        loop {
-                            let _ = sampler.sample().await;
-                        }
-                    });
+                Ok(sampler) => {
+                    common.runtime().spawn(sampler.run());
                 }
                 Err(e) => {
                     if !common.config.fault_tolerant() {
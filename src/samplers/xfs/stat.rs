@@ -4,7 +4,8 @@
 
 use crate::metrics::*;
 use serde_derive::{Deserialize, Serialize};
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+use strum::EnumMessage;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[cfg(feature = "bpf")]
 use crate::common::bpf::*;
@@ -15,6 +16,7 @@ use crate::common::bpf::*;
     Debug,
     Deserialize,
     EnumIter,
+    EnumMessage,
     EnumString,
     Eq,
     IntoStaticStr,
@@ -25,13 +27,25 @@ use crate::common::bpf::*;
 #[serde(deny_unknown_fields, try_from = "&str", into = "&str")]
 #[allow(clippy::enum_variant_names)]
 pub enum XfsStatistic {
-    #[strum(serialize = "xfs/read/latency")]
+    #[strum(
+        serialize = "xfs/read/latency",
+        message = "latency distribution for read() on xfs filesystems"
+    )]
     ReadLatency,
-    #[strum(serialize = "xfs/write/latency")]
+    #[strum(
+        serialize = "xfs/write/latency",
+        message = "latency distribution for write() on xfs filesystems"
+    )]
     WriteLatency,
-    #[strum(serialize = "xfs/open/latency")]
+    #[strum(
+        serialize = "xfs/open/latency",
+        message = "latency distribution for open() on xfs filesystems"
+    )]
     OpenLatency,
-    #[strum(serialize = "xfs/fsync/latency")]
+    #[strum(
+        serialize = "xfs/fsync/latency",
+        message = "latency distribution for fsync() on xfs filesystems"
+    )]
     FsyncLatency,
 }
 
@@ -132,4 +146,12 @@ impl Statistic for XfsStatistic {
     fn source(&self) -> Source {
         Source::Distribution
     }
+
+    fn unit(&self) -> Option<&str> {
+        Some("nanoseconds")
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.get_message()
+    }
 }